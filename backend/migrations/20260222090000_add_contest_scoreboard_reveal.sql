@@ -0,0 +1,6 @@
+BEGIN;
+
+ALTER TABLE contests
+  ADD COLUMN IF NOT EXISTS scoreboard_revealed_at TIMESTAMPTZ;
+
+COMMIT;
@@ -0,0 +1,53 @@
+BEGIN;
+
+ALTER TABLE submissions
+  ADD COLUMN IF NOT EXISTS blood_bonus INTEGER NOT NULL DEFAULT 0
+    CHECK (blood_bonus >= 0);
+
+-- Split the blood bonus that was previously baked into score_awarded so that
+-- challenge value can be recomputed independently of it.
+WITH first_solves AS (
+  SELECT DISTINCT ON (s.contest_id, s.challenge_id, s.team_id)
+         s.id,
+         s.contest_id,
+         s.challenge_id,
+         s.score_awarded,
+         s.submitted_at
+  FROM submissions s
+  WHERE s.verdict = 'accepted'
+    AND s.score_awarded > 0
+  ORDER BY s.contest_id, s.challenge_id, s.team_id, s.submitted_at ASC, s.id ASC
+),
+ranked AS (
+  SELECT fs.id,
+         fs.score_awarded,
+         ROW_NUMBER() OVER (
+           PARTITION BY fs.contest_id, fs.challenge_id
+           ORDER BY fs.submitted_at ASC, fs.id ASC
+         ) AS position,
+         ct.first_blood_bonus_percent,
+         ct.second_blood_bonus_percent,
+         ct.third_blood_bonus_percent
+  FROM first_solves fs
+  JOIN contests ct ON ct.id = fs.contest_id
+)
+UPDATE submissions s
+SET blood_bonus = GREATEST(
+  r.score_awarded - ROUND(
+    r.score_awarded / (
+      1 + (
+        CASE r.position
+          WHEN 1 THEN r.first_blood_bonus_percent
+          WHEN 2 THEN r.second_blood_bonus_percent
+          ELSE r.third_blood_bonus_percent
+        END
+      ) / 100.0
+    )
+  )::INTEGER,
+  0
+)
+FROM ranked r
+WHERE s.id = r.id
+  AND r.position <= 3;
+
+COMMIT;
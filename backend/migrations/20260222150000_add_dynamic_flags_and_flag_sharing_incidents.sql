@@ -0,0 +1,45 @@
+BEGIN;
+
+CREATE TABLE dynamic_flags (
+  id BIGSERIAL PRIMARY KEY,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  challenge_id UUID NOT NULL REFERENCES challenges(id) ON DELETE CASCADE,
+  team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  flag_value TEXT NOT NULL,
+  issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE (contest_id, challenge_id, team_id)
+);
+
+CREATE INDEX idx_dynamic_flags_value
+  ON dynamic_flags (contest_id, challenge_id, flag_value);
+
+CREATE TABLE flag_sharing_incidents (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  challenge_id UUID NOT NULL REFERENCES challenges(id) ON DELETE CASCADE,
+  submitter_team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  submitter_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+  owner_team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  first_submission_id BIGINT REFERENCES submissions(id) ON DELETE SET NULL,
+  flag_issued_at TIMESTAMPTZ NOT NULL,
+  first_submitted_at TIMESTAMPTZ NOT NULL,
+  last_submitted_at TIMESTAMPTZ NOT NULL,
+  occurrence_count INTEGER NOT NULL DEFAULT 1 CHECK (occurrence_count >= 1),
+  status VARCHAR(16) NOT NULL DEFAULT 'open'
+    CHECK (status IN ('open', 'confirmed', 'dismissed')),
+  review_note TEXT NOT NULL DEFAULT '',
+  reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+  reviewed_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE (contest_id, challenge_id, submitter_team_id, owner_team_id)
+);
+
+CREATE INDEX idx_flag_sharing_incidents_contest_status
+  ON flag_sharing_incidents (contest_id, status, last_submitted_at DESC);
+
+CREATE TRIGGER trg_flag_sharing_incidents_touch_updated_at
+BEFORE UPDATE ON flag_sharing_incidents
+FOR EACH ROW EXECUTE FUNCTION touch_updated_at();
+
+COMMIT;
@@ -0,0 +1,7 @@
+BEGIN;
+
+ALTER TABLE challenges
+  ADD COLUMN flag_rules JSONB NOT NULL DEFAULT '[]'::jsonb
+    CHECK (jsonb_typeof(flag_rules) = 'array');
+
+COMMIT;
@@ -0,0 +1,14 @@
+BEGIN;
+
+ALTER TABLE challenges
+  ADD COLUMN stages JSONB NOT NULL DEFAULT '[]'::jsonb
+    CHECK (jsonb_typeof(stages) = 'array');
+
+ALTER TABLE submissions
+  ADD COLUMN stage_key VARCHAR(32);
+
+CREATE INDEX idx_submissions_stage_progress
+  ON submissions (contest_id, challenge_id, team_id, stage_key)
+  WHERE verdict = 'accepted' AND stage_key IS NOT NULL;
+
+COMMIT;
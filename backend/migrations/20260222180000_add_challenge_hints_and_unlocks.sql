@@ -0,0 +1,48 @@
+BEGIN;
+
+CREATE TABLE challenge_hints (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  challenge_id UUID NOT NULL REFERENCES challenges(id) ON DELETE CASCADE,
+  sort_order INTEGER NOT NULL DEFAULT 0,
+  content TEXT NOT NULL,
+  cost INTEGER NOT NULL DEFAULT 0 CHECK (cost >= 0),
+  release_at TIMESTAMPTZ,
+  created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_challenge_hints_challenge_order
+  ON challenge_hints (challenge_id, sort_order, created_at);
+
+CREATE TRIGGER trg_challenge_hints_touch_updated_at
+BEFORE UPDATE ON challenge_hints
+FOR EACH ROW EXECUTE FUNCTION touch_updated_at();
+
+-- Existing plain-text hints become free hints, readable without unlocking.
+INSERT INTO challenge_hints (challenge_id, sort_order, content)
+SELECT c.id, (h.ordinality - 1)::int, h.hint
+FROM challenges c
+CROSS JOIN LATERAL unnest(c.hints) WITH ORDINALITY AS h(hint, ordinality)
+WHERE btrim(h.hint) <> '';
+
+ALTER TABLE challenges
+  DROP COLUMN hints;
+
+-- `cost` is captured at unlock time so later edits do not change past deductions.
+CREATE TABLE challenge_hint_unlocks (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  challenge_id UUID NOT NULL REFERENCES challenges(id) ON DELETE CASCADE,
+  hint_id UUID REFERENCES challenge_hints(id) ON DELETE SET NULL,
+  team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+  cost INTEGER NOT NULL CHECK (cost >= 0),
+  unlocked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE (contest_id, hint_id, team_id)
+);
+
+CREATE INDEX idx_challenge_hint_unlocks_contest_time
+  ON challenge_hint_unlocks (contest_id, unlocked_at);
+
+COMMIT;
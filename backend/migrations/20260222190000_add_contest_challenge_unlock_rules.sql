@@ -0,0 +1,9 @@
+BEGIN;
+
+-- Conditions a team must meet before a contest challenge unlocks, e.g.
+-- [{"type":"challenges","challenge_ids":["..."]},{"type":"category","category":"web","count":2}].
+ALTER TABLE contest_challenges
+  ADD COLUMN unlock_rules JSONB NOT NULL DEFAULT '[]'::jsonb
+    CHECK (jsonb_typeof(unlock_rules) = 'array');
+
+COMMIT;
@@ -0,0 +1,19 @@
+BEGIN;
+
+-- `difficulty` values every challenge from the contest's per-difficulty table.
+ALTER TABLE contests
+  DROP CONSTRAINT IF EXISTS contests_scoring_mode_check;
+
+ALTER TABLE contests
+  ADD CONSTRAINT contests_scoring_mode_check
+    CHECK (scoring_mode IN ('static', 'dynamic', 'difficulty'));
+
+-- Decay curve used when scoring_mode = 'dynamic'.
+ALTER TABLE contests
+  ADD COLUMN scoring_formula VARCHAR(16) NOT NULL DEFAULT 'hyperbolic'
+    CHECK (scoring_formula IN ('hyperbolic', 'logarithmic', 'linear')),
+  ADD COLUMN difficulty_scores JSONB NOT NULL
+    DEFAULT '{"easy":100,"normal":200,"hard":300,"insane":500}'::jsonb
+    CHECK (jsonb_typeof(difficulty_scores) = 'object');
+
+COMMIT;
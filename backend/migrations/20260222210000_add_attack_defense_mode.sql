@@ -0,0 +1,74 @@
+BEGIN;
+
+-- Attack-defense contests run in rounds ("ticks"). Every round each team's
+-- service gets a fresh flag placed by the challenge checker; stolen flags are
+-- submitted through /submissions and scored as attack points, unexploited
+-- flags as defense points and healthy service checks as SLA points.
+ALTER TABLE contests
+  ADD COLUMN contest_type VARCHAR(16) NOT NULL DEFAULT 'jeopardy'
+    CHECK (contest_type IN ('jeopardy', 'attack_defense')),
+  ADD COLUMN ad_round_seconds INTEGER NOT NULL DEFAULT 120
+    CHECK (ad_round_seconds >= 30 AND ad_round_seconds <= 3600),
+  ADD COLUMN ad_flag_lifetime_rounds INTEGER NOT NULL DEFAULT 5
+    CHECK (ad_flag_lifetime_rounds >= 1 AND ad_flag_lifetime_rounds <= 50),
+  ADD COLUMN ad_attack_points INTEGER NOT NULL DEFAULT 10
+    CHECK (ad_attack_points >= 0 AND ad_attack_points <= 10000),
+  ADD COLUMN ad_defense_points INTEGER NOT NULL DEFAULT 5
+    CHECK (ad_defense_points >= 0 AND ad_defense_points <= 10000),
+  ADD COLUMN ad_sla_points INTEGER NOT NULL DEFAULT 5
+    CHECK (ad_sla_points >= 0 AND ad_sla_points <= 10000);
+
+CREATE TABLE ad_rounds (
+  id BIGSERIAL PRIMARY KEY,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  round_number INTEGER NOT NULL CHECK (round_number >= 1),
+  started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ended_at TIMESTAMPTZ,
+  UNIQUE (contest_id, round_number)
+);
+
+CREATE UNIQUE INDEX idx_ad_rounds_open
+  ON ad_rounds (contest_id)
+  WHERE ended_at IS NULL;
+
+CREATE TABLE ad_flags (
+  id BIGSERIAL PRIMARY KEY,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  round_id BIGINT NOT NULL REFERENCES ad_rounds(id) ON DELETE CASCADE,
+  challenge_id UUID NOT NULL REFERENCES challenges(id) ON DELETE CASCADE,
+  team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  flag_value VARCHAR(128) NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE (round_id, challenge_id, team_id),
+  UNIQUE (contest_id, flag_value)
+);
+
+CREATE TABLE ad_service_checks (
+  id BIGSERIAL PRIMARY KEY,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  round_id BIGINT NOT NULL REFERENCES ad_rounds(id) ON DELETE CASCADE,
+  challenge_id UUID NOT NULL REFERENCES challenges(id) ON DELETE CASCADE,
+  team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  status VARCHAR(16) NOT NULL CHECK (status IN ('up', 'mumble', 'down', 'error')),
+  message TEXT NOT NULL DEFAULT '',
+  elapsed_ms INTEGER NOT NULL DEFAULT 0,
+  checked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE (round_id, challenge_id, team_id)
+);
+
+CREATE INDEX idx_ad_service_checks_contest_checked
+  ON ad_service_checks (contest_id, checked_at);
+
+CREATE TABLE ad_attacks (
+  id BIGSERIAL PRIMARY KEY,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  flag_id BIGINT NOT NULL REFERENCES ad_flags(id) ON DELETE CASCADE,
+  attacker_team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  submitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE (flag_id, attacker_team_id)
+);
+
+CREATE INDEX idx_ad_attacks_contest_submitted
+  ON ad_attacks (contest_id, submitted_at);
+
+COMMIT;
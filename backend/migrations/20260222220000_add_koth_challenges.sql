@@ -0,0 +1,42 @@
+BEGIN;
+
+-- King-of-the-hill challenges run one shared deployment per contest; teams
+-- fight over it and the team whose token the checker reads off the hill
+-- earns the challenge's static_score every tick.
+ALTER TABLE challenges
+  DROP CONSTRAINT IF EXISTS challenges_challenge_type_check;
+
+ALTER TABLE challenges
+  ADD CONSTRAINT challenges_challenge_type_check
+    CHECK (challenge_type IN ('static', 'dynamic', 'internal', 'koth'));
+
+ALTER TABLE contests
+  ADD COLUMN koth_tick_seconds INTEGER NOT NULL DEFAULT 60
+    CHECK (koth_tick_seconds >= 10 AND koth_tick_seconds <= 3600);
+
+-- Shared deployments are instances without a team.
+ALTER TABLE instances
+  ALTER COLUMN team_id DROP NOT NULL;
+
+CREATE UNIQUE INDEX idx_instances_shared
+  ON instances (contest_id, challenge_id)
+  WHERE team_id IS NULL;
+
+CREATE TABLE koth_ticks (
+  id BIGSERIAL PRIMARY KEY,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  challenge_id UUID NOT NULL REFERENCES challenges(id) ON DELETE CASCADE,
+  tick_number INTEGER NOT NULL CHECK (tick_number >= 0),
+  owner_team_id UUID REFERENCES teams(id) ON DELETE SET NULL,
+  status VARCHAR(16) NOT NULL CHECK (status IN ('up', 'down', 'error')),
+  points INTEGER NOT NULL DEFAULT 0 CHECK (points >= 0),
+  message TEXT NOT NULL DEFAULT '',
+  elapsed_ms INTEGER NOT NULL DEFAULT 0,
+  ticked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE (contest_id, challenge_id, tick_number)
+);
+
+CREATE INDEX idx_koth_ticks_contest_ticked
+  ON koth_ticks (contest_id, ticked_at);
+
+COMMIT;
@@ -0,0 +1,21 @@
+BEGIN;
+
+-- Admin-granted awards (positive points) and penalties (negative points)
+-- that count towards a team's contest score next to its solves.
+CREATE TABLE contest_score_adjustments (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  points INTEGER NOT NULL CHECK (points <> 0 AND points >= -100000 AND points <= 100000),
+  reason TEXT NOT NULL,
+  created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_contest_score_adjustments_contest_created
+  ON contest_score_adjustments (contest_id, created_at);
+
+CREATE INDEX idx_contest_score_adjustments_team
+  ON contest_score_adjustments (team_id, contest_id);
+
+COMMIT;
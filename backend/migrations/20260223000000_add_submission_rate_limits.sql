@@ -0,0 +1,26 @@
+BEGIN;
+
+-- Submission limits used to be compile-time constants (10 per user per 30s).
+-- Contests now configure a per-user and a per-team budget per window, plus an
+-- exponential cooldown once a team keeps submitting wrong flags for the same
+-- challenge (disabled while wrong_backoff_threshold is 0).
+ALTER TABLE contests
+  ADD COLUMN submission_rate_window_seconds INTEGER NOT NULL DEFAULT 30
+    CHECK (submission_rate_window_seconds >= 5 AND submission_rate_window_seconds <= 3600),
+  ADD COLUMN submission_rate_user_max INTEGER NOT NULL DEFAULT 10
+    CHECK (submission_rate_user_max >= 1 AND submission_rate_user_max <= 1000),
+  ADD COLUMN submission_rate_team_max INTEGER NOT NULL DEFAULT 30
+    CHECK (submission_rate_team_max >= 1 AND submission_rate_team_max <= 5000),
+  ADD COLUMN wrong_backoff_threshold INTEGER NOT NULL DEFAULT 0
+    CHECK (wrong_backoff_threshold >= 0 AND wrong_backoff_threshold <= 100),
+  ADD COLUMN wrong_backoff_base_seconds INTEGER NOT NULL DEFAULT 5
+    CHECK (wrong_backoff_base_seconds >= 1 AND wrong_backoff_base_seconds <= 3600),
+  ADD COLUMN wrong_backoff_max_seconds INTEGER NOT NULL DEFAULT 300
+    CHECK (wrong_backoff_max_seconds >= 1 AND wrong_backoff_max_seconds <= 86400);
+
+-- Per-challenge limits on top of the contest-wide ones, e.g. for
+-- brute-force-prone challenges. Empty object means no extra limit.
+ALTER TABLE contest_challenges
+  ADD COLUMN rate_limit JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+COMMIT;
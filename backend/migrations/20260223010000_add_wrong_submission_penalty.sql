@@ -0,0 +1,24 @@
+BEGIN;
+
+-- Optional deduction for wrong flags. The full penalty is stored on the
+-- submission; score_floor is applied when totals are computed, so deductions
+-- never take a team below it while points a team never earned are not added.
+ALTER TABLE contests
+  ADD COLUMN wrong_submission_penalty INTEGER NOT NULL DEFAULT 0
+    CHECK (wrong_submission_penalty >= 0 AND wrong_submission_penalty <= 100000),
+  ADD COLUMN score_floor INTEGER NOT NULL DEFAULT 0
+    CHECK (score_floor >= -100000 AND score_floor <= 100000);
+
+ALTER TABLE contest_challenges
+  ADD COLUMN wrong_submission_penalty INTEGER
+    CHECK (wrong_submission_penalty >= 0 AND wrong_submission_penalty <= 100000);
+
+ALTER TABLE submissions
+  ADD COLUMN penalty_points INTEGER NOT NULL DEFAULT 0
+    CHECK (penalty_points >= 0);
+
+CREATE INDEX idx_submissions_contest_penalty
+  ON submissions (contest_id, submitted_at)
+  WHERE penalty_points > 0;
+
+COMMIT;
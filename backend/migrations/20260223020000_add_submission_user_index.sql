@@ -0,0 +1,6 @@
+BEGIN;
+
+-- The admin submissions explorer filters by submitter across contests.
+CREATE INDEX idx_submissions_user_time ON submissions (user_id, submitted_at DESC);
+
+COMMIT;
@@ -0,0 +1,19 @@
+BEGIN;
+
+-- Solo contests rank users individually. Every user competes through a
+-- personal team (teams.solo_user_id) that has no team_members row, so it
+-- does not interfere with the user's regular team and everything keyed by
+-- team_id (registrations, submissions, instances) keeps working.
+ALTER TABLE contests
+  ADD COLUMN participation_mode VARCHAR(16) NOT NULL DEFAULT 'team'
+    CHECK (participation_mode IN ('team', 'solo'));
+
+ALTER TABLE teams
+  ADD COLUMN solo_user_id UUID UNIQUE REFERENCES users(id) ON DELETE CASCADE;
+
+-- Personal teams are named after their user and may share a name with a
+-- regular team.
+DROP INDEX IF EXISTS uk_teams_name_lower;
+CREATE UNIQUE INDEX uk_teams_name_lower ON teams ((LOWER(name))) WHERE solo_user_id IS NULL;
+
+COMMIT;
@@ -0,0 +1,10 @@
+BEGIN;
+
+-- Users may belong to several teams. Which one they play with is decided per
+-- contest by the registration: at most one of a user's teams may be
+-- registered for the same contest (enforced when registering and joining).
+ALTER TABLE team_members DROP CONSTRAINT IF EXISTS team_members_user_id_key;
+
+CREATE INDEX IF NOT EXISTS idx_team_members_user ON team_members(user_id, joined_at);
+
+COMMIT;
@@ -0,0 +1,24 @@
+BEGIN;
+
+-- Divisions split one contest into separately ranked brackets (e.g. student
+-- and professional). Teams pick a self-selectable division when registering;
+-- admins can assign any division.
+CREATE TABLE contest_divisions (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  name VARCHAR(64) NOT NULL,
+  description TEXT NOT NULL DEFAULT '',
+  self_selectable BOOLEAN NOT NULL DEFAULT TRUE,
+  sort_order INTEGER NOT NULL DEFAULT 0,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX uk_contest_divisions_name ON contest_divisions (contest_id, LOWER(name));
+
+ALTER TABLE contest_registrations
+  ADD COLUMN division_id UUID REFERENCES contest_divisions(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_contest_registrations_division ON contest_registrations(contest_id, division_id);
+
+COMMIT;
@@ -0,0 +1,12 @@
+BEGIN;
+
+-- Spectator access: when enabled, the scoreboard (and optionally challenge
+-- titles with solve counts) can be read without logging in. Anonymous
+-- requests are limited per client IP and per minute.
+ALTER TABLE contests
+  ADD COLUMN public_scoreboard BOOLEAN NOT NULL DEFAULT FALSE,
+  ADD COLUMN public_challenge_stats BOOLEAN NOT NULL DEFAULT FALSE,
+  ADD COLUMN public_scoreboard_rate_limit INTEGER NOT NULL DEFAULT 120
+    CHECK (public_scoreboard_rate_limit >= 10 AND public_scoreboard_rate_limit <= 6000);
+
+COMMIT;
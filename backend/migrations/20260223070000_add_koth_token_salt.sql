@@ -0,0 +1,8 @@
+BEGIN;
+
+-- King-of-the-hill team tokens are HMACs over the contest, this salt and the
+-- team. Replacing the salt rotates every team token of the contest.
+ALTER TABLE contests
+  ADD COLUMN koth_token_salt UUID NOT NULL DEFAULT gen_random_uuid();
+
+COMMIT;
@@ -0,0 +1,90 @@
+BEGIN;
+
+-- At most one of a user's teams may hold a live (not rejected) registration
+-- for the same contest. Registering and joining both take transaction-level
+-- advisory locks on the team and on the affected users before checking, so
+-- two concurrent requests cannot both pass the check. Violations raise
+-- exclusion_violation (23P01).
+CREATE OR REPLACE FUNCTION contest_team_lock_key(kind TEXT, id UUID)
+RETURNS BIGINT
+LANGUAGE sql
+IMMUTABLE
+AS $$
+  SELECT hashtextextended('contest_team:' || kind || ':' || id::text, 0)
+$$;
+
+CREATE OR REPLACE FUNCTION enforce_contest_registration_team()
+RETURNS TRIGGER
+LANGUAGE plpgsql
+AS $$
+BEGIN
+  PERFORM pg_advisory_xact_lock(contest_team_lock_key('team', NEW.team_id));
+  PERFORM pg_advisory_xact_lock(contest_team_lock_key('user', m.user_id))
+  FROM (
+    SELECT user_id
+    FROM team_members
+    WHERE team_id = NEW.team_id
+    ORDER BY user_id
+  ) m;
+
+  IF EXISTS (
+    SELECT 1
+    FROM team_members tm
+    JOIN team_members other
+      ON other.user_id = tm.user_id
+     AND other.team_id <> tm.team_id
+    JOIN contest_registrations r ON r.team_id = other.team_id
+    WHERE tm.team_id = NEW.team_id
+      AND r.contest_id = NEW.contest_id
+      AND r.status <> 'rejected'
+  ) THEN
+    RAISE EXCEPTION 'a team member is already registered for this contest with another team'
+      USING ERRCODE = 'exclusion_violation';
+  END IF;
+
+  RETURN NEW;
+END;
+$$;
+
+DROP TRIGGER IF EXISTS trg_contest_registrations_one_team_per_user ON contest_registrations;
+CREATE TRIGGER trg_contest_registrations_one_team_per_user
+BEFORE INSERT OR UPDATE OF team_id, status ON contest_registrations
+FOR EACH ROW
+WHEN (NEW.status <> 'rejected')
+EXECUTE FUNCTION enforce_contest_registration_team();
+
+CREATE OR REPLACE FUNCTION enforce_team_member_contest_team()
+RETURNS TRIGGER
+LANGUAGE plpgsql
+AS $$
+BEGIN
+  PERFORM pg_advisory_xact_lock(contest_team_lock_key('team', NEW.team_id));
+  PERFORM pg_advisory_xact_lock(contest_team_lock_key('user', NEW.user_id));
+
+  IF EXISTS (
+    SELECT 1
+    FROM contest_registrations target
+    JOIN contest_registrations mine
+      ON mine.contest_id = target.contest_id
+     AND mine.team_id <> target.team_id
+    JOIN team_members tm ON tm.team_id = mine.team_id
+    WHERE target.team_id = NEW.team_id
+      AND tm.user_id = NEW.user_id
+      AND target.status <> 'rejected'
+      AND mine.status <> 'rejected'
+  ) THEN
+    RAISE EXCEPTION 'user is already registered for a contest of this team with another team'
+      USING ERRCODE = 'exclusion_violation';
+  END IF;
+
+  RETURN NEW;
+END;
+$$;
+
+DROP TRIGGER IF EXISTS trg_team_members_one_contest_team ON team_members;
+CREATE TRIGGER trg_team_members_one_contest_team
+BEFORE INSERT OR UPDATE OF team_id, user_id ON team_members
+FOR EACH ROW
+EXECUTE FUNCTION enforce_team_member_contest_team();
+
+COMMIT;
@@ -1,7 +1,7 @@
 use std::{future::Future, sync::Arc};
 
 use axum::{
-    extract::FromRequestParts,
+    extract::{FromRequestParts, OptionalFromRequestParts},
     http::{request::Parts, HeaderMap},
 };
 use chrono::{Duration, Utc};
@@ -79,6 +79,28 @@ impl FromRequestParts<Arc<AppState>> for AuthenticatedUser {
     }
 }
 
+/// `Option<AuthenticatedUser>` is `None` only when no `Authorization` header
+/// was sent; a header with a bad or expired token is still rejected.
+impl OptionalFromRequestParts<Arc<AppState>> for AuthenticatedUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        if !parts
+            .headers
+            .contains_key(axum::http::header::AUTHORIZATION)
+        {
+            return Ok(None);
+        }
+
+        <Self as FromRequestParts<Arc<AppState>>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
+
 pub async fn issue_new_session_tokens(
     state: &AppState,
     user_id: Uuid,
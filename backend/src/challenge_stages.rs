@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::flag_rules::{matches_any_flag_rule, normalize_flag_rules, FlagRule};
+
+const MAX_CHALLENGE_STAGES: usize = 16;
+const MAX_STAGE_KEY_CHARS: usize = 32;
+const MAX_STAGE_TITLE_CHARS: usize = 64;
+const MAX_STAGE_POINTS: i32 = 100_000;
+
+/// One stage of a multi-stage challenge, stored in `challenges.stages`.
+/// Each stage has its own flags and awards its own points.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengeStage {
+    pub key: String,
+    pub title: String,
+    pub points: i32,
+    pub flag_rules: Vec<FlagRule>,
+}
+
+pub fn normalize_challenge_stages(
+    stages: Vec<ChallengeStage>,
+) -> Result<Vec<ChallengeStage>, String> {
+    if stages.len() > MAX_CHALLENGE_STAGES {
+        return Err(format!(
+            "stages must be at most {} items",
+            MAX_CHALLENGE_STAGES
+        ));
+    }
+
+    let mut keys: HashSet<String> = HashSet::new();
+    let mut out = Vec::with_capacity(stages.len());
+    for stage in stages {
+        let key = stage.key.trim().to_lowercase();
+        validate_stage_key(&key)?;
+        if !keys.insert(key.clone()) {
+            return Err(format!("stage key '{}' is duplicated", key));
+        }
+
+        let title = stage.title.trim().to_string();
+        if title.is_empty() || title.chars().count() > MAX_STAGE_TITLE_CHARS {
+            return Err(format!(
+                "stage '{}' title must be 1..{} characters",
+                key, MAX_STAGE_TITLE_CHARS
+            ));
+        }
+
+        if stage.points <= 0 || stage.points > MAX_STAGE_POINTS {
+            return Err(format!(
+                "stage '{}' points must be between 1 and {}",
+                key, MAX_STAGE_POINTS
+            ));
+        }
+
+        let flag_rules = normalize_flag_rules(stage.flag_rules)
+            .map_err(|err| format!("stage '{}': {}", key, err))?;
+        if flag_rules.is_empty() {
+            return Err(format!("stage '{}' requires at least one flag rule", key));
+        }
+
+        out.push(ChallengeStage {
+            key,
+            title,
+            points: stage.points,
+            flag_rules,
+        });
+    }
+
+    Ok(out)
+}
+
+pub fn parse_challenge_stages(value: &Value) -> Vec<ChallengeStage> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| serde_json::from_value::<ChallengeStage>(item.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn challenge_stages_to_value(stages: &[ChallengeStage]) -> Value {
+    serde_json::to_value(stages).unwrap_or_else(|_| Value::Array(Vec::new()))
+}
+
+/// Returns the first stage whose flag rules accept the submission.
+pub fn match_challenge_stage<'a>(
+    stages: &'a [ChallengeStage],
+    submitted_flag: &str,
+) -> Option<&'a ChallengeStage> {
+    stages
+        .iter()
+        .find(|stage| matches_any_flag_rule(&stage.flag_rules, submitted_flag))
+}
+
+fn validate_stage_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.chars().count() > MAX_STAGE_KEY_CHARS {
+        return Err(format!(
+            "stage key must be 1..{} characters",
+            MAX_STAGE_KEY_CHARS
+        ));
+    }
+
+    if !key
+        .chars()
+        .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-' || ch == '_')
+    {
+        return Err(format!(
+            "stage key '{}' may only contain a-z, 0-9, '-' and '_'",
+            key
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{match_challenge_stage, normalize_challenge_stages, ChallengeStage};
+    use crate::flag_rules::{FlagMatchType, FlagRule};
+
+    fn stage(key: &str, points: i32, flag: &str) -> ChallengeStage {
+        ChallengeStage {
+            key: key.to_string(),
+            title: format!("Stage {}", key),
+            points,
+            flag_rules: vec![FlagRule {
+                match_type: FlagMatchType::Exact,
+                value: flag.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn matches_stage_by_its_own_flags() {
+        let stages = vec![
+            stage("user", 100, "flag{user}"),
+            stage("root", 200, "flag{root}"),
+        ];
+        assert_eq!(
+            match_challenge_stage(&stages, "flag{root}").map(|item| item.key.as_str()),
+            Some("root")
+        );
+        assert!(match_challenge_stage(&stages, "flag{other}").is_none());
+    }
+
+    #[test]
+    fn normalize_rejects_duplicate_keys_and_missing_flags() {
+        assert!(
+            normalize_challenge_stages(vec![stage("a", 10, "x"), stage("A", 20, "y")]).is_err()
+        );
+
+        let mut no_flags = stage("a", 10, "x");
+        no_flags.flag_rules.clear();
+        assert!(normalize_challenge_stages(vec![no_flags]).is_err());
+
+        assert!(normalize_challenge_stages(vec![stage("bad key", 10, "x")]).is_err());
+        assert!(normalize_challenge_stages(vec![stage("a", 0, "x")]).is_err());
+    }
+}
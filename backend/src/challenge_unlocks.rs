@@ -0,0 +1,324 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+const MAX_UNLOCK_RULES: usize = 8;
+const MAX_RULE_CHALLENGES: usize = 32;
+const MAX_CATEGORY_CHARS: usize = 64;
+
+/// One condition a team must meet before a contest challenge unlocks,
+/// stored in `contest_challenges.unlock_rules`. All rules of a challenge
+/// must hold.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UnlockRule {
+    /// Solve `count` of the listed challenges, or all of them when omitted.
+    Challenges {
+        challenge_ids: Vec<Uuid>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        count: Option<i32>,
+    },
+    /// Solve `count` challenges of the given category in the same contest.
+    Category { category: String, count: i32 },
+}
+
+/// A contest challenge together with what gates it.
+#[derive(Debug, Clone)]
+pub struct UnlockNode {
+    pub challenge_id: Uuid,
+    pub category: String,
+    pub rules: Vec<UnlockRule>,
+}
+
+pub fn normalize_unlock_rules(
+    challenge_id: Uuid,
+    rules: Vec<UnlockRule>,
+) -> Result<Vec<UnlockRule>, String> {
+    if rules.len() > MAX_UNLOCK_RULES {
+        return Err(format!(
+            "unlock_rules must be at most {} items",
+            MAX_UNLOCK_RULES
+        ));
+    }
+
+    let mut out: Vec<UnlockRule> = Vec::with_capacity(rules.len());
+    for rule in rules {
+        let normalized = match rule {
+            UnlockRule::Challenges {
+                challenge_ids,
+                count,
+            } => {
+                let mut ids: Vec<Uuid> = Vec::with_capacity(challenge_ids.len());
+                for id in challenge_ids {
+                    if id == challenge_id {
+                        return Err("a challenge cannot require itself".to_string());
+                    }
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+                if ids.is_empty() || ids.len() > MAX_RULE_CHALLENGES {
+                    return Err(format!(
+                        "unlock rule challenge_ids must have 1..{} items",
+                        MAX_RULE_CHALLENGES
+                    ));
+                }
+                // A count equal to the list length is the same as "all".
+                let count = match count {
+                    Some(value) if value < 1 || value as usize > ids.len() => {
+                        return Err(format!(
+                            "unlock rule count must be between 1 and {}",
+                            ids.len()
+                        ));
+                    }
+                    Some(value) if value as usize == ids.len() => None,
+                    other => other,
+                };
+                UnlockRule::Challenges {
+                    challenge_ids: ids,
+                    count,
+                }
+            }
+            UnlockRule::Category { category, count } => {
+                let category = category.trim().to_lowercase();
+                if category.is_empty() || category.chars().count() > MAX_CATEGORY_CHARS {
+                    return Err(format!(
+                        "unlock rule category must be 1..{} characters",
+                        MAX_CATEGORY_CHARS
+                    ));
+                }
+                if count < 1 {
+                    return Err("unlock rule count must be at least 1".to_string());
+                }
+                UnlockRule::Category { category, count }
+            }
+        };
+
+        if !out.contains(&normalized) {
+            out.push(normalized);
+        }
+    }
+
+    Ok(out)
+}
+
+pub fn parse_unlock_rules(value: &Value) -> Vec<UnlockRule> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| serde_json::from_value::<UnlockRule>(item.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn unlock_rules_to_value(rules: &[UnlockRule]) -> Value {
+    serde_json::to_value(rules).unwrap_or_else(|_| Value::Array(Vec::new()))
+}
+
+/// Whether a team that has solved `solved` meets every rule.
+/// `categories` maps contest challenges to their category.
+pub fn unlock_rules_satisfied(
+    rules: &[UnlockRule],
+    solved: &HashSet<Uuid>,
+    categories: &HashMap<Uuid, String>,
+) -> bool {
+    rules.iter().all(|rule| match rule {
+        UnlockRule::Challenges {
+            challenge_ids,
+            count,
+        } => {
+            let required = count.map(|value| value.max(0) as usize);
+            let solved_count = challenge_ids
+                .iter()
+                .filter(|id| solved.contains(id))
+                .count();
+            solved_count >= required.unwrap_or(challenge_ids.len())
+        }
+        UnlockRule::Category { category, count } => {
+            let solved_count = solved
+                .iter()
+                .filter(|id| categories.get(id).is_some_and(|value| value == category))
+                .count();
+            solved_count >= (*count).max(0) as usize
+        }
+    })
+}
+
+/// Checks that every challenge of the contest can eventually be unlocked.
+/// Starting from the ungated challenges, solves are assumed one unlock round
+/// at a time; whatever never unlocks sits on a cycle or on a rule that asks
+/// for more than the contest offers.
+pub fn validate_unlock_graph(nodes: &[UnlockNode]) -> Result<(), String> {
+    let categories: HashMap<Uuid, String> = nodes
+        .iter()
+        .map(|node| (node.challenge_id, node.category.to_lowercase()))
+        .collect();
+
+    for node in nodes {
+        for rule in &node.rules {
+            if let UnlockRule::Challenges { challenge_ids, .. } = rule {
+                if let Some(missing) = challenge_ids.iter().find(|id| !categories.contains_key(id))
+                {
+                    return Err(format!(
+                        "unlock rule of challenge {} references challenge {} which is not in this contest",
+                        node.challenge_id, missing
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut solved: HashSet<Uuid> = HashSet::new();
+    loop {
+        let unlocked: Vec<Uuid> = nodes
+            .iter()
+            .filter(|node| !solved.contains(&node.challenge_id))
+            .filter(|node| unlock_rules_satisfied(&node.rules, &solved, &categories))
+            .map(|node| node.challenge_id)
+            .collect();
+        if unlocked.is_empty() {
+            break;
+        }
+        solved.extend(unlocked);
+    }
+
+    let mut stuck: Vec<String> = nodes
+        .iter()
+        .filter(|node| !solved.contains(&node.challenge_id))
+        .map(|node| node.challenge_id.to_string())
+        .collect();
+    if stuck.is_empty() {
+        return Ok(());
+    }
+
+    stuck.sort();
+    Err(format!(
+        "unlock rules form a cycle or can never be satisfied for challenges: {}",
+        stuck.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use uuid::Uuid;
+
+    use super::{
+        normalize_unlock_rules, unlock_rules_satisfied, validate_unlock_graph, UnlockNode,
+        UnlockRule,
+    };
+
+    fn node(id: Uuid, category: &str, rules: Vec<UnlockRule>) -> UnlockNode {
+        UnlockNode {
+            challenge_id: id,
+            category: category.to_string(),
+            rules,
+        }
+    }
+
+    fn requires(ids: &[Uuid]) -> UnlockRule {
+        UnlockRule::Challenges {
+            challenge_ids: ids.to_vec(),
+            count: None,
+        }
+    }
+
+    fn web(count: i32) -> UnlockRule {
+        UnlockRule::Category {
+            category: "web".to_string(),
+            count,
+        }
+    }
+
+    #[test]
+    fn satisfied_by_challenges_and_category_counts() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let categories = HashMap::from([
+            (a, "web".to_string()),
+            (b, "web".to_string()),
+            (c, "pwn".to_string()),
+        ]);
+        let any_one = UnlockRule::Challenges {
+            challenge_ids: vec![a, c],
+            count: Some(1),
+        };
+
+        let solved = HashSet::from([a]);
+        assert!(unlock_rules_satisfied(
+            std::slice::from_ref(&any_one),
+            &solved,
+            &categories
+        ));
+        assert!(!unlock_rules_satisfied(
+            &[requires(&[a, c])],
+            &solved,
+            &categories
+        ));
+        assert!(!unlock_rules_satisfied(&[web(2)], &solved, &categories));
+
+        let solved = HashSet::from([a, b]);
+        assert!(unlock_rules_satisfied(
+            &[web(2), any_one],
+            &solved,
+            &categories
+        ));
+    }
+
+    #[test]
+    fn graph_rejects_cycles_and_unsatisfiable_categories() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        let chain = vec![
+            node(a, "web", Vec::new()),
+            node(b, "web", vec![requires(&[a])]),
+            node(c, "pwn", vec![web(2)]),
+        ];
+        assert!(validate_unlock_graph(&chain).is_ok());
+
+        let cycle = vec![
+            node(a, "web", Vec::new()),
+            node(b, "web", vec![requires(&[c])]),
+            node(c, "pwn", vec![requires(&[b])]),
+        ];
+        let err = validate_unlock_graph(&cycle).unwrap_err();
+        assert!(err.contains(&b.to_string()) && err.contains(&c.to_string()));
+
+        // b can only count a toward "2 web", so it never unlocks.
+        let starved = vec![node(a, "web", Vec::new()), node(b, "web", vec![web(2)])];
+        assert!(validate_unlock_graph(&starved).is_err());
+
+        let dangling = vec![node(a, "web", vec![requires(&[Uuid::new_v4()])])];
+        assert!(validate_unlock_graph(&dangling).is_err());
+    }
+
+    #[test]
+    fn normalize_rejects_self_reference_and_bad_counts() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert!(normalize_unlock_rules(a, vec![requires(&[a])]).is_err());
+        assert!(normalize_unlock_rules(
+            a,
+            vec![UnlockRule::Challenges {
+                challenge_ids: vec![b],
+                count: Some(2),
+            }]
+        )
+        .is_err());
+        assert!(normalize_unlock_rules(a, vec![web(0)]).is_err());
+
+        let rules = normalize_unlock_rules(
+            a,
+            vec![UnlockRule::Category {
+                category: " Web ".to_string(),
+                count: 1,
+            }],
+        )
+        .unwrap();
+        assert_eq!(rules, vec![web(1)]);
+    }
+}
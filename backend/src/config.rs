@@ -1,3 +1,6 @@
+use std::net::IpAddr;
+
+use anyhow::Context;
 use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -58,6 +61,11 @@ pub struct AppConfig {
     pub instance_heartbeat_report_interval_seconds: u64,
     pub instance_stale_reaper_enabled: bool,
     pub instance_stale_reaper_batch_size: i64,
+    pub attack_defense_ticker_enabled: bool,
+    pub attack_defense_ticker_interval_seconds: u64,
+    pub koth_ticker_enabled: bool,
+    pub koth_ticker_interval_seconds: u64,
+    pub trusted_proxies: String,
 }
 
 impl AppConfig {
@@ -125,8 +133,28 @@ impl AppConfig {
             .set_default("instance_heartbeat_report_interval_seconds", 30_u64)?
             .set_default("instance_stale_reaper_enabled", false)?
             .set_default("instance_stale_reaper_batch_size", 20_i64)?
+            .set_default("attack_defense_ticker_enabled", true)?
+            .set_default("attack_defense_ticker_interval_seconds", 5_u64)?
+            .set_default("koth_ticker_enabled", true)?
+            .set_default("koth_ticker_interval_seconds", 5_u64)?
+            .set_default("trusted_proxies", "")?
             .add_source(::config::Environment::default().separator("__"));
 
         builder.build()?.try_deserialize().map_err(Into::into)
     }
+
+    /// Parses `TRUSTED_PROXIES`, a comma-separated list of proxy addresses
+    /// whose forwarded client address headers are believed.
+    pub fn trusted_proxy_ips(&self) -> anyhow::Result<Vec<IpAddr>> {
+        self.trusted_proxies
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                value
+                    .parse()
+                    .with_context(|| format!("invalid TRUSTED_PROXIES entry: {value}"))
+            })
+            .collect()
+    }
 }
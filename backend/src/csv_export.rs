@@ -0,0 +1,47 @@
+/// Quotes a CSV field when needed (RFC 4180). Text that a spreadsheet would
+/// evaluate as a formula gets a leading `'`, since exported values such as
+/// submitted flags and team names come from players.
+pub fn csv_text_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Joins already formatted fields into one CSV line, including the trailing
+/// newline.
+pub fn csv_line(fields: &[String]) -> String {
+    let mut line = fields.join(",");
+    line.push_str("\r\n");
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_line, csv_text_field};
+
+    #[test]
+    fn text_fields_are_quoted_and_defused() {
+        assert_eq!(csv_text_field("flag{plain}"), "flag{plain}");
+        assert_eq!(csv_text_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_text_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_text_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_text_field("=1+1"), "'=1+1");
+        assert_eq!(csv_text_field("-2,3"), "\"'-2,3\"");
+    }
+
+    #[test]
+    fn lines_end_with_crlf() {
+        assert_eq!(
+            csv_line(&["1".to_string(), csv_text_field("x,y")]),
+            "1,\"x,y\"\r\n"
+        );
+    }
+}
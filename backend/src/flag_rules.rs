@@ -0,0 +1,169 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const MAX_FLAG_RULES: usize = 32;
+const MAX_FLAG_RULE_VALUE_CHARS: usize = 512;
+const REGEX_SIZE_LIMIT_BYTES: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagMatchType {
+    Exact,
+    CaseInsensitive,
+    Regex,
+}
+
+/// One accepted answer of a static challenge, stored in `challenges.flag_rules`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagRule {
+    pub match_type: FlagMatchType,
+    pub value: String,
+}
+
+/// Validates rules submitted through the admin API. Values are trimmed,
+/// duplicates dropped and regex rules compiled once to reject bad patterns.
+pub fn normalize_flag_rules(rules: Vec<FlagRule>) -> Result<Vec<FlagRule>, String> {
+    if rules.len() > MAX_FLAG_RULES {
+        return Err(format!(
+            "flag_rules must be at most {} items",
+            MAX_FLAG_RULES
+        ));
+    }
+
+    let mut out: Vec<FlagRule> = Vec::new();
+    for rule in rules {
+        let value = rule.value.trim().to_string();
+        if value.is_empty() {
+            return Err("flag_rules value must not be empty".to_string());
+        }
+        if value.chars().count() > MAX_FLAG_RULE_VALUE_CHARS {
+            return Err(format!(
+                "flag_rules value must be at most {} characters",
+                MAX_FLAG_RULE_VALUE_CHARS
+            ));
+        }
+        if rule.match_type == FlagMatchType::Regex {
+            compile_anchored(&value)
+                .map_err(|err| format!("flag_rules regex '{}' is invalid: {}", value, err))?;
+        }
+
+        let normalized = FlagRule {
+            match_type: rule.match_type,
+            value,
+        };
+        if !out.contains(&normalized) {
+            out.push(normalized);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Reads rules back from the stored JSON, skipping entries that no longer
+/// deserialize so one bad row cannot break judging.
+pub fn parse_flag_rules(value: &Value) -> Vec<FlagRule> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| serde_json::from_value::<FlagRule>(item.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn flag_rules_to_value(rules: &[FlagRule]) -> Value {
+    serde_json::to_value(rules).unwrap_or_else(|_| Value::Array(Vec::new()))
+}
+
+pub fn matches_any_flag_rule(rules: &[FlagRule], submitted_flag: &str) -> bool {
+    rules
+        .iter()
+        .any(|rule| matches_flag_rule(rule, submitted_flag))
+}
+
+fn matches_flag_rule(rule: &FlagRule, submitted_flag: &str) -> bool {
+    match rule.match_type {
+        FlagMatchType::Exact => submitted_flag == rule.value,
+        FlagMatchType::CaseInsensitive => {
+            submitted_flag.to_lowercase() == rule.value.to_lowercase()
+        }
+        FlagMatchType::Regex => compile_anchored(&rule.value)
+            .map(|regex| regex.is_match(submitted_flag))
+            .unwrap_or(false),
+    }
+}
+
+/// Regex rules must match the whole submission, not a substring of it.
+fn compile_anchored(pattern: &str) -> Result<Regex, regex::Error> {
+    regex::RegexBuilder::new(&format!("^(?:{})$", pattern))
+        .size_limit(REGEX_SIZE_LIMIT_BYTES)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        matches_any_flag_rule, normalize_flag_rules, parse_flag_rules, FlagMatchType, FlagRule,
+    };
+
+    fn rule(match_type: FlagMatchType, value: &str) -> FlagRule {
+        FlagRule {
+            match_type,
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn matches_each_rule_type() {
+        let rules = vec![
+            rule(FlagMatchType::Exact, "flag{exact}"),
+            rule(FlagMatchType::CaseInsensitive, "flag{Mixed_Case}"),
+            rule(FlagMatchType::Regex, r"flag\{[a-f0-9]{4}\}"),
+        ];
+
+        assert!(matches_any_flag_rule(&rules, "flag{exact}"));
+        assert!(!matches_any_flag_rule(&rules, "FLAG{exact}"));
+        assert!(matches_any_flag_rule(&rules, "FLAG{mixed_case}"));
+        assert!(matches_any_flag_rule(&rules, "flag{0a9f}"));
+        assert!(!matches_any_flag_rule(&rules, "flag{0a9g}"));
+    }
+
+    #[test]
+    fn regex_rules_are_anchored() {
+        let rules = vec![rule(FlagMatchType::Regex, r"flag\{\d+\}|ctf\{\d+\}")];
+        assert!(matches_any_flag_rule(&rules, "ctf{42}"));
+        assert!(!matches_any_flag_rule(&rules, "xflag{42}"));
+        assert!(!matches_any_flag_rule(&rules, "flag{42}x"));
+    }
+
+    #[test]
+    fn normalize_rejects_invalid_regex_and_empty_values() {
+        assert!(normalize_flag_rules(vec![rule(FlagMatchType::Regex, "flag{(")]).is_err());
+        assert!(normalize_flag_rules(vec![rule(FlagMatchType::Exact, "   ")]).is_err());
+
+        let rules = normalize_flag_rules(vec![
+            rule(FlagMatchType::Exact, " flag{a} "),
+            rule(FlagMatchType::Exact, "flag{a}"),
+        ])
+        .unwrap();
+        assert_eq!(rules, vec![rule(FlagMatchType::Exact, "flag{a}")]);
+    }
+
+    #[test]
+    fn parse_skips_malformed_entries() {
+        let value = json!([
+            {"match_type": "case_insensitive", "value": "flag{x}"},
+            {"match_type": "glob", "value": "flag{*}"},
+            "flag{y}"
+        ]);
+        assert_eq!(
+            parse_flag_rules(&value),
+            vec![rule(FlagMatchType::CaseInsensitive, "flag{x}")]
+        );
+    }
+}
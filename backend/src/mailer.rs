@@ -104,5 +104,5 @@ fn parse_from_mailbox(config: &AppConfig) -> anyhow::Result<Mailbox> {
 
     format!("{from_name} <{from_address}>")
         .parse()
-        .with_context(|| format!("invalid AUTH_EMAIL_FROM_NAME/AUTH_EMAIL_FROM_ADDRESS pair"))
+        .with_context(|| "invalid AUTH_EMAIL_FROM_NAME/AUTH_EMAIL_FROM_ADDRESS pair".to_string())
 }
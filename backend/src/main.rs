@@ -1,11 +1,16 @@
 mod auth;
+mod challenge_stages;
+mod challenge_unlocks;
 mod config;
+mod csv_export;
 mod error;
+mod flag_rules;
 mod mailer;
 mod password_policy;
 mod routes;
 mod runtime_template;
 mod state;
+mod submission_limits;
 
 use std::{net::SocketAddr, sync::Arc};
 
@@ -30,6 +35,8 @@ async fn main() -> anyhow::Result<()> {
     let state = Arc::new(AppState::new(config.clone()).await?);
     spawn_runtime_alert_scanner(Arc::clone(&state));
     spawn_instance_reaper(Arc::clone(&state));
+    spawn_attack_defense_ticker(Arc::clone(&state));
+    spawn_koth_ticker(Arc::clone(&state));
 
     let app = build_router(state);
     let addr: SocketAddr = format!("{}:{}", config.app_host, config.app_port).parse()?;
@@ -37,9 +44,12 @@ async fn main() -> anyhow::Result<()> {
     info!("rust-ctf backend listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     Ok(())
 }
@@ -213,6 +223,79 @@ fn spawn_instance_reaper(state: Arc<AppState>) {
     });
 }
 
+fn spawn_attack_defense_ticker(state: Arc<AppState>) {
+    if !state.config.attack_defense_ticker_enabled {
+        info!("attack-defense ticker disabled by configuration");
+        return;
+    }
+
+    let interval_seconds = state
+        .config
+        .attack_defense_ticker_interval_seconds
+        .clamp(1, 60);
+
+    info!(interval_seconds, "attack-defense ticker task scheduled");
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval_seconds));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            match routes::attack_defense::run_attack_defense_ticker(state.as_ref()).await {
+                Ok(summary) => {
+                    if summary.due > 0 {
+                        info!(
+                            due = summary.due,
+                            advanced = summary.advanced,
+                            failed = summary.failed,
+                            "attack-defense ticker tick completed"
+                        );
+                    }
+                }
+                Err(err) => {
+                    warn!(error = %err, "attack-defense ticker tick failed");
+                }
+            }
+        }
+    });
+}
+
+fn spawn_koth_ticker(state: Arc<AppState>) {
+    if !state.config.koth_ticker_enabled {
+        info!("king-of-the-hill ticker disabled by configuration");
+        return;
+    }
+
+    let interval_seconds = state.config.koth_ticker_interval_seconds.clamp(1, 60);
+
+    info!(interval_seconds, "king-of-the-hill ticker task scheduled");
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval_seconds));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            match routes::koth::run_koth_ticker(state.as_ref()).await {
+                Ok(summary) => {
+                    if summary.due > 0 {
+                        info!(
+                            due = summary.due,
+                            ticked = summary.ticked,
+                            failed = summary.failed,
+                            "king-of-the-hill ticker tick completed"
+                        );
+                    }
+                }
+                Err(err) => {
+                    warn!(error = %err, "king-of-the-hill ticker tick failed");
+                }
+            }
+        }
+    });
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
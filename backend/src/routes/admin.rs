@@ -12,14 +12,14 @@ use axum::{
     extract::{DefaultBodyLimit, Path, Query, State},
     http::{header, HeaderValue, StatusCode},
     response::Response,
-    routing::{get, patch, post},
+    routing::{delete, get, patch, post, put},
     Json, Router,
 };
 use chrono::{DateTime, Utc};
-use futures_util::stream;
+use futures_util::{stream, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use sqlx::FromRow;
+use sqlx::{postgres::PgArguments, query::QueryAs, FromRow, PgConnection, Postgres};
 use tokio::fs;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command;
@@ -30,23 +30,74 @@ use uuid::Uuid;
 
 use crate::{
     auth::{self, AuthenticatedUser},
+    challenge_stages::{
+        challenge_stages_to_value, normalize_challenge_stages, parse_challenge_stages,
+        ChallengeStage,
+    },
+    challenge_unlocks::{
+        normalize_unlock_rules, unlock_rules_to_value, validate_unlock_graph, UnlockRule,
+    },
+    csv_export::{csv_line, csv_text_field},
     error::{AppError, AppResult},
+    flag_rules::{flag_rules_to_value, normalize_flag_rules, parse_flag_rules, FlagRule},
     password_policy::{enforce_password_policy, PasswordContext},
-    routes::instances,
+    routes::{
+        attack_defense::{self, AdRoundAdvanceSummary, AdRoundItem},
+        challenge_unlocks::load_contest_unlock_nodes,
+        contest_access::{
+            is_contest_team_conflict, sync_solo_team_name, CONTEST_PARTICIPATION_SOLO,
+            CONTEST_PARTICIPATION_TEAM,
+        },
+        contest_divisions::{list_contest_divisions, load_contest_division, ContestDivisionItem},
+        instances,
+        koth::{self, KothTickItem},
+        scoreboard::{
+            publish_scoreboard_announcement, publish_scoreboard_update, SCOREBOARD_EVENT_REVEAL,
+            SCOREBOARD_EVENT_UPDATE,
+        },
+        scoreboard_cache::{invalidate_challenge_scoreboards, invalidate_scoreboard_cache},
+        scoring::{
+            blood_bonus, challenge_value, load_contest_scoring_config, ChallengeScoreBounds,
+            DifficultyScores,
+        },
+        submissions::{self, RejudgeReport},
+        webhook_verifier::{
+            parse_webhook_verifier_config, run_webhook_verifier, WebhookVerifierResult,
+            WebhookVerifyRequest,
+        },
+    },
     runtime_template::{
         build_single_image_compose_template, parse_runtime_metadata_options,
         validate_compose_template_schema, RuntimeMode,
     },
     state::AppState,
+    submission_limits::{
+        challenge_rate_limit_to_value, normalize_challenge_rate_limit, ChallengeRateLimit,
+    },
 };
 
 const DIFFICULTY_ALLOWED: &[&str] = &["easy", "normal", "hard", "insane"];
-const CHALLENGE_TYPE_ALLOWED: &[&str] = &["static", "dynamic", "internal"];
-const FLAG_MODE_ALLOWED: &[&str] = &["static", "dynamic", "script"];
+const CHALLENGE_TYPE_ALLOWED: &[&str] = &["static", "dynamic", "internal", "koth"];
+const FLAG_MODE_ALLOWED: &[&str] = &["static", "dynamic", "script", "webhook"];
 const CONTEST_STATUS_ALLOWED: &[&str] = &["draft", "scheduled", "running", "ended", "archived"];
 const CONTEST_VISIBILITY_ALLOWED: &[&str] = &["public", "private"];
-const CONTEST_SCORING_MODE_ALLOWED: &[&str] = &["static", "dynamic"];
+const CONTEST_SCORING_MODE_ALLOWED: &[&str] = &["static", "dynamic", "difficulty"];
+const CONTEST_TYPE_ALLOWED: &[&str] = &["jeopardy", "attack_defense"];
+const CONTEST_PARTICIPATION_MODE_ALLOWED: &[&str] =
+    &[CONTEST_PARTICIPATION_TEAM, CONTEST_PARTICIPATION_SOLO];
+const CONTEST_SCORING_FORMULA_ALLOWED: &[&str] = &["hyperbolic", "logarithmic", "linear"];
 const CONTEST_REGISTRATION_STATUS_ALLOWED: &[&str] = &["pending", "approved", "rejected"];
+const SUBMISSION_VERDICT_ALLOWED: &[&str] =
+    &["accepted", "wrong", "invalid", "rate_limited", "error"];
+const SUBMISSION_EXPORT_FORMAT_ALLOWED: &[&str] = &["csv", "json"];
+const MAX_SUBMISSION_FLAG_FILTER_CHARS: usize = 256;
+const MAX_CHALLENGE_HINTS: i64 = 20;
+const MAX_CHALLENGE_HINT_CHARS: usize = 2000;
+const MAX_CHALLENGE_HINT_COST: i32 = 100_000;
+const MAX_CONTEST_DIVISIONS: i64 = 20;
+const MAX_CONTEST_DIVISION_NAME_CHARS: usize = 64;
+const MAX_CONTEST_DIVISION_DESCRIPTION_CHARS: usize = 500;
+const FLAG_SHARING_INCIDENT_STATUS_ALLOWED: &[&str] = &["open", "confirmed", "dismissed"];
 const TIME_DISPLAY_MODE_ALLOWED: &[&str] = &["local", "utc"];
 const WRITEUP_VISIBILITY_ALLOWED: &[&str] = &["hidden", "after_solve", "after_contest", "public"];
 const CHALLENGE_STATUS_ALLOWED: &[&str] = &["draft", "published", "offline"];
@@ -76,7 +127,7 @@ const RUNTIME_ALERT_SCANNER_TYPES: &[&str] = &[
 const CONTEST_POSTER_MAX_BYTES: usize = 8 * 1024 * 1024;
 const IMAGE_TEST_LOG_MAX_BYTES: usize = 256 * 1024;
 const DEFAULT_CHALLENGE_ATTACHMENT_MAX_BYTES: i64 = 20 * 1024 * 1024;
-const MIN_CHALLENGE_ATTACHMENT_MAX_BYTES: i64 = 1 * 1024 * 1024;
+const MIN_CHALLENGE_ATTACHMENT_MAX_BYTES: i64 = 1024 * 1024;
 const MAX_CHALLENGE_ATTACHMENT_MAX_BYTES: i64 = 256 * 1024 * 1024;
 const ATTACHMENT_UPLOAD_JSON_BODY_LIMIT_BYTES: usize = 384 * 1024 * 1024;
 
@@ -93,6 +144,9 @@ struct AdminChallengeItem {
     status: String,
     is_visible: bool,
     tags: Vec<String>,
+    /// Hint contents in display order; costs and release times are managed
+    /// through the challenge hint endpoints.
+    hints: Vec<String>,
     writeup_visibility: String,
     current_version: i32,
     created_at: DateTime<Utc>,
@@ -125,6 +179,8 @@ struct AdminChallengeDetailItem {
     flag_mode: String,
     status: String,
     flag_hash: String,
+    flag_rules: Value,
+    stages: Value,
     compose_template: Option<String>,
     metadata: Value,
     is_visible: bool,
@@ -151,6 +207,8 @@ struct CreateChallengeRequest {
     flag_mode: Option<String>,
     status: Option<String>,
     flag_hash: Option<String>,
+    flag_rules: Option<Vec<FlagRule>>,
+    stages: Option<Vec<ChallengeStage>>,
     compose_template: Option<String>,
     metadata: Option<Value>,
     is_visible: Option<bool>,
@@ -173,6 +231,8 @@ struct UpdateChallengeRequest {
     flag_mode: Option<String>,
     status: Option<String>,
     flag_hash: Option<String>,
+    flag_rules: Option<Vec<FlagRule>>,
+    stages: Option<Vec<ChallengeStage>>,
     compose_template: Option<String>,
     metadata: Option<Value>,
     is_visible: Option<bool>,
@@ -198,16 +258,66 @@ struct ChallengeSnapshot {
     #[serde(default = "default_challenge_status")]
     status: String,
     flag_hash: String,
+    #[serde(default)]
+    flag_rules: Vec<FlagRule>,
+    #[serde(default)]
+    stages: Vec<ChallengeStage>,
     compose_template: Option<String>,
     metadata: Value,
     is_visible: bool,
     tags: Vec<String>,
+    /// Absent in snapshots that did not version hints; rolling back to those
+    /// leaves the current hints alone.
     #[serde(default)]
-    hints: Vec<String>,
+    hints: Option<Vec<ChallengeSnapshotHint>>,
     writeup_visibility: String,
     writeup_content: String,
 }
 
+/// A hint as versioned with its challenge. Snapshots taken before hints had
+/// costs hold plain strings, which restore as free hints.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(from = "ChallengeSnapshotHintValue")]
+struct ChallengeSnapshotHint {
+    content: String,
+    cost: i32,
+    release_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChallengeSnapshotHintValue {
+    Text(String),
+    Hint {
+        content: String,
+        #[serde(default)]
+        cost: i32,
+        #[serde(default)]
+        release_at: Option<DateTime<Utc>>,
+    },
+}
+
+impl From<ChallengeSnapshotHintValue> for ChallengeSnapshotHint {
+    fn from(value: ChallengeSnapshotHintValue) -> Self {
+        match value {
+            ChallengeSnapshotHintValue::Text(content) => Self {
+                content,
+                cost: 0,
+                release_at: None,
+            },
+            ChallengeSnapshotHintValue::Hint {
+                content,
+                cost,
+                release_at,
+            } => Self {
+                content,
+                cost,
+                release_at,
+            },
+        }
+    }
+}
+
 #[derive(Debug, FromRow)]
 struct ChallengeSnapshotRow {
     id: Uuid,
@@ -223,11 +333,12 @@ struct ChallengeSnapshotRow {
     flag_mode: String,
     status: String,
     flag_hash: String,
+    flag_rules: Value,
+    stages: Value,
     compose_template: Option<String>,
     metadata: Value,
     is_visible: bool,
     tags: Vec<String>,
-    hints: Vec<String>,
     writeup_visibility: String,
     writeup_content: String,
     current_version: i32,
@@ -397,11 +508,72 @@ struct AdminChallengeAttachmentItem {
     created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Deserialize)]
+struct TestWebhookVerifierRequest {
+    flag: String,
+    contest_id: Option<Uuid>,
+    team_id: Option<Uuid>,
+    webhook_verifier: Option<Value>,
+}
+
 #[derive(Debug, Deserialize)]
 struct ChallengeAttachmentsQuery {
     limit: Option<i64>,
 }
 
+#[derive(Debug, Serialize, FromRow)]
+struct AdminChallengeHintItem {
+    id: Uuid,
+    challenge_id: Uuid,
+    sort_order: i32,
+    content: String,
+    cost: i32,
+    release_at: Option<DateTime<Utc>>,
+    unlock_count: i64,
+    created_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateChallengeHintRequest {
+    content: String,
+    cost: Option<i32>,
+    release_at: Option<DateTime<Utc>>,
+    sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateChallengeHintRequest {
+    content: Option<String>,
+    cost: Option<i32>,
+    release_at: Option<DateTime<Utc>>,
+    clear_release_at: Option<bool>,
+    sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdminHintUnlocksQuery {
+    challenge_id: Option<Uuid>,
+    team_id: Option<Uuid>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct AdminHintUnlockItem {
+    id: Uuid,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    challenge_title: String,
+    hint_id: Option<Uuid>,
+    team_id: Uuid,
+    team_name: String,
+    user_id: Option<Uuid>,
+    username: Option<String>,
+    cost: i32,
+    unlocked_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Deserialize)]
 struct CreateChallengeCategoryRequest {
     slug: String,
@@ -425,15 +597,37 @@ struct AdminContestItem {
     poster_url: Option<String>,
     visibility: String,
     status: String,
+    contest_type: String,
     scoring_mode: String,
+    scoring_formula: String,
     dynamic_decay: i32,
+    difficulty_scores: Value,
     first_blood_bonus_percent: i32,
     second_blood_bonus_percent: i32,
     third_blood_bonus_percent: i32,
+    ad_round_seconds: i32,
+    ad_flag_lifetime_rounds: i32,
+    ad_attack_points: i32,
+    ad_defense_points: i32,
+    ad_sla_points: i32,
+    koth_tick_seconds: i32,
+    submission_rate_window_seconds: i32,
+    submission_rate_user_max: i32,
+    submission_rate_team_max: i32,
+    wrong_backoff_threshold: i32,
+    wrong_backoff_base_seconds: i32,
+    wrong_backoff_max_seconds: i32,
+    wrong_submission_penalty: i32,
+    score_floor: i32,
+    participation_mode: String,
     registration_requires_approval: bool,
+    public_scoreboard: bool,
+    public_challenge_stats: bool,
+    public_scoreboard_rate_limit: i32,
     start_at: DateTime<Utc>,
     end_at: DateTime<Utc>,
     freeze_at: Option<DateTime<Utc>>,
+    scoreboard_revealed_at: Option<DateTime<Utc>>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
@@ -445,12 +639,33 @@ struct CreateContestRequest {
     description: Option<String>,
     visibility: Option<String>,
     status: Option<String>,
+    contest_type: Option<String>,
     scoring_mode: Option<String>,
+    scoring_formula: Option<String>,
     dynamic_decay: Option<i32>,
+    difficulty_scores: Option<DifficultyScores>,
     first_blood_bonus_percent: Option<i32>,
     second_blood_bonus_percent: Option<i32>,
     third_blood_bonus_percent: Option<i32>,
+    ad_round_seconds: Option<i32>,
+    ad_flag_lifetime_rounds: Option<i32>,
+    ad_attack_points: Option<i32>,
+    ad_defense_points: Option<i32>,
+    ad_sla_points: Option<i32>,
+    koth_tick_seconds: Option<i32>,
+    submission_rate_window_seconds: Option<i32>,
+    submission_rate_user_max: Option<i32>,
+    submission_rate_team_max: Option<i32>,
+    wrong_backoff_threshold: Option<i32>,
+    wrong_backoff_base_seconds: Option<i32>,
+    wrong_backoff_max_seconds: Option<i32>,
+    wrong_submission_penalty: Option<i32>,
+    score_floor: Option<i32>,
+    participation_mode: Option<String>,
     registration_requires_approval: Option<bool>,
+    public_scoreboard: Option<bool>,
+    public_challenge_stats: Option<bool>,
+    public_scoreboard_rate_limit: Option<i32>,
     start_at: DateTime<Utc>,
     end_at: DateTime<Utc>,
     freeze_at: Option<DateTime<Utc>>,
@@ -463,12 +678,33 @@ struct UpdateContestRequest {
     description: Option<String>,
     visibility: Option<String>,
     status: Option<String>,
+    contest_type: Option<String>,
     scoring_mode: Option<String>,
+    scoring_formula: Option<String>,
     dynamic_decay: Option<i32>,
+    difficulty_scores: Option<DifficultyScores>,
     first_blood_bonus_percent: Option<i32>,
     second_blood_bonus_percent: Option<i32>,
     third_blood_bonus_percent: Option<i32>,
+    ad_round_seconds: Option<i32>,
+    ad_flag_lifetime_rounds: Option<i32>,
+    ad_attack_points: Option<i32>,
+    ad_defense_points: Option<i32>,
+    ad_sla_points: Option<i32>,
+    koth_tick_seconds: Option<i32>,
+    submission_rate_window_seconds: Option<i32>,
+    submission_rate_user_max: Option<i32>,
+    submission_rate_team_max: Option<i32>,
+    wrong_backoff_threshold: Option<i32>,
+    wrong_backoff_base_seconds: Option<i32>,
+    wrong_backoff_max_seconds: Option<i32>,
+    wrong_submission_penalty: Option<i32>,
+    score_floor: Option<i32>,
+    participation_mode: Option<String>,
     registration_requires_approval: Option<bool>,
+    public_scoreboard: Option<bool>,
+    public_challenge_stats: Option<bool>,
+    public_scoreboard_rate_limit: Option<i32>,
     start_at: Option<DateTime<Utc>>,
     end_at: Option<DateTime<Utc>>,
     freeze_at: Option<DateTime<Utc>>,
@@ -534,6 +770,9 @@ struct AdminContestChallengeItem {
     challenge_difficulty: String,
     sort_order: i32,
     release_at: Option<DateTime<Utc>>,
+    unlock_rules: Value,
+    rate_limit: Value,
+    wrong_submission_penalty: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -541,6 +780,7 @@ struct UpsertContestChallengeRequest {
     challenge_id: Uuid,
     sort_order: Option<i32>,
     release_at: Option<DateTime<Utc>>,
+    wrong_submission_penalty: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -548,6 +788,138 @@ struct UpdateContestChallengeRequest {
     sort_order: Option<i32>,
     release_at: Option<DateTime<Utc>>,
     clear_release_at: Option<bool>,
+    wrong_submission_penalty: Option<i32>,
+    clear_wrong_submission_penalty: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateContestChallengeUnlockRulesRequest {
+    unlock_rules: Vec<UnlockRule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateContestChallengeRateLimitRequest {
+    rate_limit: ChallengeRateLimit,
+}
+
+#[derive(Debug, Serialize)]
+struct AdminContestUnlockGraphResponse {
+    contest_id: Uuid,
+    valid: bool,
+    error: Option<String>,
+    challenges: Vec<AdminContestChallengeItem>,
+}
+
+/// Unset fields fall back to the contest's scoring config and, for the score
+/// bounds, to `challenge_id` or the challenge defaults.
+#[derive(Debug, Deserialize)]
+struct PreviewContestScoringRequest {
+    scoring_mode: Option<String>,
+    scoring_formula: Option<String>,
+    dynamic_decay: Option<i32>,
+    difficulty_scores: Option<DifficultyScores>,
+    challenge_id: Option<Uuid>,
+    static_score: Option<i32>,
+    min_score: Option<i32>,
+    max_score: Option<i32>,
+    difficulty: Option<String>,
+    max_solves: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContestScoringPreviewPoint {
+    solve_count: i64,
+    value: i64,
+    blood_bonus: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct ContestScoringPreviewResponse {
+    contest_id: Uuid,
+    scoring_mode: String,
+    scoring_formula: String,
+    dynamic_decay: i32,
+    difficulty_scores: DifficultyScores,
+    static_score: i32,
+    min_score: i32,
+    max_score: i32,
+    difficulty: String,
+    points: Vec<ContestScoringPreviewPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdminAttackDefenseRoundsQuery {
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct AttackDefenseDeployItem {
+    team_id: Uuid,
+    team_name: String,
+    challenge_id: Uuid,
+    challenge_title: String,
+    status: String,
+    entrypoint_url: Option<String>,
+    message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct AttackDefenseDeployResponse {
+    contest_id: Uuid,
+    deployed: i64,
+    failed: i64,
+    items: Vec<AttackDefenseDeployItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdminKothTicksQuery {
+    challenge_id: Option<Uuid>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct KothDeployResponse {
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    entrypoint_url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct KothTokenRotateResponse {
+    contest_id: Uuid,
+    rotated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RejudgeSubmissionsRequest {
+    challenge_id: Option<Uuid>,
+    dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdminScoreAdjustmentsQuery {
+    team_id: Option<Uuid>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct AdminScoreAdjustmentItem {
+    id: Uuid,
+    contest_id: Uuid,
+    team_id: Uuid,
+    team_name: String,
+    points: i32,
+    reason: String,
+    created_by: Option<Uuid>,
+    created_by_username: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateScoreAdjustmentRequest {
+    team_id: Uuid,
+    points: i32,
+    reason: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -564,6 +936,35 @@ struct AdminAuditLogsQuery {
     limit: Option<i64>,
 }
 
+/// Filters shared by the submissions list, summary and export endpoints.
+/// `before_id/limit` only apply to the list and `format` only to the export.
+#[derive(Debug, Deserialize)]
+struct AdminSubmissionsQuery {
+    contest_id: Option<Uuid>,
+    challenge_id: Option<Uuid>,
+    team_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+    verdict: Option<String>,
+    submitted_from: Option<DateTime<Utc>>,
+    submitted_to: Option<DateTime<Utc>>,
+    flag_contains: Option<String>,
+    before_id: Option<i64>,
+    limit: Option<i64>,
+    format: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AdminSubmissionFilter {
+    contest_id: Option<Uuid>,
+    challenge_id: Option<Uuid>,
+    team_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+    verdict: Option<String>,
+    submitted_from: Option<DateTime<Utc>>,
+    submitted_to: Option<DateTime<Utc>>,
+    flag_contains: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct AdminRuntimeAlertsQuery {
     status: Option<String>,
@@ -615,15 +1016,73 @@ struct UpdateSiteSettingsRequest {
 #[derive(Debug, Deserialize)]
 struct AdminContestRegistrationsQuery {
     status: Option<String>,
+    division_id: Option<Uuid>,
     limit: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
 struct UpdateContestRegistrationRequest {
+    status: Option<String>,
+    review_note: Option<String>,
+    division_id: Option<Uuid>,
+    clear_division: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateContestDivisionRequest {
+    name: String,
+    description: Option<String>,
+    self_selectable: Option<bool>,
+    sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateContestDivisionRequest {
+    name: Option<String>,
+    description: Option<String>,
+    self_selectable: Option<bool>,
+    sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdminFlagSharingIncidentsQuery {
+    status: Option<String>,
+    challenge_id: Option<Uuid>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateFlagSharingIncidentRequest {
     status: String,
     review_note: Option<String>,
 }
 
+#[derive(Debug, Serialize, FromRow)]
+struct AdminFlagSharingIncidentItem {
+    id: Uuid,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    challenge_title: String,
+    submitter_team_id: Uuid,
+    submitter_team_name: String,
+    submitter_user_id: Option<Uuid>,
+    submitter_username: Option<String>,
+    owner_team_id: Uuid,
+    owner_team_name: String,
+    first_submission_id: Option<i64>,
+    flag_issued_at: DateTime<Utc>,
+    first_submitted_at: DateTime<Utc>,
+    last_submitted_at: DateTime<Utc>,
+    occurrence_count: i32,
+    status: String,
+    review_note: String,
+    reviewed_by: Option<Uuid>,
+    reviewed_by_username: Option<String>,
+    reviewed_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize, FromRow)]
 struct AdminInstanceItem {
     id: Uuid,
@@ -631,8 +1090,8 @@ struct AdminInstanceItem {
     contest_title: String,
     challenge_id: Uuid,
     challenge_title: String,
-    team_id: Uuid,
-    team_name: String,
+    team_id: Option<Uuid>,
+    team_name: Option<String>,
     status: String,
     subnet: String,
     compose_project_name: String,
@@ -701,6 +1160,60 @@ struct AdminAuditLogItem {
     created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Serialize, FromRow)]
+struct AdminSubmissionItem {
+    id: i64,
+    contest_id: Uuid,
+    contest_title: String,
+    challenge_id: Uuid,
+    challenge_title: String,
+    team_id: Uuid,
+    team_name: String,
+    user_id: Uuid,
+    username: String,
+    submitted_flag: String,
+    verdict: String,
+    score_awarded: i32,
+    blood_bonus: i32,
+    penalty_points: i32,
+    stage_key: Option<String>,
+    judger_message: String,
+    submitted_at: DateTime<Utc>,
+    judged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct AdminSubmissionTotals {
+    total: i64,
+    accepted: i64,
+    wrong: i64,
+    invalid: i64,
+    rate_limited: i64,
+    error: i64,
+    distinct_teams: i64,
+    distinct_users: i64,
+    penalty_points: i64,
+    first_submitted_at: Option<DateTime<Utc>>,
+    last_submitted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct AdminSubmissionChallengeSummary {
+    challenge_id: Uuid,
+    challenge_title: String,
+    total: i64,
+    accepted: i64,
+    wrong: i64,
+    solved_teams: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct AdminSubmissionSummaryResponse {
+    #[serde(flatten)]
+    totals: AdminSubmissionTotals,
+    challenges: Vec<AdminSubmissionChallengeSummary>,
+}
+
 #[derive(Debug, Serialize, FromRow)]
 struct AdminRuntimeAlertItem {
     id: Uuid,
@@ -758,6 +1271,8 @@ struct AdminContestRegistrationItem {
     team_id: Uuid,
     team_name: String,
     status: String,
+    division_id: Option<Uuid>,
+    division_name: Option<String>,
     requested_by: Option<Uuid>,
     requested_by_username: Option<String>,
     requested_at: DateTime<Utc>,
@@ -776,8 +1291,8 @@ struct AdminRuntimeInstanceAlertItem {
     contest_title: String,
     challenge_id: Uuid,
     challenge_title: String,
-    team_id: Uuid,
-    team_name: String,
+    team_id: Option<Uuid>,
+    team_name: Option<String>,
     status: String,
     expires_at: Option<DateTime<Utc>>,
     last_heartbeat_at: Option<DateTime<Utc>>,
@@ -837,14 +1352,21 @@ struct RuntimeAlertSignalInstanceRow {
     contest_title: String,
     challenge_id: Uuid,
     challenge_title: String,
-    team_id: Uuid,
-    team_name: String,
+    team_id: Option<Uuid>,
+    team_name: Option<String>,
     status: String,
     expires_at: Option<DateTime<Utc>>,
     last_heartbeat_at: Option<DateTime<Utc>>,
     updated_at: DateTime<Utc>,
 }
 
+impl RuntimeAlertSignalInstanceRow {
+    /// Shared king-of-the-hill deployments have no team.
+    fn team_label(&self) -> &str {
+        self.team_name.as_deref().unwrap_or("共享实例")
+    }
+}
+
 #[derive(Debug)]
 struct RuntimeAlertCandidate {
     alert_type: String,
@@ -967,6 +1489,10 @@ pub fn router() -> Router<Arc<AppState>> {
             "/admin/challenges/{challenge_id}/rollback",
             post(rollback_challenge_version),
         )
+        .route(
+            "/admin/challenges/{challenge_id}/webhook-verifier/test",
+            post(test_challenge_webhook_verifier),
+        )
         .route(
             "/admin/challenges/{challenge_id}/attachments",
             get(list_challenge_attachments)
@@ -979,6 +1505,14 @@ pub fn router() -> Router<Arc<AppState>> {
             "/admin/challenges/{challenge_id}/attachments/{attachment_id}",
             axum::routing::delete(delete_challenge_attachment),
         )
+        .route(
+            "/admin/challenges/{challenge_id}/hints",
+            get(list_challenge_hints).post(create_challenge_hint),
+        )
+        .route(
+            "/admin/challenges/{challenge_id}/hints/{hint_id}",
+            patch(update_challenge_hint).delete(delete_challenge_hint),
+        )
         .route("/admin/contests", get(list_contests).post(create_contest))
         .route(
             "/admin/contests/{contest_id}",
@@ -992,6 +1526,46 @@ pub fn router() -> Router<Arc<AppState>> {
             "/admin/contests/{contest_id}/status",
             patch(update_contest_status),
         )
+        .route(
+            "/admin/contests/{contest_id}/scoreboard/reveal",
+            post(reveal_contest_scoreboard),
+        )
+        .route(
+            "/admin/contests/{contest_id}/scoring/preview",
+            post(preview_contest_scoring),
+        )
+        .route(
+            "/admin/contests/{contest_id}/rejudge",
+            post(rejudge_contest_submissions),
+        )
+        .route(
+            "/admin/contests/{contest_id}/attack-defense/rounds",
+            get(list_attack_defense_rounds).post(advance_attack_defense_round),
+        )
+        .route(
+            "/admin/contests/{contest_id}/attack-defense/deploy",
+            post(deploy_attack_defense_services),
+        )
+        .route(
+            "/admin/contests/{contest_id}/koth/ticks",
+            get(list_koth_ticks),
+        )
+        .route(
+            "/admin/contests/{contest_id}/koth/tokens/rotate",
+            post(rotate_koth_tokens),
+        )
+        .route(
+            "/admin/contests/{contest_id}/koth/{challenge_id}/deploy",
+            post(deploy_koth_hill),
+        )
+        .route(
+            "/admin/contests/{contest_id}/score-adjustments",
+            get(list_contest_score_adjustments).post(create_contest_score_adjustment),
+        )
+        .route(
+            "/admin/contests/{contest_id}/score-adjustments/{adjustment_id}",
+            delete(delete_contest_score_adjustment),
+        )
         .route(
             "/admin/contests/{contest_id}/challenges",
             get(list_contest_challenges).post(upsert_contest_challenge),
@@ -1000,6 +1574,18 @@ pub fn router() -> Router<Arc<AppState>> {
             "/admin/contests/{contest_id}/challenges/{challenge_id}",
             patch(update_contest_challenge).delete(remove_contest_challenge),
         )
+        .route(
+            "/admin/contests/{contest_id}/challenges/{challenge_id}/unlock-rules",
+            put(update_contest_challenge_unlock_rules),
+        )
+        .route(
+            "/admin/contests/{contest_id}/challenges/{challenge_id}/rate-limit",
+            put(update_contest_challenge_rate_limit),
+        )
+        .route(
+            "/admin/contests/{contest_id}/unlock-graph",
+            get(get_contest_unlock_graph),
+        )
         .route(
             "/admin/contests/{contest_id}/announcements",
             get(list_contest_announcements).post(create_contest_announcement),
@@ -1016,12 +1602,38 @@ pub fn router() -> Router<Arc<AppState>> {
             "/admin/contests/{contest_id}/registrations/{registration_id}",
             patch(update_contest_registration),
         )
-        .route("/admin/instances", get(list_instances))
+        .route(
+            "/admin/contests/{contest_id}/divisions",
+            get(list_contest_divisions_admin).post(create_contest_division),
+        )
+        .route(
+            "/admin/contests/{contest_id}/divisions/{division_id}",
+            patch(update_contest_division).delete(delete_contest_division),
+        )
+        .route(
+            "/admin/contests/{contest_id}/hint-unlocks",
+            get(list_contest_hint_unlocks),
+        )
+        .route(
+            "/admin/contests/{contest_id}/flag-sharing-incidents",
+            get(list_flag_sharing_incidents),
+        )
+        .route(
+            "/admin/contests/{contest_id}/flag-sharing-incidents/{incident_id}",
+            patch(update_flag_sharing_incident),
+        )
+        .route("/admin/instances", get(list_instances))
         .route(
             "/admin/instances/{instance_id}/runtime-metrics",
             get(get_instance_runtime_metrics),
         )
         .route("/admin/audit-logs", get(list_audit_logs))
+        .route("/admin/submissions", get(list_admin_submissions))
+        .route(
+            "/admin/submissions/summary",
+            get(get_admin_submission_summary),
+        )
+        .route("/admin/submissions/export", get(export_admin_submissions))
         .route("/admin/runtime/alerts", get(list_runtime_alerts))
         .route("/admin/runtime/alerts/scan", post(scan_runtime_alerts))
         .route(
@@ -1531,6 +2143,7 @@ async fn delete_user_account(
     let deleted_email = format!("deleted+{}@deleted.local", user_simple);
     let deleted_password_hash = hash_password(Uuid::new_v4().to_string().as_str())?;
 
+    let mut tx = state.db.begin().await.map_err(AppError::internal)?;
     sqlx::query(
         "UPDATE users
          SET username = $2,
@@ -1545,9 +2158,11 @@ async fn delete_user_account(
     .bind(&deleted_username)
     .bind(&deleted_email)
     .bind(&deleted_password_hash)
-    .execute(&state.db)
+    .execute(&mut *tx)
     .await
     .map_err(AppError::internal)?;
+    sync_solo_team_name(&mut tx, user_id).await?;
+    tx.commit().await.map_err(AppError::internal)?;
 
     auth::revoke_all_user_sessions(state.as_ref(), user_id).await?;
 
@@ -1775,6 +2390,12 @@ async fn list_challenges(
                 status,
                 is_visible,
                 tags,
+                ARRAY(
+                    SELECT h.content
+                    FROM challenge_hints h
+                    WHERE h.challenge_id = challenges.id
+                    ORDER BY h.sort_order ASC, h.created_at ASC
+                ) AS hints,
                 writeup_visibility,
                 current_version,
                 created_at,
@@ -1810,11 +2431,18 @@ async fn get_challenge_detail(
                 flag_mode,
                 status,
                 flag_hash,
+                flag_rules,
+                stages,
                 compose_template,
                 metadata,
                 is_visible,
                 tags,
-                hints,
+                ARRAY(
+                    SELECT h.content
+                    FROM challenge_hints h
+                    WHERE h.challenge_id = challenges.id
+                    ORDER BY h.sort_order ASC, h.created_at ASC
+                ) AS hints,
                 writeup_visibility,
                 writeup_content,
                 current_version,
@@ -2574,6 +3202,10 @@ async fn create_challenge(
 
     let description = req.description.unwrap_or_default();
     let flag_hash = req.flag_hash.unwrap_or_default();
+    let flag_rules = normalize_flag_rules(req.flag_rules.unwrap_or_default())
+        .map_err(AppError::BadRequest)?;
+    let stages = normalize_challenge_stages(req.stages.unwrap_or_default())
+        .map_err(AppError::BadRequest)?;
     let compose_template = req.compose_template.and_then(|value| {
         let trimmed = value.trim().to_string();
         if trimmed.is_empty() {
@@ -2618,7 +3250,7 @@ async fn create_challenge(
         (None, None) => ("draft".to_string(), false),
     };
     let tags = normalize_tags(req.tags.unwrap_or_default())?;
-    let hints = normalize_hints(req.hints.unwrap_or_default())?;
+    let hint_texts = normalize_hint_texts(req.hints.unwrap_or_default())?;
     let writeup_visibility = normalize_with_allowed(
         req.writeup_visibility.as_deref().unwrap_or("hidden"),
         WRITEUP_VISIBILITY_ALLOWED,
@@ -2652,16 +3284,17 @@ async fn create_challenge(
             flag_mode,
             status,
             flag_hash,
+            flag_rules,
+            stages,
             compose_template,
             metadata,
             is_visible,
             tags,
-            hints,
             writeup_visibility,
             writeup_content,
             created_by
          )
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
          RETURNING id,
                    title,
                    slug,
@@ -2675,11 +3308,12 @@ async fn create_challenge(
                    flag_mode,
                    status,
                    flag_hash,
+                   flag_rules,
+                   stages,
                    compose_template,
                    metadata,
                    is_visible,
                    tags,
-                   hints,
                    writeup_visibility,
                    writeup_content,
                    current_version,
@@ -2698,11 +3332,12 @@ async fn create_challenge(
     .bind(flag_mode)
     .bind(status)
     .bind(flag_hash)
+    .bind(flag_rules_to_value(&flag_rules))
+    .bind(challenge_stages_to_value(&stages))
     .bind(compose_template)
     .bind(metadata)
     .bind(is_visible)
     .bind(tags)
-    .bind(hints)
     .bind(writeup_visibility)
     .bind(writeup_content)
     .bind(current_user.user_id)
@@ -2716,7 +3351,17 @@ async fn create_challenge(
         }
     })?;
 
-    let snapshot = challenge_snapshot_to_value(&row);
+    let hints = hint_texts
+        .into_iter()
+        .map(|content| ChallengeSnapshotHint {
+            content,
+            cost: 0,
+            release_at: None,
+        })
+        .collect::<Vec<_>>();
+    replace_challenge_hints(&mut tx, row.id, &hints, current_user.user_id).await?;
+
+    let snapshot = challenge_snapshot_to_value(&row, &hints);
     sqlx::query(
         "INSERT INTO challenge_versions (
             challenge_id,
@@ -2737,7 +3382,7 @@ async fn create_challenge(
     .map_err(AppError::internal)?;
 
     tx.commit().await.map_err(AppError::internal)?;
-    let item = challenge_item_from_snapshot_row(&row);
+    let item = challenge_item_from_snapshot_row(&row, &hints);
 
     record_audit_log(
         state.as_ref(),
@@ -2808,7 +3453,19 @@ async fn update_challenge(
         })
         .transpose()?;
     let normalized_tags = req.tags.map(normalize_tags).transpose()?;
-    let normalized_hints = req.hints.map(normalize_hints).transpose()?;
+    let normalized_flag_rules = req
+        .flag_rules
+        .map(normalize_flag_rules)
+        .transpose()
+        .map_err(AppError::BadRequest)?
+        .map(|rules| flag_rules_to_value(&rules));
+    let normalized_stages = req
+        .stages
+        .map(normalize_challenge_stages)
+        .transpose()
+        .map_err(AppError::BadRequest)?
+        .map(|stages| challenge_stages_to_value(&stages));
+    let normalized_hint_texts = req.hints.map(normalize_hint_texts).transpose()?;
     let change_note = req
         .change_note
         .as_deref()
@@ -2901,10 +3558,11 @@ async fn update_challenge(
               metadata = COALESCE($12, metadata),
               is_visible = COALESCE($13, is_visible),
               tags = COALESCE($14, tags),
-              hints = COALESCE($15, hints),
-              writeup_visibility = COALESCE($16, writeup_visibility),
-              writeup_content = COALESCE($17, writeup_content),
-              status = COALESCE($18, status),
+              writeup_visibility = COALESCE($15, writeup_visibility),
+              writeup_content = COALESCE($16, writeup_content),
+              status = COALESCE($17, status),
+              flag_rules = COALESCE($18, flag_rules),
+              stages = COALESCE($19, stages),
               current_version = current_version + 1,
               updated_at = NOW()
          WHERE id = $1
@@ -2921,11 +3579,12 @@ async fn update_challenge(
                    flag_mode,
                    status,
                    flag_hash,
+                   flag_rules,
+                   stages,
                    compose_template,
                    metadata,
                    is_visible,
                    tags,
-                   hints,
                    writeup_visibility,
                    writeup_content,
                    current_version,
@@ -2946,10 +3605,11 @@ async fn update_challenge(
     .bind(req.metadata)
     .bind(resolved_is_visible)
     .bind(normalized_tags)
-    .bind(normalized_hints)
     .bind(normalized_writeup_visibility)
     .bind(req.writeup_content)
     .bind(resolved_status)
+    .bind(normalized_flag_rules)
+    .bind(normalized_stages)
     .fetch_optional(&mut *tx)
     .await
     .map_err(|err| {
@@ -2961,7 +3621,29 @@ async fn update_challenge(
     })?
     .ok_or(AppError::BadRequest("challenge not found".to_string()))?;
 
-    let snapshot = challenge_snapshot_to_value(&row);
+    let mut hints = load_challenge_snapshot_hints(&mut tx, row.id).await?;
+    if let Some(hint_texts) = normalized_hint_texts {
+        // Hints keep their cost and release time by position, so saving an
+        // unchanged list is a no-op.
+        hints = hint_texts
+            .into_iter()
+            .enumerate()
+            .map(|(index, content)| match hints.get(index) {
+                Some(existing) => ChallengeSnapshotHint {
+                    content,
+                    ..existing.clone()
+                },
+                None => ChallengeSnapshotHint {
+                    content,
+                    cost: 0,
+                    release_at: None,
+                },
+            })
+            .collect();
+        replace_challenge_hints(&mut tx, row.id, &hints, current_user.user_id).await?;
+    }
+
+    let snapshot = challenge_snapshot_to_value(&row, &hints);
     sqlx::query(
         "INSERT INTO challenge_versions (
             challenge_id,
@@ -2982,7 +3664,7 @@ async fn update_challenge(
     .map_err(AppError::internal)?;
 
     tx.commit().await.map_err(AppError::internal)?;
-    let item = challenge_item_from_snapshot_row(&row);
+    let item = challenge_item_from_snapshot_row(&row, &hints);
 
     record_audit_log(
         state.as_ref(),
@@ -3006,6 +3688,8 @@ async fn update_challenge(
     )
     .await;
 
+    invalidate_challenge_scoreboards(state.as_ref(), item.id).await;
+
     Ok(Json(item))
 }
 
@@ -3179,10 +3863,11 @@ async fn rollback_challenge_version(
              metadata = $14,
              is_visible = $15,
              tags = $16,
-             hints = $17,
-             writeup_visibility = $18,
-             writeup_content = $19,
-             status = $20,
+             writeup_visibility = $17,
+             writeup_content = $18,
+             status = $19,
+             flag_rules = $20,
+             stages = $21,
              current_version = current_version + 1,
              updated_at = NOW()
          WHERE id = $1
@@ -3199,11 +3884,12 @@ async fn rollback_challenge_version(
                    flag_mode,
                    status,
                    flag_hash,
+                   flag_rules,
+                   stages,
                    compose_template,
                    metadata,
                    is_visible,
                    tags,
-                   hints,
                    writeup_visibility,
                    writeup_content,
                    current_version,
@@ -3226,10 +3912,11 @@ async fn rollback_challenge_version(
     .bind(target_snapshot.metadata)
     .bind(rollback_visible)
     .bind(target_snapshot.tags)
-    .bind(target_snapshot.hints)
     .bind(target_snapshot.writeup_visibility)
     .bind(target_snapshot.writeup_content)
     .bind(rollback_status)
+    .bind(flag_rules_to_value(&target_snapshot.flag_rules))
+    .bind(challenge_stages_to_value(&target_snapshot.stages))
     .fetch_one(&mut *tx)
     .await
     .map_err(|err| {
@@ -3240,7 +3927,16 @@ async fn rollback_challenge_version(
         }
     })?;
 
-    let snapshot = challenge_snapshot_to_value(&row);
+    let hints = match target_snapshot.hints {
+        Some(mut hints) => {
+            hints.truncate(MAX_CHALLENGE_HINTS as usize);
+            replace_challenge_hints(&mut tx, row.id, &hints, current_user.user_id).await?;
+            hints
+        }
+        None => load_challenge_snapshot_hints(&mut tx, row.id).await?,
+    };
+
+    let snapshot = challenge_snapshot_to_value(&row, &hints);
     let change_note = req
         .change_note
         .as_deref()
@@ -3268,7 +3964,7 @@ async fn rollback_challenge_version(
     .map_err(AppError::internal)?;
 
     tx.commit().await.map_err(AppError::internal)?;
-    let item = challenge_item_from_snapshot_row(&row);
+    let item = challenge_item_from_snapshot_row(&row, &hints);
 
     record_audit_log(
         state.as_ref(),
@@ -3283,6 +3979,8 @@ async fn rollback_challenge_version(
     )
     .await;
 
+    invalidate_challenge_scoreboards(state.as_ref(), item.id).await;
+
     Ok(Json(item))
 }
 
@@ -3418,6 +4116,414 @@ async fn list_challenge_attachments(
     Ok(Json(rows))
 }
 
+async fn test_challenge_webhook_verifier(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(challenge_id): Path<Uuid>,
+    Json(req): Json<TestWebhookVerifierRequest>,
+) -> AppResult<Json<WebhookVerifierResult>> {
+    ensure_admin_or_judge(&current_user)?;
+
+    let flag = trim_required(&req.flag, "flag")?;
+    let metadata = match req.webhook_verifier {
+        Some(verifier) => json!({ "webhook_verifier": verifier }),
+        None => sqlx::query_scalar::<_, Value>("SELECT metadata FROM challenges WHERE id = $1")
+            .bind(challenge_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(AppError::internal)?
+            .ok_or(AppError::BadRequest("challenge not found".to_string()))?,
+    };
+    let config = parse_webhook_verifier_config(&metadata).map_err(AppError::BadRequest)?;
+
+    let result = run_webhook_verifier(
+        &config,
+        &WebhookVerifyRequest {
+            contest_id: req.contest_id.unwrap_or_else(Uuid::nil),
+            challenge_id,
+            team_id: req.team_id.unwrap_or_else(Uuid::nil),
+            flag: &flag,
+            dry_run: true,
+        },
+    )
+    .await;
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.challenge.webhook_verifier.test",
+        "challenge",
+        Some(challenge_id),
+        json!({
+            "url": config.url,
+            "verdict": result.verdict,
+            "http_status": result.http_status,
+            "elapsed_ms": result.elapsed_ms
+        }),
+    )
+    .await;
+
+    Ok(Json(result))
+}
+
+async fn list_challenge_hints(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(challenge_id): Path<Uuid>,
+) -> AppResult<Json<Vec<AdminChallengeHintItem>>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_challenge_exists(state.as_ref(), challenge_id).await?;
+
+    let rows = sqlx::query_as::<_, AdminChallengeHintItem>(
+        "SELECT h.id,
+                h.challenge_id,
+                h.sort_order,
+                h.content,
+                h.cost,
+                h.release_at,
+                (SELECT COUNT(*) FROM challenge_hint_unlocks u WHERE u.hint_id = h.id) AS unlock_count,
+                h.created_by,
+                h.created_at,
+                h.updated_at
+         FROM challenge_hints h
+         WHERE h.challenge_id = $1
+         ORDER BY h.sort_order ASC, h.created_at ASC",
+    )
+    .bind(challenge_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(Json(rows))
+}
+
+async fn create_challenge_hint(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(challenge_id): Path<Uuid>,
+    Json(req): Json<CreateChallengeHintRequest>,
+) -> AppResult<Json<AdminChallengeHintItem>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_challenge_exists(state.as_ref(), challenge_id).await?;
+
+    let content = normalize_hint_content(&req.content)?;
+    let cost = validate_hint_cost(req.cost.unwrap_or(0))?;
+
+    let hint_count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM challenge_hints WHERE challenge_id = $1",
+    )
+    .bind(challenge_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+    if hint_count >= MAX_CHALLENGE_HINTS {
+        return Err(AppError::BadRequest(format!(
+            "a challenge can have at most {} hints",
+            MAX_CHALLENGE_HINTS
+        )));
+    }
+
+    let hint_id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO challenge_hints (challenge_id, sort_order, content, cost, release_at, created_by)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING id",
+    )
+    .bind(challenge_id)
+    .bind(req.sort_order.unwrap_or(hint_count as i32))
+    .bind(content)
+    .bind(cost)
+    .bind(req.release_at)
+    .bind(current_user.user_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let item = load_challenge_hint_item(state.as_ref(), challenge_id, hint_id).await?;
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.challenge.hint.create",
+        "challenge_hint",
+        Some(item.id),
+        json!({
+            "challenge_id": item.challenge_id,
+            "cost": item.cost,
+            "release_at": item.release_at
+        }),
+    )
+    .await;
+
+    Ok(Json(item))
+}
+
+async fn update_challenge_hint(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path((challenge_id, hint_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateChallengeHintRequest>,
+) -> AppResult<Json<AdminChallengeHintItem>> {
+    ensure_admin_or_judge(&current_user)?;
+
+    let content = req
+        .content
+        .as_deref()
+        .map(normalize_hint_content)
+        .transpose()?;
+    let cost = req.cost.map(validate_hint_cost).transpose()?;
+    let clear_release_at = req.clear_release_at.unwrap_or(false);
+
+    if content.is_none()
+        && cost.is_none()
+        && req.release_at.is_none()
+        && !clear_release_at
+        && req.sort_order.is_none()
+    {
+        return Err(AppError::BadRequest(
+            "at least one field is required for update".to_string(),
+        ));
+    }
+
+    let updated = sqlx::query(
+        "UPDATE challenge_hints
+         SET content = COALESCE($3, content),
+             cost = COALESCE($4, cost),
+             release_at = CASE WHEN $6 THEN NULL ELSE COALESCE($5, release_at) END,
+             sort_order = COALESCE($7, sort_order),
+             updated_at = NOW()
+         WHERE challenge_id = $1
+           AND id = $2",
+    )
+    .bind(challenge_id)
+    .bind(hint_id)
+    .bind(content)
+    .bind(cost)
+    .bind(req.release_at)
+    .bind(clear_release_at)
+    .bind(req.sort_order)
+    .execute(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    if updated.rows_affected() == 0 {
+        return Err(AppError::BadRequest("challenge hint not found".to_string()));
+    }
+
+    let item = load_challenge_hint_item(state.as_ref(), challenge_id, hint_id).await?;
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.challenge.hint.update",
+        "challenge_hint",
+        Some(item.id),
+        json!({
+            "challenge_id": item.challenge_id,
+            "cost": item.cost,
+            "release_at": item.release_at,
+            "sort_order": item.sort_order
+        }),
+    )
+    .await;
+
+    Ok(Json(item))
+}
+
+async fn delete_challenge_hint(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path((challenge_id, hint_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<StatusCode> {
+    ensure_admin_or_judge(&current_user)?;
+
+    let deleted = sqlx::query(
+        "DELETE FROM challenge_hints
+         WHERE challenge_id = $1
+           AND id = $2",
+    )
+    .bind(challenge_id)
+    .bind(hint_id)
+    .execute(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::BadRequest("challenge hint not found".to_string()));
+    }
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.challenge.hint.delete",
+        "challenge_hint",
+        Some(hint_id),
+        json!({
+            "challenge_id": challenge_id
+        }),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn load_challenge_hint_item(
+    state: &AppState,
+    challenge_id: Uuid,
+    hint_id: Uuid,
+) -> AppResult<AdminChallengeHintItem> {
+    sqlx::query_as::<_, AdminChallengeHintItem>(
+        "SELECT h.id,
+                h.challenge_id,
+                h.sort_order,
+                h.content,
+                h.cost,
+                h.release_at,
+                (SELECT COUNT(*) FROM challenge_hint_unlocks u WHERE u.hint_id = h.id) AS unlock_count,
+                h.created_by,
+                h.created_at,
+                h.updated_at
+         FROM challenge_hints h
+         WHERE h.challenge_id = $1
+           AND h.id = $2
+         LIMIT 1",
+    )
+    .bind(challenge_id)
+    .bind(hint_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest("challenge hint not found".to_string()))
+}
+
+fn normalize_hint_content(content: &str) -> AppResult<String> {
+    let content = trim_required(content, "content")?;
+    if content.chars().count() > MAX_CHALLENGE_HINT_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "hint content must be at most {} characters",
+            MAX_CHALLENGE_HINT_CHARS
+        )));
+    }
+    Ok(content.to_string())
+}
+
+/// Plain hint texts accepted by the challenge create and update requests.
+fn normalize_hint_texts(hints: Vec<String>) -> AppResult<Vec<String>> {
+    if hints.len() > MAX_CHALLENGE_HINTS as usize {
+        return Err(AppError::BadRequest(format!(
+            "hints must be at most {} items",
+            MAX_CHALLENGE_HINTS
+        )));
+    }
+
+    let mut out: Vec<String> = Vec::new();
+    for hint in hints {
+        if hint.trim().is_empty() {
+            continue;
+        }
+        let normalized = normalize_hint_content(&hint)?;
+        if !out.contains(&normalized) {
+            out.push(normalized);
+        }
+    }
+
+    Ok(out)
+}
+
+async fn load_challenge_snapshot_hints(
+    conn: &mut PgConnection,
+    challenge_id: Uuid,
+) -> AppResult<Vec<ChallengeSnapshotHint>> {
+    sqlx::query_as::<_, ChallengeSnapshotHint>(
+        "SELECT content, cost, release_at
+         FROM challenge_hints
+         WHERE challenge_id = $1
+         ORDER BY sort_order ASC, created_at ASC",
+    )
+    .bind(challenge_id)
+    .fetch_all(conn)
+    .await
+    .map_err(AppError::internal)
+}
+
+/// Makes the challenge's hints match `hints` position by position. Existing
+/// hints are updated in place so their unlocks stay attached; surplus hints
+/// are deleted like `DELETE .../hints/{hint_id}`, keeping past deductions.
+async fn replace_challenge_hints(
+    conn: &mut PgConnection,
+    challenge_id: Uuid,
+    hints: &[ChallengeSnapshotHint],
+    created_by: Uuid,
+) -> AppResult<()> {
+    let existing_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id
+         FROM challenge_hints
+         WHERE challenge_id = $1
+         ORDER BY sort_order ASC, created_at ASC",
+    )
+    .bind(challenge_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(AppError::internal)?;
+
+    for (index, hint) in hints.iter().enumerate() {
+        match existing_ids.get(index) {
+            Some(hint_id) => {
+                sqlx::query(
+                    "UPDATE challenge_hints
+                     SET sort_order = $2,
+                         content = $3,
+                         cost = $4,
+                         release_at = $5
+                     WHERE id = $1
+                       AND (sort_order, content, cost, release_at)
+                           IS DISTINCT FROM ($2, $3, $4, $5)",
+                )
+                .bind(hint_id)
+                .bind(index as i32)
+                .bind(&hint.content)
+                .bind(hint.cost)
+                .bind(hint.release_at)
+                .execute(&mut *conn)
+                .await
+                .map_err(AppError::internal)?;
+            }
+            None => {
+                sqlx::query(
+                    "INSERT INTO challenge_hints (challenge_id, sort_order, content, cost, release_at, created_by)
+                     VALUES ($1, $2, $3, $4, $5, $6)",
+                )
+                .bind(challenge_id)
+                .bind(index as i32)
+                .bind(&hint.content)
+                .bind(hint.cost)
+                .bind(hint.release_at)
+                .bind(created_by)
+                .execute(&mut *conn)
+                .await
+                .map_err(AppError::internal)?;
+            }
+        }
+    }
+
+    if existing_ids.len() > hints.len() {
+        sqlx::query("DELETE FROM challenge_hints WHERE id = ANY($1)")
+            .bind(&existing_ids[hints.len()..])
+            .execute(&mut *conn)
+            .await
+            .map_err(AppError::internal)?;
+    }
+
+    Ok(())
+}
+
+fn validate_hint_cost(cost: i32) -> AppResult<i32> {
+    if !(0..=MAX_CHALLENGE_HINT_COST).contains(&cost) {
+        return Err(AppError::BadRequest(format!(
+            "hint cost must be between 0 and {}",
+            MAX_CHALLENGE_HINT_COST
+        )));
+    }
+    Ok(cost)
+}
+
 async fn delete_challenge_attachment(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
@@ -3492,15 +4598,37 @@ async fn list_contests(
                 END AS poster_url,
                 visibility,
                 status,
+                contest_type,
                 scoring_mode,
+                scoring_formula,
                 dynamic_decay,
+                difficulty_scores,
                 first_blood_bonus_percent,
                 second_blood_bonus_percent,
                 third_blood_bonus_percent,
+                ad_round_seconds,
+                ad_flag_lifetime_rounds,
+                ad_attack_points,
+                ad_defense_points,
+                ad_sla_points,
+                koth_tick_seconds,
+                submission_rate_window_seconds,
+                submission_rate_user_max,
+                submission_rate_team_max,
+                wrong_backoff_threshold,
+                wrong_backoff_base_seconds,
+                wrong_backoff_max_seconds,
+                wrong_submission_penalty,
+                score_floor,
+                participation_mode,
                 registration_requires_approval,
+                public_scoreboard,
+                public_challenge_stats,
+                public_scoreboard_rate_limit,
                 start_at,
                 end_at,
                 freeze_at,
+                scoreboard_revealed_at,
                 created_at,
                 updated_at
          FROM contests
@@ -3540,12 +4668,19 @@ async fn create_contest(
         CONTEST_SCORING_MODE_ALLOWED,
         "scoring_mode",
     )?;
+    let scoring_formula = normalize_with_allowed(
+        req.scoring_formula.as_deref().unwrap_or("hyperbolic"),
+        CONTEST_SCORING_FORMULA_ALLOWED,
+        "scoring_formula",
+    )?;
     let dynamic_decay = req.dynamic_decay.unwrap_or(20);
     if !(1..=100000).contains(&dynamic_decay) {
         return Err(AppError::BadRequest(
             "dynamic_decay must be between 1 and 100000".to_string(),
         ));
     }
+    let difficulty_scores = req.difficulty_scores.unwrap_or_default();
+    difficulty_scores.validate().map_err(AppError::BadRequest)?;
     let first_blood_bonus_percent = validate_blood_bonus_percent(
         req.first_blood_bonus_percent.unwrap_or(10),
         "first_blood_bonus_percent",
@@ -3558,27 +4693,139 @@ async fn create_contest(
         req.third_blood_bonus_percent.unwrap_or(2),
         "third_blood_bonus_percent",
     )?;
-    let registration_requires_approval = req.registration_requires_approval.unwrap_or(true);
-
-    let row = sqlx::query_as::<_, AdminContestItem>(
-        "INSERT INTO contests (
-            title,
-            slug,
-            description,
+    let contest_type = normalize_with_allowed(
+        req.contest_type.as_deref().unwrap_or("jeopardy"),
+        CONTEST_TYPE_ALLOWED,
+        "contest_type",
+    )?;
+    let ad_round_seconds = validate_contest_int_range(
+        req.ad_round_seconds.unwrap_or(120),
+        30..=3600,
+        "ad_round_seconds",
+    )?;
+    let ad_flag_lifetime_rounds = validate_contest_int_range(
+        req.ad_flag_lifetime_rounds.unwrap_or(5),
+        1..=50,
+        "ad_flag_lifetime_rounds",
+    )?;
+    let ad_attack_points = validate_contest_int_range(
+        req.ad_attack_points.unwrap_or(10),
+        0..=10000,
+        "ad_attack_points",
+    )?;
+    let ad_defense_points = validate_contest_int_range(
+        req.ad_defense_points.unwrap_or(5),
+        0..=10000,
+        "ad_defense_points",
+    )?;
+    let ad_sla_points =
+        validate_contest_int_range(req.ad_sla_points.unwrap_or(5), 0..=10000, "ad_sla_points")?;
+    let koth_tick_seconds = validate_contest_int_range(
+        req.koth_tick_seconds.unwrap_or(60),
+        10..=3600,
+        "koth_tick_seconds",
+    )?;
+    let submission_rate_window_seconds = validate_contest_int_range(
+        req.submission_rate_window_seconds.unwrap_or(30),
+        5..=3600,
+        "submission_rate_window_seconds",
+    )?;
+    let submission_rate_user_max = validate_contest_int_range(
+        req.submission_rate_user_max.unwrap_or(10),
+        1..=1000,
+        "submission_rate_user_max",
+    )?;
+    let submission_rate_team_max = validate_contest_int_range(
+        req.submission_rate_team_max.unwrap_or(30),
+        1..=5000,
+        "submission_rate_team_max",
+    )?;
+    let wrong_backoff_threshold = validate_contest_int_range(
+        req.wrong_backoff_threshold.unwrap_or(0),
+        0..=100,
+        "wrong_backoff_threshold",
+    )?;
+    let wrong_backoff_base_seconds = validate_contest_int_range(
+        req.wrong_backoff_base_seconds.unwrap_or(5),
+        1..=3600,
+        "wrong_backoff_base_seconds",
+    )?;
+    let wrong_backoff_max_seconds = validate_contest_int_range(
+        req.wrong_backoff_max_seconds.unwrap_or(300),
+        1..=86400,
+        "wrong_backoff_max_seconds",
+    )?;
+    validate_wrong_backoff_bounds(wrong_backoff_base_seconds, wrong_backoff_max_seconds)?;
+    let wrong_submission_penalty = validate_contest_int_range(
+        req.wrong_submission_penalty.unwrap_or(0),
+        0..=100_000,
+        "wrong_submission_penalty",
+    )?;
+    let score_floor = validate_contest_int_range(
+        req.score_floor.unwrap_or(0),
+        -100_000..=100_000,
+        "score_floor",
+    )?;
+    let participation_mode = normalize_with_allowed(
+        req.participation_mode
+            .as_deref()
+            .unwrap_or(CONTEST_PARTICIPATION_TEAM),
+        CONTEST_PARTICIPATION_MODE_ALLOWED,
+        "participation_mode",
+    )?;
+    let registration_requires_approval = req.registration_requires_approval.unwrap_or(true);
+    let public_scoreboard = req.public_scoreboard.unwrap_or(false);
+    let public_challenge_stats = req.public_challenge_stats.unwrap_or(false);
+    let public_scoreboard_rate_limit = validate_contest_int_range(
+        req.public_scoreboard_rate_limit.unwrap_or(120),
+        10..=6000,
+        "public_scoreboard_rate_limit",
+    )?;
+
+    let row = sqlx::query_as::<_, AdminContestItem>(
+        "INSERT INTO contests (
+            title,
+            slug,
+            description,
             visibility,
             status,
+            contest_type,
             scoring_mode,
+            scoring_formula,
             dynamic_decay,
+            difficulty_scores,
             first_blood_bonus_percent,
             second_blood_bonus_percent,
             third_blood_bonus_percent,
+            ad_round_seconds,
+            ad_flag_lifetime_rounds,
+            ad_attack_points,
+            ad_defense_points,
+            ad_sla_points,
+            koth_tick_seconds,
+            submission_rate_window_seconds,
+            submission_rate_user_max,
+            submission_rate_team_max,
+            wrong_backoff_threshold,
+            wrong_backoff_base_seconds,
+            wrong_backoff_max_seconds,
+            wrong_submission_penalty,
+            score_floor,
+            participation_mode,
             registration_requires_approval,
             start_at,
             end_at,
             freeze_at,
-            created_by
+            created_by,
+            public_scoreboard,
+            public_challenge_stats,
+            public_scoreboard_rate_limit
+         )
+         VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
+            $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30,
+            $31, $32, $33, $34, $35, $36
          )
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
          RETURNING id,
                    title,
                    slug,
@@ -3589,15 +4836,37 @@ async fn create_contest(
                    END AS poster_url,
                    visibility,
                    status,
+                   contest_type,
                    scoring_mode,
+                   scoring_formula,
                    dynamic_decay,
+                   difficulty_scores,
                    first_blood_bonus_percent,
                    second_blood_bonus_percent,
                    third_blood_bonus_percent,
+                   ad_round_seconds,
+                   ad_flag_lifetime_rounds,
+                   ad_attack_points,
+                   ad_defense_points,
+                   ad_sla_points,
+                   koth_tick_seconds,
+                   submission_rate_window_seconds,
+                   submission_rate_user_max,
+                   submission_rate_team_max,
+                   wrong_backoff_threshold,
+                   wrong_backoff_base_seconds,
+                   wrong_backoff_max_seconds,
+                   wrong_submission_penalty,
+                   score_floor,
+                   participation_mode,
                    registration_requires_approval,
+                   public_scoreboard,
+                   public_challenge_stats,
+                   public_scoreboard_rate_limit,
                    start_at,
                    end_at,
                    freeze_at,
+                   scoreboard_revealed_at,
                    created_at,
                    updated_at",
     )
@@ -3606,16 +4875,37 @@ async fn create_contest(
     .bind(description)
     .bind(visibility)
     .bind(status)
+    .bind(contest_type)
     .bind(scoring_mode)
+    .bind(scoring_formula)
     .bind(dynamic_decay)
+    .bind(difficulty_scores.to_value())
     .bind(first_blood_bonus_percent)
     .bind(second_blood_bonus_percent)
     .bind(third_blood_bonus_percent)
+    .bind(ad_round_seconds)
+    .bind(ad_flag_lifetime_rounds)
+    .bind(ad_attack_points)
+    .bind(ad_defense_points)
+    .bind(ad_sla_points)
+    .bind(koth_tick_seconds)
+    .bind(submission_rate_window_seconds)
+    .bind(submission_rate_user_max)
+    .bind(submission_rate_team_max)
+    .bind(wrong_backoff_threshold)
+    .bind(wrong_backoff_base_seconds)
+    .bind(wrong_backoff_max_seconds)
+    .bind(wrong_submission_penalty)
+    .bind(score_floor)
+    .bind(&participation_mode)
     .bind(registration_requires_approval)
     .bind(req.start_at)
     .bind(req.end_at)
     .bind(req.freeze_at)
     .bind(current_user.user_id)
+    .bind(public_scoreboard)
+    .bind(public_challenge_stats)
+    .bind(public_scoreboard_rate_limit)
     .fetch_one(&state.db)
     .await
     .map_err(|err| {
@@ -3637,12 +4927,33 @@ async fn create_contest(
             "slug": &row.slug,
             "status": &row.status,
             "visibility": &row.visibility,
+            "contest_type": &row.contest_type,
             "scoring_mode": &row.scoring_mode,
+            "scoring_formula": &row.scoring_formula,
             "dynamic_decay": row.dynamic_decay,
+            "difficulty_scores": &row.difficulty_scores,
             "first_blood_bonus_percent": row.first_blood_bonus_percent,
             "second_blood_bonus_percent": row.second_blood_bonus_percent,
             "third_blood_bonus_percent": row.third_blood_bonus_percent,
+            "ad_round_seconds": row.ad_round_seconds,
+            "ad_flag_lifetime_rounds": row.ad_flag_lifetime_rounds,
+            "ad_attack_points": row.ad_attack_points,
+            "ad_defense_points": row.ad_defense_points,
+            "ad_sla_points": row.ad_sla_points,
+            "koth_tick_seconds": row.koth_tick_seconds,
+            "submission_rate_window_seconds": row.submission_rate_window_seconds,
+            "submission_rate_user_max": row.submission_rate_user_max,
+            "submission_rate_team_max": row.submission_rate_team_max,
+            "wrong_backoff_threshold": row.wrong_backoff_threshold,
+            "wrong_backoff_base_seconds": row.wrong_backoff_base_seconds,
+            "wrong_backoff_max_seconds": row.wrong_backoff_max_seconds,
+            "wrong_submission_penalty": row.wrong_submission_penalty,
+            "score_floor": row.score_floor,
+            "participation_mode": &row.participation_mode,
             "registration_requires_approval": row.registration_requires_approval,
+            "public_scoreboard": row.public_scoreboard,
+            "public_challenge_stats": row.public_challenge_stats,
+            "public_scoreboard_rate_limit": row.public_scoreboard_rate_limit,
             "start_at": row.start_at,
             "end_at": row.end_at,
             "freeze_at": row.freeze_at
@@ -3672,15 +4983,37 @@ async fn update_contest(
                 END AS poster_url,
                 visibility,
                 status,
+                contest_type,
                 scoring_mode,
+                scoring_formula,
                 dynamic_decay,
+                difficulty_scores,
                 first_blood_bonus_percent,
                 second_blood_bonus_percent,
                 third_blood_bonus_percent,
+                ad_round_seconds,
+                ad_flag_lifetime_rounds,
+                ad_attack_points,
+                ad_defense_points,
+                ad_sla_points,
+                koth_tick_seconds,
+                submission_rate_window_seconds,
+                submission_rate_user_max,
+                submission_rate_team_max,
+                wrong_backoff_threshold,
+                wrong_backoff_base_seconds,
+                wrong_backoff_max_seconds,
+                wrong_submission_penalty,
+                score_floor,
+                participation_mode,
                 registration_requires_approval,
+                public_scoreboard,
+                public_challenge_stats,
+                public_scoreboard_rate_limit,
                 start_at,
                 end_at,
                 freeze_at,
+                scoreboard_revealed_at,
                 created_at,
                 updated_at
          FROM contests
@@ -3716,12 +5049,25 @@ async fn update_contest(
         }
         None => existing.scoring_mode,
     };
+    let scoring_formula = match req.scoring_formula {
+        Some(value) => {
+            normalize_with_allowed(&value, CONTEST_SCORING_FORMULA_ALLOWED, "scoring_formula")?
+        }
+        None => existing.scoring_formula,
+    };
     let dynamic_decay = req.dynamic_decay.unwrap_or(existing.dynamic_decay);
     if !(1..=100000).contains(&dynamic_decay) {
         return Err(AppError::BadRequest(
             "dynamic_decay must be between 1 and 100000".to_string(),
         ));
     }
+    let difficulty_scores = match req.difficulty_scores {
+        Some(value) => {
+            value.validate().map_err(AppError::BadRequest)?;
+            value.to_value()
+        }
+        None => existing.difficulty_scores,
+    };
     let first_blood_bonus_percent = validate_blood_bonus_percent(
         req.first_blood_bonus_percent
             .unwrap_or(existing.first_blood_bonus_percent),
@@ -3737,9 +5083,113 @@ async fn update_contest(
             .unwrap_or(existing.third_blood_bonus_percent),
         "third_blood_bonus_percent",
     )?;
+    let contest_type = match req.contest_type {
+        Some(value) => normalize_with_allowed(&value, CONTEST_TYPE_ALLOWED, "contest_type")?,
+        None => existing.contest_type,
+    };
+    let ad_round_seconds = validate_contest_int_range(
+        req.ad_round_seconds.unwrap_or(existing.ad_round_seconds),
+        30..=3600,
+        "ad_round_seconds",
+    )?;
+    let ad_flag_lifetime_rounds = validate_contest_int_range(
+        req.ad_flag_lifetime_rounds
+            .unwrap_or(existing.ad_flag_lifetime_rounds),
+        1..=50,
+        "ad_flag_lifetime_rounds",
+    )?;
+    let ad_attack_points = validate_contest_int_range(
+        req.ad_attack_points.unwrap_or(existing.ad_attack_points),
+        0..=10000,
+        "ad_attack_points",
+    )?;
+    let ad_defense_points = validate_contest_int_range(
+        req.ad_defense_points.unwrap_or(existing.ad_defense_points),
+        0..=10000,
+        "ad_defense_points",
+    )?;
+    let ad_sla_points = validate_contest_int_range(
+        req.ad_sla_points.unwrap_or(existing.ad_sla_points),
+        0..=10000,
+        "ad_sla_points",
+    )?;
+    let koth_tick_seconds = validate_contest_int_range(
+        req.koth_tick_seconds.unwrap_or(existing.koth_tick_seconds),
+        10..=3600,
+        "koth_tick_seconds",
+    )?;
+    let submission_rate_window_seconds = validate_contest_int_range(
+        req.submission_rate_window_seconds
+            .unwrap_or(existing.submission_rate_window_seconds),
+        5..=3600,
+        "submission_rate_window_seconds",
+    )?;
+    let submission_rate_user_max = validate_contest_int_range(
+        req.submission_rate_user_max
+            .unwrap_or(existing.submission_rate_user_max),
+        1..=1000,
+        "submission_rate_user_max",
+    )?;
+    let submission_rate_team_max = validate_contest_int_range(
+        req.submission_rate_team_max
+            .unwrap_or(existing.submission_rate_team_max),
+        1..=5000,
+        "submission_rate_team_max",
+    )?;
+    let wrong_backoff_threshold = validate_contest_int_range(
+        req.wrong_backoff_threshold
+            .unwrap_or(existing.wrong_backoff_threshold),
+        0..=100,
+        "wrong_backoff_threshold",
+    )?;
+    let wrong_backoff_base_seconds = validate_contest_int_range(
+        req.wrong_backoff_base_seconds
+            .unwrap_or(existing.wrong_backoff_base_seconds),
+        1..=3600,
+        "wrong_backoff_base_seconds",
+    )?;
+    let wrong_backoff_max_seconds = validate_contest_int_range(
+        req.wrong_backoff_max_seconds
+            .unwrap_or(existing.wrong_backoff_max_seconds),
+        1..=86400,
+        "wrong_backoff_max_seconds",
+    )?;
+    validate_wrong_backoff_bounds(wrong_backoff_base_seconds, wrong_backoff_max_seconds)?;
+    let wrong_submission_penalty = validate_contest_int_range(
+        req.wrong_submission_penalty
+            .unwrap_or(existing.wrong_submission_penalty),
+        0..=100_000,
+        "wrong_submission_penalty",
+    )?;
+    let score_floor = validate_contest_int_range(
+        req.score_floor.unwrap_or(existing.score_floor),
+        -100_000..=100_000,
+        "score_floor",
+    )?;
+    let participation_mode = match req.participation_mode {
+        Some(value) => normalize_with_allowed(
+            &value,
+            CONTEST_PARTICIPATION_MODE_ALLOWED,
+            "participation_mode",
+        )?,
+        None => existing.participation_mode.clone(),
+    };
+    if participation_mode != existing.participation_mode {
+        ensure_contest_has_no_registrations(state.as_ref(), contest_id).await?;
+    }
     let registration_requires_approval = req
         .registration_requires_approval
         .unwrap_or(existing.registration_requires_approval);
+    let public_scoreboard = req.public_scoreboard.unwrap_or(existing.public_scoreboard);
+    let public_challenge_stats = req
+        .public_challenge_stats
+        .unwrap_or(existing.public_challenge_stats);
+    let public_scoreboard_rate_limit = validate_contest_int_range(
+        req.public_scoreboard_rate_limit
+            .unwrap_or(existing.public_scoreboard_rate_limit),
+        10..=6000,
+        "public_scoreboard_rate_limit",
+    )?;
 
     let start_at = req.start_at.unwrap_or(existing.start_at);
     let end_at = req.end_at.unwrap_or(existing.end_at);
@@ -3758,15 +5208,40 @@ async fn update_contest(
              description = $4,
              visibility = $5,
              status = $6,
-             scoring_mode = $7,
-             dynamic_decay = $8,
-             first_blood_bonus_percent = $9,
-             second_blood_bonus_percent = $10,
-             third_blood_bonus_percent = $11,
-             registration_requires_approval = $12,
-             start_at = $13,
-             end_at = $14,
-             freeze_at = $15,
+             contest_type = $7,
+             scoring_mode = $8,
+             scoring_formula = $9,
+             dynamic_decay = $10,
+             difficulty_scores = $11,
+             first_blood_bonus_percent = $12,
+             second_blood_bonus_percent = $13,
+             third_blood_bonus_percent = $14,
+             ad_round_seconds = $15,
+             ad_flag_lifetime_rounds = $16,
+             ad_attack_points = $17,
+             ad_defense_points = $18,
+             ad_sla_points = $19,
+             koth_tick_seconds = $20,
+             submission_rate_window_seconds = $21,
+             submission_rate_user_max = $22,
+             submission_rate_team_max = $23,
+             wrong_backoff_threshold = $24,
+             wrong_backoff_base_seconds = $25,
+             wrong_backoff_max_seconds = $26,
+             wrong_submission_penalty = $27,
+             score_floor = $28,
+             participation_mode = $29,
+             registration_requires_approval = $30,
+             public_scoreboard = $34,
+             public_challenge_stats = $35,
+             public_scoreboard_rate_limit = $36,
+             start_at = $31,
+             end_at = $32,
+             scoreboard_revealed_at = CASE
+                 WHEN freeze_at IS DISTINCT FROM $33 THEN NULL
+                 ELSE scoreboard_revealed_at
+             END,
+             freeze_at = $33,
              updated_at = NOW()
          WHERE id = $1
          RETURNING id,
@@ -3779,15 +5254,37 @@ async fn update_contest(
                    END AS poster_url,
                    visibility,
                    status,
+                   contest_type,
                    scoring_mode,
+                   scoring_formula,
                    dynamic_decay,
+                   difficulty_scores,
                    first_blood_bonus_percent,
                    second_blood_bonus_percent,
                    third_blood_bonus_percent,
+                   ad_round_seconds,
+                   ad_flag_lifetime_rounds,
+                   ad_attack_points,
+                   ad_defense_points,
+                   ad_sla_points,
+                   koth_tick_seconds,
+                   submission_rate_window_seconds,
+                   submission_rate_user_max,
+                   submission_rate_team_max,
+                   wrong_backoff_threshold,
+                   wrong_backoff_base_seconds,
+                   wrong_backoff_max_seconds,
+                   wrong_submission_penalty,
+                   score_floor,
+                   participation_mode,
                    registration_requires_approval,
+                   public_scoreboard,
+                   public_challenge_stats,
+                   public_scoreboard_rate_limit,
                    start_at,
                    end_at,
                    freeze_at,
+                   scoreboard_revealed_at,
                    created_at,
                    updated_at",
     )
@@ -3797,15 +5294,36 @@ async fn update_contest(
     .bind(description)
     .bind(visibility)
     .bind(status)
+    .bind(contest_type)
     .bind(scoring_mode)
+    .bind(scoring_formula)
     .bind(dynamic_decay)
+    .bind(difficulty_scores)
     .bind(first_blood_bonus_percent)
     .bind(second_blood_bonus_percent)
     .bind(third_blood_bonus_percent)
+    .bind(ad_round_seconds)
+    .bind(ad_flag_lifetime_rounds)
+    .bind(ad_attack_points)
+    .bind(ad_defense_points)
+    .bind(ad_sla_points)
+    .bind(koth_tick_seconds)
+    .bind(submission_rate_window_seconds)
+    .bind(submission_rate_user_max)
+    .bind(submission_rate_team_max)
+    .bind(wrong_backoff_threshold)
+    .bind(wrong_backoff_base_seconds)
+    .bind(wrong_backoff_max_seconds)
+    .bind(wrong_submission_penalty)
+    .bind(score_floor)
+    .bind(&participation_mode)
     .bind(registration_requires_approval)
     .bind(start_at)
     .bind(end_at)
     .bind(freeze_at)
+    .bind(public_scoreboard)
+    .bind(public_challenge_stats)
+    .bind(public_scoreboard_rate_limit)
     .fetch_one(&state.db)
     .await
     .map_err(|err| {
@@ -3827,12 +5345,33 @@ async fn update_contest(
             "slug": &row.slug,
             "status": &row.status,
             "visibility": &row.visibility,
+            "contest_type": &row.contest_type,
             "scoring_mode": &row.scoring_mode,
+            "scoring_formula": &row.scoring_formula,
             "dynamic_decay": row.dynamic_decay,
+            "difficulty_scores": &row.difficulty_scores,
             "first_blood_bonus_percent": row.first_blood_bonus_percent,
             "second_blood_bonus_percent": row.second_blood_bonus_percent,
             "third_blood_bonus_percent": row.third_blood_bonus_percent,
+            "ad_round_seconds": row.ad_round_seconds,
+            "ad_flag_lifetime_rounds": row.ad_flag_lifetime_rounds,
+            "ad_attack_points": row.ad_attack_points,
+            "ad_defense_points": row.ad_defense_points,
+            "ad_sla_points": row.ad_sla_points,
+            "koth_tick_seconds": row.koth_tick_seconds,
+            "submission_rate_window_seconds": row.submission_rate_window_seconds,
+            "submission_rate_user_max": row.submission_rate_user_max,
+            "submission_rate_team_max": row.submission_rate_team_max,
+            "wrong_backoff_threshold": row.wrong_backoff_threshold,
+            "wrong_backoff_base_seconds": row.wrong_backoff_base_seconds,
+            "wrong_backoff_max_seconds": row.wrong_backoff_max_seconds,
+            "wrong_submission_penalty": row.wrong_submission_penalty,
+            "score_floor": row.score_floor,
+            "participation_mode": &row.participation_mode,
             "registration_requires_approval": row.registration_requires_approval,
+            "public_scoreboard": row.public_scoreboard,
+            "public_challenge_stats": row.public_challenge_stats,
+            "public_scoreboard_rate_limit": row.public_scoreboard_rate_limit,
             "start_at": row.start_at,
             "end_at": row.end_at,
             "freeze_at": row.freeze_at
@@ -3840,6 +5379,8 @@ async fn update_contest(
     )
     .await;
 
+    invalidate_scoreboard_cache(state.as_ref(), contest_id).await;
+
     Ok(Json(row))
 }
 
@@ -3868,15 +5409,37 @@ async fn update_contest_status(
                    END AS poster_url,
                    visibility,
                    status,
+                   contest_type,
                    scoring_mode,
+                   scoring_formula,
                    dynamic_decay,
+                   difficulty_scores,
                    first_blood_bonus_percent,
                    second_blood_bonus_percent,
                    third_blood_bonus_percent,
+                   ad_round_seconds,
+                   ad_flag_lifetime_rounds,
+                   ad_attack_points,
+                   ad_defense_points,
+                   ad_sla_points,
+                   koth_tick_seconds,
+                   submission_rate_window_seconds,
+                   submission_rate_user_max,
+                   submission_rate_team_max,
+                   wrong_backoff_threshold,
+                   wrong_backoff_base_seconds,
+                   wrong_backoff_max_seconds,
+                   wrong_submission_penalty,
+                   score_floor,
+                   participation_mode,
                    registration_requires_approval,
+                   public_scoreboard,
+                   public_challenge_stats,
+                   public_scoreboard_rate_limit,
                    start_at,
                    end_at,
                    freeze_at,
+                   scoreboard_revealed_at,
                    created_at,
                    updated_at",
     )
@@ -3902,148 +5465,19 @@ async fn update_contest_status(
     Ok(Json(row))
 }
 
-async fn delete_contest(
-    State(state): State<Arc<AppState>>,
-    current_user: AuthenticatedUser,
-    Path(contest_id): Path<Uuid>,
-) -> AppResult<StatusCode> {
-    ensure_admin_or_judge(&current_user)?;
-    ensure_contest_exists(state.as_ref(), contest_id).await?;
-
-    let instance_cleanup =
-        instances::destroy_instances_for_contest(state.as_ref(), contest_id).await?;
-    if instance_cleanup.failed > 0 {
-        return Err(AppError::Conflict(format!(
-            "failed to destroy {} runtime instances, resolve runtime errors and retry",
-            instance_cleanup.failed
-        )));
-    }
-
-    let deleted = sqlx::query_as::<_, (Uuid, String, Option<String>)>(
-        "DELETE FROM contests
-         WHERE id = $1
-         RETURNING id, title, poster_storage_path",
-    )
-    .bind(contest_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(AppError::internal)?
-    .ok_or(AppError::BadRequest("contest not found".to_string()))?;
-
-    if let Some(path) = deleted.2.as_deref() {
-        let poster_path = PathBuf::from(path);
-        if let Err(err) = fs::remove_file(&poster_path).await {
-            if err.kind() != std::io::ErrorKind::NotFound {
-                return Err(AppError::internal(err));
-            }
-        }
-    }
-
-    let poster_dir = contest_posters_dir(state.as_ref(), contest_id);
-    if let Err(err) = fs::remove_dir_all(&poster_dir).await {
-        if err.kind() != std::io::ErrorKind::NotFound {
-            return Err(AppError::internal(err));
-        }
-    }
-
-    record_audit_log(
-        state.as_ref(),
-        &current_user,
-        "admin.contest.delete",
-        "contest",
-        Some(deleted.0),
-        json!({
-            "title": deleted.1,
-            "instance_cleanup": {
-                "scanned": instance_cleanup.scanned,
-                "destroyed": instance_cleanup.reaped,
-                "failed": instance_cleanup.failed,
-                "skipped": instance_cleanup.skipped
-            }
-        }),
-    )
-    .await;
-
-    Ok(StatusCode::NO_CONTENT)
-}
-
-async fn upload_contest_poster(
+async fn reveal_contest_scoreboard(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
     Path(contest_id): Path<Uuid>,
-    Json(req): Json<UploadContestPosterRequest>,
 ) -> AppResult<Json<AdminContestItem>> {
     ensure_admin_or_judge(&current_user)?;
-    ensure_contest_exists(state.as_ref(), contest_id).await?;
-
-    let filename = trim_required(&req.filename, "filename")?;
-    if filename.chars().count() > 255 {
-        return Err(AppError::BadRequest(
-            "filename must be at most 255 characters".to_string(),
-        ));
-    }
-
-    let decoded = {
-        use base64::{engine::general_purpose::STANDARD, Engine as _};
-        STANDARD
-            .decode(req.content_base64.trim())
-            .map_err(|_| AppError::BadRequest("content_base64 is invalid".to_string()))?
-    };
-
-    if decoded.is_empty() {
-        return Err(AppError::BadRequest("poster content is empty".to_string()));
-    }
-    if decoded.len() > CONTEST_POSTER_MAX_BYTES {
-        return Err(AppError::BadRequest(format!(
-            "poster size must be <= {}MB",
-            CONTEST_POSTER_MAX_BYTES / (1024 * 1024)
-        )));
-    }
-
-    let content_type = req
-        .content_type
-        .as_deref()
-        .and_then(normalize_optional_text)
-        .map(str::to_lowercase)
-        .or_else(|| infer_image_content_type_from_filename(&filename).map(str::to_string))
-        .unwrap_or_else(|| "image/png".to_string());
-    if !content_type.starts_with("image/") {
-        return Err(AppError::BadRequest(
-            "content_type must be image/*".to_string(),
-        ));
-    }
-
-    let old_poster_path = sqlx::query_as::<_, (Option<String>,)>(
-        "SELECT poster_storage_path
-         FROM contests
-         WHERE id = $1
-         LIMIT 1",
-    )
-    .bind(contest_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(AppError::internal)?
-    .ok_or(AppError::BadRequest("contest not found".to_string()))?
-    .0;
-
-    let safe_filename = sanitize_filename(&filename);
-    let poster_dir = contest_posters_dir(state.as_ref(), contest_id);
-    fs::create_dir_all(&poster_dir)
-        .await
-        .map_err(AppError::internal)?;
 
-    let stored_name = format!("{}-{}", Uuid::new_v4(), safe_filename);
-    let stored_path = poster_dir.join(stored_name);
-    fs::write(&stored_path, &decoded)
-        .await
-        .map_err(AppError::internal)?;
-
-    let updated = sqlx::query_as::<_, AdminContestItem>(
+    let row = sqlx::query_as::<_, AdminContestItem>(
         "UPDATE contests
-         SET poster_storage_path = $2,
-             poster_content_type = $3,
+         SET scoreboard_revealed_at = COALESCE(scoreboard_revealed_at, NOW()),
              updated_at = NOW()
          WHERE id = $1
+           AND freeze_at IS NOT NULL
          RETURNING id,
                    title,
                    slug,
@@ -4054,343 +5488,427 @@ async fn upload_contest_poster(
                    END AS poster_url,
                    visibility,
                    status,
+                   contest_type,
                    scoring_mode,
+                   scoring_formula,
                    dynamic_decay,
+                   difficulty_scores,
                    first_blood_bonus_percent,
                    second_blood_bonus_percent,
                    third_blood_bonus_percent,
+                   ad_round_seconds,
+                   ad_flag_lifetime_rounds,
+                   ad_attack_points,
+                   ad_defense_points,
+                   ad_sla_points,
+                   koth_tick_seconds,
+                   submission_rate_window_seconds,
+                   submission_rate_user_max,
+                   submission_rate_team_max,
+                   wrong_backoff_threshold,
+                   wrong_backoff_base_seconds,
+                   wrong_backoff_max_seconds,
+                   wrong_submission_penalty,
+                   score_floor,
+                   participation_mode,
                    registration_requires_approval,
+                   public_scoreboard,
+                   public_challenge_stats,
+                   public_scoreboard_rate_limit,
                    start_at,
                    end_at,
                    freeze_at,
+                   scoreboard_revealed_at,
                    created_at,
                    updated_at",
     )
     .bind(contest_id)
-    .bind(stored_path.to_string_lossy().to_string())
-    .bind(&content_type)
     .fetch_optional(&state.db)
     .await
-    .map_err(AppError::internal)?
-    .ok_or(AppError::BadRequest("contest not found".to_string()))?;
+    .map_err(AppError::internal)?;
 
-    if let Some(old_path) = old_poster_path {
-        let old_path = PathBuf::from(old_path);
-        if old_path != stored_path {
-            if let Err(err) = fs::remove_file(&old_path).await {
-                if err.kind() != std::io::ErrorKind::NotFound {
-                    return Err(AppError::internal(err));
-                }
-            }
-        }
-    }
+    let Some(row) = row else {
+        ensure_contest_exists(state.as_ref(), contest_id).await?;
+        return Err(AppError::BadRequest(
+            "contest has no freeze_at configured".to_string(),
+        ));
+    };
 
     record_audit_log(
         state.as_ref(),
         &current_user,
-        "admin.contest.poster.upload",
+        "admin.contest.scoreboard.reveal",
         "contest",
-        Some(updated.id),
+        Some(row.id),
         json!({
-            "filename": filename,
-            "content_type": content_type,
-            "size_bytes": decoded.len()
+            "freeze_at": row.freeze_at,
+            "scoreboard_revealed_at": row.scoreboard_revealed_at
         }),
     )
     .await;
 
-    Ok(Json(updated))
+    publish_scoreboard_update(state.as_ref(), row.id, SCOREBOARD_EVENT_REVEAL).await;
+
+    Ok(Json(row))
 }
 
-async fn delete_contest_poster(
+async fn preview_contest_scoring(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
     Path(contest_id): Path<Uuid>,
-) -> AppResult<StatusCode> {
+    Json(req): Json<PreviewContestScoringRequest>,
+) -> AppResult<Json<ContestScoringPreviewResponse>> {
     ensure_admin_or_judge(&current_user)?;
 
-    let existing = sqlx::query_as::<_, (Uuid, String, Option<String>)>(
-        "SELECT id, title, poster_storage_path
-         FROM contests
-         WHERE id = $1
-         LIMIT 1",
-    )
-    .bind(contest_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(AppError::internal)?
-    .ok_or(AppError::BadRequest("contest not found".to_string()))?;
-
-    sqlx::query(
-        "UPDATE contests
-         SET poster_storage_path = NULL,
-             poster_content_type = NULL,
-             updated_at = NOW()
-         WHERE id = $1",
-    )
-    .bind(contest_id)
-    .execute(&state.db)
-    .await
-    .map_err(AppError::internal)?;
-
-    if let Some(path) = existing.2.as_deref() {
-        let poster_path = PathBuf::from(path);
-        if let Err(err) = fs::remove_file(&poster_path).await {
-            if err.kind() != std::io::ErrorKind::NotFound {
-                return Err(AppError::internal(err));
-            }
-        }
+    let mut config = load_contest_scoring_config(state.as_ref(), contest_id).await?;
+    if let Some(value) = req.scoring_mode {
+        config.scoring_mode =
+            normalize_with_allowed(&value, CONTEST_SCORING_MODE_ALLOWED, "scoring_mode")?;
     }
-
-    let poster_dir = contest_posters_dir(state.as_ref(), contest_id);
-    if let Err(err) = fs::remove_dir_all(&poster_dir).await {
-        if err.kind() != std::io::ErrorKind::NotFound {
-            return Err(AppError::internal(err));
+    if let Some(value) = req.scoring_formula {
+        config.scoring_formula =
+            normalize_with_allowed(&value, CONTEST_SCORING_FORMULA_ALLOWED, "scoring_formula")?;
+    }
+    if let Some(value) = req.dynamic_decay {
+        if !(1..=100000).contains(&value) {
+            return Err(AppError::BadRequest(
+                "dynamic_decay must be between 1 and 100000".to_string(),
+            ));
         }
+        config.dynamic_decay = value;
+    }
+    if let Some(value) = req.difficulty_scores {
+        value.validate().map_err(AppError::BadRequest)?;
+        config.difficulty_scores = value;
     }
 
-    record_audit_log(
-        state.as_ref(),
-        &current_user,
-        "admin.contest.poster.delete",
-        "contest",
-        Some(existing.0),
-        json!({
-            "title": existing.1
-        }),
-    )
-    .await;
+    let (mut static_score, mut min_score, mut max_score, mut difficulty) = match req.challenge_id {
+        Some(challenge_id) => sqlx::query_as::<_, (i32, i32, i32, String)>(
+            "SELECT static_score, min_score, max_score, difficulty
+             FROM challenges
+             WHERE id = $1
+             LIMIT 1",
+        )
+        .bind(challenge_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(AppError::internal)?
+        .ok_or(AppError::BadRequest("challenge not found".to_string()))?,
+        None => (100, 50, 500, "normal".to_string()),
+    };
+    static_score = req.static_score.unwrap_or(static_score);
+    min_score = req.min_score.unwrap_or(min_score);
+    max_score = req.max_score.unwrap_or(max_score);
+    if let Some(value) = req.difficulty {
+        difficulty = normalize_with_allowed(&value, DIFFICULTY_ALLOWED, "difficulty")?;
+    }
+    if static_score <= 0 {
+        return Err(AppError::BadRequest(
+            "static_score must be greater than 0".to_string(),
+        ));
+    }
+    if min_score < 0 || max_score < min_score {
+        return Err(AppError::BadRequest(
+            "min_score/max_score is invalid".to_string(),
+        ));
+    }
 
-    Ok(StatusCode::NO_CONTENT)
+    let max_solves = req.max_solves.unwrap_or(50).clamp(1, 500);
+    let bounds = ChallengeScoreBounds {
+        static_score,
+        min_score,
+        max_score,
+        difficulty: &difficulty,
+    };
+    let points = (1..=max_solves)
+        .map(|solve_count| {
+            let value = challenge_value(&config, bounds, solve_count);
+            ContestScoringPreviewPoint {
+                solve_count,
+                value,
+                blood_bonus: blood_bonus(&config, value, solve_count - 1),
+            }
+        })
+        .collect();
+
+    Ok(Json(ContestScoringPreviewResponse {
+        contest_id,
+        scoring_mode: config.scoring_mode,
+        scoring_formula: config.scoring_formula,
+        dynamic_decay: config.dynamic_decay,
+        difficulty_scores: config.difficulty_scores,
+        static_score,
+        min_score,
+        max_score,
+        difficulty,
+        points,
+    }))
 }
 
-async fn list_contest_challenges(
+async fn list_attack_defense_rounds(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
     Path(contest_id): Path<Uuid>,
-) -> AppResult<Json<Vec<AdminContestChallengeItem>>> {
+    Query(query): Query<AdminAttackDefenseRoundsQuery>,
+) -> AppResult<Json<Vec<AdRoundItem>>> {
     ensure_admin_or_judge(&current_user)?;
-    ensure_contest_exists(state.as_ref(), contest_id).await?;
-
-    let rows = sqlx::query_as::<_, AdminContestChallengeItem>(
-        "SELECT cc.contest_id,
-                cc.challenge_id,
-                c.title AS challenge_title,
-                c.category AS challenge_category,
-                c.difficulty AS challenge_difficulty,
-                cc.sort_order,
-                cc.release_at
-         FROM contest_challenges cc
-         JOIN challenges c ON c.id = cc.challenge_id
-         WHERE cc.contest_id = $1
-         ORDER BY cc.sort_order ASC, c.created_at ASC",
-    )
-    .bind(contest_id)
-    .fetch_all(&state.db)
-    .await
-    .map_err(AppError::internal)?;
 
-    Ok(Json(rows))
+    let limit = query.limit.unwrap_or(200).clamp(1, 1000);
+    let rounds = attack_defense::load_ad_rounds(state.as_ref(), contest_id, limit).await?;
+    Ok(Json(rounds))
 }
 
-async fn upsert_contest_challenge(
+async fn advance_attack_defense_round(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
     Path(contest_id): Path<Uuid>,
-    Json(req): Json<UpsertContestChallengeRequest>,
-) -> AppResult<Json<AdminContestChallengeItem>> {
+) -> AppResult<Json<AdRoundAdvanceSummary>> {
     ensure_admin_or_judge(&current_user)?;
 
-    let sort_order = req.sort_order.unwrap_or(0);
+    let status = sqlx::query_scalar::<_, String>("SELECT status FROM contests WHERE id = $1")
+        .bind(contest_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(AppError::internal)?
+        .ok_or(AppError::BadRequest("contest not found".to_string()))?;
+    if status != "running" {
+        return Err(AppError::BadRequest(
+            "rounds can only be advanced while the contest is running".to_string(),
+        ));
+    }
 
-    let row = sqlx::query_as::<_, AdminContestChallengeItem>(
-        "WITH upserted AS (
-            INSERT INTO contest_challenges (contest_id, challenge_id, sort_order, release_at)
-            VALUES ($1, $2, $3, $4)
-            ON CONFLICT (contest_id, challenge_id)
-            DO UPDATE SET sort_order = EXCLUDED.sort_order,
-                          release_at = EXCLUDED.release_at
-            RETURNING contest_id, challenge_id, sort_order, release_at
-         )
-         SELECT u.contest_id,
-                u.challenge_id,
-                c.title AS challenge_title,
-                c.category AS challenge_category,
-                c.difficulty AS challenge_difficulty,
-                u.sort_order,
-                u.release_at
-         FROM upserted u
-         JOIN challenges c ON c.id = u.challenge_id",
-    )
-    .bind(contest_id)
-    .bind(req.challenge_id)
-    .bind(sort_order)
-    .bind(req.release_at)
-    .fetch_one(&state.db)
-    .await
-    .map_err(|err| {
-        if is_foreign_key_violation(&err) {
-            AppError::BadRequest("contest or challenge not found".to_string())
-        } else {
-            AppError::internal(err)
-        }
-    })?;
+    let summary = attack_defense::advance_attack_defense_round(state.as_ref(), contest_id, true)
+        .await?
+        .ok_or_else(|| AppError::Conflict("round was not advanced".to_string()))?;
 
     record_audit_log(
         state.as_ref(),
         &current_user,
-        "admin.contest_challenge.upsert",
-        "contest_challenge",
-        Some(row.challenge_id),
+        "admin.contest.ad.round.advance",
+        "contest",
+        Some(contest_id),
         json!({
-            "contest_id": row.contest_id,
-            "challenge_id": row.challenge_id,
-            "sort_order": row.sort_order,
-            "release_at": row.release_at
+            "round_number": summary.round.round_number,
+            "services": summary.services,
+            "services_up": summary.services_up
         }),
     )
     .await;
 
-    Ok(Json(row))
+    Ok(Json(summary))
 }
 
-async fn update_contest_challenge(
+async fn deploy_attack_defense_services(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
-    Path((contest_id, challenge_id)): Path<(Uuid, Uuid)>,
-    Json(req): Json<UpdateContestChallengeRequest>,
-) -> AppResult<Json<AdminContestChallengeItem>> {
+    Path(contest_id): Path<Uuid>,
+) -> AppResult<Json<AttackDefenseDeployResponse>> {
     ensure_admin_or_judge(&current_user)?;
-
-    if req.sort_order.is_none()
-        && req.release_at.is_none()
-        && !req.clear_release_at.unwrap_or(false)
-    {
-        return Err(AppError::BadRequest(
-            "at least one field is required for update".to_string(),
-        ));
+    attack_defense::ensure_attack_defense_contest(state.as_ref(), contest_id).await?;
+
+    let participants = attack_defense::load_ad_participants(state.as_ref(), contest_id).await?;
+    let services = attack_defense::load_ad_services(state.as_ref(), contest_id).await?;
+
+    let mut items = Vec::with_capacity(participants.len() * services.len());
+    for team in &participants {
+        for service in &services {
+            let result = instances::deploy_team_service(
+                state.as_ref(),
+                contest_id,
+                service.challenge_id,
+                team.team_id,
+            )
+            .await;
+            let (status, entrypoint_url, message) = match result {
+                Ok(url) => ("running".to_string(), Some(url), None),
+                Err(err) => {
+                    warn!(
+                        contest_id = %contest_id,
+                        team_id = %team.team_id,
+                        challenge_id = %service.challenge_id,
+                        error = %err,
+                        "attack-defense service deploy failed"
+                    );
+                    ("failed".to_string(), None, Some(err.to_string()))
+                }
+            };
+            items.push(AttackDefenseDeployItem {
+                team_id: team.team_id,
+                team_name: team.team_name.clone(),
+                challenge_id: service.challenge_id,
+                challenge_title: service.challenge_title.clone(),
+                status,
+                entrypoint_url,
+                message,
+            });
+        }
     }
 
-    let row = sqlx::query_as::<_, AdminContestChallengeItem>(
-        "WITH updated AS (
-            UPDATE contest_challenges
-            SET sort_order = COALESCE($3, sort_order),
-                release_at = CASE
-                    WHEN $4 THEN NULL
-                    ELSE COALESCE($5, release_at)
-                END
-            WHERE contest_id = $1 AND challenge_id = $2
-            RETURNING contest_id, challenge_id, sort_order, release_at
-         )
-         SELECT u.contest_id,
-                u.challenge_id,
-                c.title AS challenge_title,
-                c.category AS challenge_category,
-                c.difficulty AS challenge_difficulty,
-                u.sort_order,
-                u.release_at
-         FROM updated u
-         JOIN challenges c ON c.id = u.challenge_id",
-    )
-    .bind(contest_id)
-    .bind(challenge_id)
-    .bind(req.sort_order)
-    .bind(req.clear_release_at.unwrap_or(false))
-    .bind(req.release_at)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(AppError::internal)?
-    .ok_or(AppError::BadRequest(
-        "contest challenge binding not found".to_string(),
-    ))?;
+    let deployed = items.iter().filter(|item| item.status == "running").count() as i64;
+    let failed = items.len() as i64 - deployed;
 
     record_audit_log(
         state.as_ref(),
         &current_user,
-        "admin.contest_challenge.update",
-        "contest_challenge",
-        Some(row.challenge_id),
+        "admin.contest.ad.deploy",
+        "contest",
+        Some(contest_id),
         json!({
-            "contest_id": row.contest_id,
-            "challenge_id": row.challenge_id,
-            "sort_order": row.sort_order,
-            "release_at": row.release_at
+            "deployed": deployed,
+            "failed": failed
         }),
     )
     .await;
 
-    Ok(Json(row))
+    Ok(Json(AttackDefenseDeployResponse {
+        contest_id,
+        deployed,
+        failed,
+        items,
+    }))
 }
 
-async fn remove_contest_challenge(
+async fn list_koth_ticks(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
-    Path((contest_id, challenge_id)): Path<(Uuid, Uuid)>,
-) -> AppResult<StatusCode> {
+    Path(contest_id): Path<Uuid>,
+    Query(query): Query<AdminKothTicksQuery>,
+) -> AppResult<Json<Vec<KothTickItem>>> {
     ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
 
-    let result =
-        sqlx::query("DELETE FROM contest_challenges WHERE contest_id = $1 AND challenge_id = $2")
-            .bind(contest_id)
-            .bind(challenge_id)
-            .execute(&state.db)
-            .await
-            .map_err(AppError::internal)?;
+    let limit = query.limit.unwrap_or(200).clamp(1, 1000);
+    let ticks =
+        koth::load_koth_ticks(state.as_ref(), contest_id, query.challenge_id, limit).await?;
+    Ok(Json(ticks))
+}
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::BadRequest(
-            "contest challenge binding not found".to_string(),
-        ));
-    }
+async fn deploy_koth_hill(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path((contest_id, challenge_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<KothDeployResponse>> {
+    ensure_admin_or_judge(&current_user)?;
+
+    let entrypoint_url =
+        instances::deploy_shared_service(state.as_ref(), contest_id, challenge_id).await?;
 
     record_audit_log(
         state.as_ref(),
         &current_user,
-        "admin.contest_challenge.delete",
-        "contest_challenge",
-        Some(challenge_id),
+        "admin.contest.koth.deploy",
+        "contest",
+        Some(contest_id),
         json!({
-            "contest_id": contest_id,
-            "challenge_id": challenge_id
+            "challenge_id": challenge_id,
+            "entrypoint_url": &entrypoint_url
         }),
     )
     .await;
 
-    Ok(StatusCode::NO_CONTENT)
+    Ok(Json(KothDeployResponse {
+        contest_id,
+        challenge_id,
+        entrypoint_url,
+    }))
 }
 
-async fn list_contest_announcements(
+/// Invalidates every team's hill token; teams fetch their new token from the
+/// contest's king-of-the-hill status.
+async fn rotate_koth_tokens(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
     Path(contest_id): Path<Uuid>,
-    Query(query): Query<ContestAnnouncementsQuery>,
-) -> AppResult<Json<Vec<AdminContestAnnouncementItem>>> {
+) -> AppResult<Json<KothTokenRotateResponse>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    koth::rotate_koth_team_tokens(state.as_ref(), contest_id).await?;
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest.koth.rotate_tokens",
+        "contest",
+        Some(contest_id),
+        json!({}),
+    )
+    .await;
+
+    Ok(Json(KothTokenRotateResponse {
+        contest_id,
+        rotated_at: Utc::now(),
+    }))
+}
+
+async fn rejudge_contest_submissions(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+    Json(req): Json<RejudgeSubmissionsRequest>,
+) -> AppResult<Json<RejudgeReport>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let apply = !req.dry_run.unwrap_or(true);
+    let report =
+        submissions::rejudge_submissions(state.as_ref(), contest_id, req.challenge_id, apply)
+            .await?;
+
+    if apply {
+        record_audit_log(
+            state.as_ref(),
+            &current_user,
+            "admin.contest.rejudge",
+            "contest",
+            Some(contest_id),
+            json!({
+                "challenge_id": req.challenge_id,
+                "scanned": report.scanned,
+                "changed": report.changed,
+                "submission_ids": report
+                    .items
+                    .iter()
+                    .map(|item| item.submission_id)
+                    .collect::<Vec<_>>()
+            }),
+        )
+        .await;
+    }
+
+    Ok(Json(report))
+}
+
+async fn list_contest_score_adjustments(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+    Query(query): Query<AdminScoreAdjustmentsQuery>,
+) -> AppResult<Json<Vec<AdminScoreAdjustmentItem>>> {
     ensure_admin_or_judge(&current_user)?;
     ensure_contest_exists(state.as_ref(), contest_id).await?;
 
     let limit = query.limit.unwrap_or(200).clamp(1, 1000);
-    let rows = sqlx::query_as::<_, AdminContestAnnouncementItem>(
+    let rows = sqlx::query_as::<_, AdminScoreAdjustmentItem>(
         "SELECT a.id,
                 a.contest_id,
-                a.title,
-                a.content,
-                a.is_published,
-                a.is_pinned,
-                a.published_at,
+                a.team_id,
+                t.name AS team_name,
+                a.points,
+                a.reason,
                 a.created_by,
-                cu.username AS created_by_username,
-                a.updated_by,
-                uu.username AS updated_by_username,
-                a.created_at,
-                a.updated_at
-         FROM contest_announcements a
-         LEFT JOIN users cu ON cu.id = a.created_by
-         LEFT JOIN users uu ON uu.id = a.updated_by
+                u.username AS created_by_username,
+                a.created_at
+         FROM contest_score_adjustments a
+         JOIN teams t ON t.id = a.team_id
+         LEFT JOIN users u ON u.id = a.created_by
          WHERE a.contest_id = $1
-         ORDER BY a.is_pinned DESC, COALESCE(a.published_at, a.created_at) DESC, a.created_at DESC
-         LIMIT $2",
+           AND ($2::uuid IS NULL OR a.team_id = $2)
+         ORDER BY a.created_at DESC
+         LIMIT $3",
     )
     .bind(contest_id)
+    .bind(query.team_id)
     .bind(limit)
     .fetch_all(&state.db)
     .await
@@ -4399,24 +5917,867 @@ async fn list_contest_announcements(
     Ok(Json(rows))
 }
 
-async fn create_contest_announcement(
+async fn create_contest_score_adjustment(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
     Path(contest_id): Path<Uuid>,
-    Json(req): Json<CreateContestAnnouncementRequest>,
-) -> AppResult<Json<AdminContestAnnouncementItem>> {
+    Json(req): Json<CreateScoreAdjustmentRequest>,
+) -> AppResult<Json<AdminScoreAdjustmentItem>> {
     ensure_admin_or_judge(&current_user)?;
     ensure_contest_exists(state.as_ref(), contest_id).await?;
 
-    let title = trim_required(&req.title, "title")?;
-    let content = trim_required(&req.content, "content")?;
-    let is_published = req.is_published.unwrap_or(false);
-    let is_pinned = req.is_pinned.unwrap_or(false);
-    let published_at = if is_published { Some(Utc::now()) } else { None };
-
-    let announcement_id = sqlx::query_scalar::<_, Uuid>(
-        "INSERT INTO contest_announcements (
-            contest_id,
+    if req.points == 0 {
+        return Err(AppError::BadRequest("points must not be zero".to_string()));
+    }
+    validate_contest_int_range(req.points, -100_000..=100_000, "points")?;
+    let reason = trim_required(&req.reason, "reason")?;
+
+    let row = sqlx::query_as::<_, AdminScoreAdjustmentItem>(
+        "WITH inserted AS (
+            INSERT INTO contest_score_adjustments (contest_id, team_id, points, reason, created_by)
+            SELECT $1, r.team_id, $3, $4, $5
+            FROM contest_registrations r
+            WHERE r.contest_id = $1
+              AND r.team_id = $2
+              AND r.status <> 'rejected'
+            RETURNING id, contest_id, team_id, points, reason, created_by, created_at
+         )
+         SELECT i.id,
+                i.contest_id,
+                i.team_id,
+                t.name AS team_name,
+                i.points,
+                i.reason,
+                i.created_by,
+                u.username AS created_by_username,
+                i.created_at
+         FROM inserted i
+         JOIN teams t ON t.id = i.team_id
+         LEFT JOIN users u ON u.id = i.created_by",
+    )
+    .bind(contest_id)
+    .bind(req.team_id)
+    .bind(req.points)
+    .bind(reason)
+    .bind(current_user.user_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest(
+        "team is not registered for this contest".to_string(),
+    ))?;
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest.score_adjustment.create",
+        "contest_score_adjustment",
+        Some(row.id),
+        json!({
+            "contest_id": row.contest_id,
+            "team_id": row.team_id,
+            "points": row.points,
+            "reason": &row.reason
+        }),
+    )
+    .await;
+
+    publish_scoreboard_update(state.as_ref(), contest_id, SCOREBOARD_EVENT_UPDATE).await;
+
+    Ok(Json(row))
+}
+
+async fn delete_contest_score_adjustment(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path((contest_id, adjustment_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<StatusCode> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let deleted = sqlx::query_as::<_, (Uuid, i32, String)>(
+        "DELETE FROM contest_score_adjustments
+         WHERE contest_id = $1
+           AND id = $2
+         RETURNING team_id, points, reason",
+    )
+    .bind(contest_id)
+    .bind(adjustment_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest(
+        "score adjustment not found".to_string(),
+    ))?;
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest.score_adjustment.delete",
+        "contest_score_adjustment",
+        Some(adjustment_id),
+        json!({
+            "contest_id": contest_id,
+            "team_id": deleted.0,
+            "points": deleted.1,
+            "reason": deleted.2
+        }),
+    )
+    .await;
+
+    publish_scoreboard_update(state.as_ref(), contest_id, SCOREBOARD_EVENT_UPDATE).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn delete_contest(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let instance_cleanup =
+        instances::destroy_instances_for_contest(state.as_ref(), contest_id).await?;
+    if instance_cleanup.failed > 0 {
+        return Err(AppError::Conflict(format!(
+            "failed to destroy {} runtime instances, resolve runtime errors and retry",
+            instance_cleanup.failed
+        )));
+    }
+
+    let deleted = sqlx::query_as::<_, (Uuid, String, Option<String>)>(
+        "DELETE FROM contests
+         WHERE id = $1
+         RETURNING id, title, poster_storage_path",
+    )
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest("contest not found".to_string()))?;
+
+    if let Some(path) = deleted.2.as_deref() {
+        let poster_path = PathBuf::from(path);
+        if let Err(err) = fs::remove_file(&poster_path).await {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                return Err(AppError::internal(err));
+            }
+        }
+    }
+
+    let poster_dir = contest_posters_dir(state.as_ref(), contest_id);
+    if let Err(err) = fs::remove_dir_all(&poster_dir).await {
+        if err.kind() != std::io::ErrorKind::NotFound {
+            return Err(AppError::internal(err));
+        }
+    }
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest.delete",
+        "contest",
+        Some(deleted.0),
+        json!({
+            "title": deleted.1,
+            "instance_cleanup": {
+                "scanned": instance_cleanup.scanned,
+                "destroyed": instance_cleanup.reaped,
+                "failed": instance_cleanup.failed,
+                "skipped": instance_cleanup.skipped
+            }
+        }),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn upload_contest_poster(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+    Json(req): Json<UploadContestPosterRequest>,
+) -> AppResult<Json<AdminContestItem>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let filename = trim_required(&req.filename, "filename")?;
+    if filename.chars().count() > 255 {
+        return Err(AppError::BadRequest(
+            "filename must be at most 255 characters".to_string(),
+        ));
+    }
+
+    let decoded = {
+        use base64::{engine::general_purpose::STANDARD, Engine as _};
+        STANDARD
+            .decode(req.content_base64.trim())
+            .map_err(|_| AppError::BadRequest("content_base64 is invalid".to_string()))?
+    };
+
+    if decoded.is_empty() {
+        return Err(AppError::BadRequest("poster content is empty".to_string()));
+    }
+    if decoded.len() > CONTEST_POSTER_MAX_BYTES {
+        return Err(AppError::BadRequest(format!(
+            "poster size must be <= {}MB",
+            CONTEST_POSTER_MAX_BYTES / (1024 * 1024)
+        )));
+    }
+
+    let content_type = req
+        .content_type
+        .as_deref()
+        .and_then(normalize_optional_text)
+        .map(str::to_lowercase)
+        .or_else(|| infer_image_content_type_from_filename(&filename).map(str::to_string))
+        .unwrap_or_else(|| "image/png".to_string());
+    if !content_type.starts_with("image/") {
+        return Err(AppError::BadRequest(
+            "content_type must be image/*".to_string(),
+        ));
+    }
+
+    let old_poster_path = sqlx::query_as::<_, (Option<String>,)>(
+        "SELECT poster_storage_path
+         FROM contests
+         WHERE id = $1
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest("contest not found".to_string()))?
+    .0;
+
+    let safe_filename = sanitize_filename(&filename);
+    let poster_dir = contest_posters_dir(state.as_ref(), contest_id);
+    fs::create_dir_all(&poster_dir)
+        .await
+        .map_err(AppError::internal)?;
+
+    let stored_name = format!("{}-{}", Uuid::new_v4(), safe_filename);
+    let stored_path = poster_dir.join(stored_name);
+    fs::write(&stored_path, &decoded)
+        .await
+        .map_err(AppError::internal)?;
+
+    let updated = sqlx::query_as::<_, AdminContestItem>(
+        "UPDATE contests
+         SET poster_storage_path = $2,
+             poster_content_type = $3,
+             updated_at = NOW()
+         WHERE id = $1
+         RETURNING id,
+                   title,
+                   slug,
+                   description,
+                   CASE
+                       WHEN poster_storage_path IS NULL OR poster_storage_path = '' THEN NULL
+                       ELSE '/api/v1/contests/' || id::text || '/poster'
+                   END AS poster_url,
+                   visibility,
+                   status,
+                   contest_type,
+                   scoring_mode,
+                   scoring_formula,
+                   dynamic_decay,
+                   difficulty_scores,
+                   first_blood_bonus_percent,
+                   second_blood_bonus_percent,
+                   third_blood_bonus_percent,
+                   ad_round_seconds,
+                   ad_flag_lifetime_rounds,
+                   ad_attack_points,
+                   ad_defense_points,
+                   ad_sla_points,
+                   koth_tick_seconds,
+                   submission_rate_window_seconds,
+                   submission_rate_user_max,
+                   submission_rate_team_max,
+                   wrong_backoff_threshold,
+                   wrong_backoff_base_seconds,
+                   wrong_backoff_max_seconds,
+                   wrong_submission_penalty,
+                   score_floor,
+                   participation_mode,
+                   registration_requires_approval,
+                   public_scoreboard,
+                   public_challenge_stats,
+                   public_scoreboard_rate_limit,
+                   start_at,
+                   end_at,
+                   freeze_at,
+                   scoreboard_revealed_at,
+                   created_at,
+                   updated_at",
+    )
+    .bind(contest_id)
+    .bind(stored_path.to_string_lossy().to_string())
+    .bind(&content_type)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest("contest not found".to_string()))?;
+
+    if let Some(old_path) = old_poster_path {
+        let old_path = PathBuf::from(old_path);
+        if old_path != stored_path {
+            if let Err(err) = fs::remove_file(&old_path).await {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    return Err(AppError::internal(err));
+                }
+            }
+        }
+    }
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest.poster.upload",
+        "contest",
+        Some(updated.id),
+        json!({
+            "filename": filename,
+            "content_type": content_type,
+            "size_bytes": decoded.len()
+        }),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+async fn delete_contest_poster(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    ensure_admin_or_judge(&current_user)?;
+
+    let existing = sqlx::query_as::<_, (Uuid, String, Option<String>)>(
+        "SELECT id, title, poster_storage_path
+         FROM contests
+         WHERE id = $1
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest("contest not found".to_string()))?;
+
+    sqlx::query(
+        "UPDATE contests
+         SET poster_storage_path = NULL,
+             poster_content_type = NULL,
+             updated_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(contest_id)
+    .execute(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    if let Some(path) = existing.2.as_deref() {
+        let poster_path = PathBuf::from(path);
+        if let Err(err) = fs::remove_file(&poster_path).await {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                return Err(AppError::internal(err));
+            }
+        }
+    }
+
+    let poster_dir = contest_posters_dir(state.as_ref(), contest_id);
+    if let Err(err) = fs::remove_dir_all(&poster_dir).await {
+        if err.kind() != std::io::ErrorKind::NotFound {
+            return Err(AppError::internal(err));
+        }
+    }
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest.poster.delete",
+        "contest",
+        Some(existing.0),
+        json!({
+            "title": existing.1
+        }),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_contest_challenges(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+) -> AppResult<Json<Vec<AdminContestChallengeItem>>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let rows = load_admin_contest_challenges(state.as_ref(), contest_id).await?;
+    Ok(Json(rows))
+}
+
+async fn load_admin_contest_challenges(
+    state: &AppState,
+    contest_id: Uuid,
+) -> AppResult<Vec<AdminContestChallengeItem>> {
+    sqlx::query_as::<_, AdminContestChallengeItem>(
+        "SELECT cc.contest_id,
+                cc.challenge_id,
+                c.title AS challenge_title,
+                c.category AS challenge_category,
+                c.difficulty AS challenge_difficulty,
+                cc.sort_order,
+                cc.release_at,
+                cc.unlock_rules,
+                cc.rate_limit,
+                cc.wrong_submission_penalty
+         FROM contest_challenges cc
+         JOIN challenges c ON c.id = cc.challenge_id
+         WHERE cc.contest_id = $1
+         ORDER BY cc.sort_order ASC, c.created_at ASC",
+    )
+    .bind(contest_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)
+}
+
+async fn upsert_contest_challenge(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+    Json(req): Json<UpsertContestChallengeRequest>,
+) -> AppResult<Json<AdminContestChallengeItem>> {
+    ensure_admin_or_judge(&current_user)?;
+
+    let sort_order = req.sort_order.unwrap_or(0);
+    if let Some(penalty) = req.wrong_submission_penalty {
+        validate_contest_int_range(penalty, 0..=100_000, "wrong_submission_penalty")?;
+    }
+
+    let row = sqlx::query_as::<_, AdminContestChallengeItem>(
+        "WITH upserted AS (
+            INSERT INTO contest_challenges (
+                contest_id,
+                challenge_id,
+                sort_order,
+                release_at,
+                wrong_submission_penalty
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (contest_id, challenge_id)
+            DO UPDATE SET sort_order = EXCLUDED.sort_order,
+                          release_at = EXCLUDED.release_at,
+                          wrong_submission_penalty = EXCLUDED.wrong_submission_penalty
+            RETURNING contest_id, challenge_id, sort_order, release_at, unlock_rules, rate_limit,
+                      wrong_submission_penalty
+         )
+         SELECT u.contest_id,
+                u.challenge_id,
+                c.title AS challenge_title,
+                c.category AS challenge_category,
+                c.difficulty AS challenge_difficulty,
+                u.sort_order,
+                u.release_at,
+                u.unlock_rules,
+                u.rate_limit,
+                u.wrong_submission_penalty
+         FROM upserted u
+         JOIN challenges c ON c.id = u.challenge_id",
+    )
+    .bind(contest_id)
+    .bind(req.challenge_id)
+    .bind(sort_order)
+    .bind(req.release_at)
+    .bind(req.wrong_submission_penalty)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| {
+        if is_foreign_key_violation(&err) {
+            AppError::BadRequest("contest or challenge not found".to_string())
+        } else {
+            AppError::internal(err)
+        }
+    })?;
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest_challenge.upsert",
+        "contest_challenge",
+        Some(row.challenge_id),
+        json!({
+            "contest_id": row.contest_id,
+            "challenge_id": row.challenge_id,
+            "sort_order": row.sort_order,
+            "release_at": row.release_at,
+            "wrong_submission_penalty": row.wrong_submission_penalty
+        }),
+    )
+    .await;
+
+    invalidate_scoreboard_cache(state.as_ref(), contest_id).await;
+
+    Ok(Json(row))
+}
+
+async fn update_contest_challenge(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path((contest_id, challenge_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateContestChallengeRequest>,
+) -> AppResult<Json<AdminContestChallengeItem>> {
+    ensure_admin_or_judge(&current_user)?;
+
+    if req.sort_order.is_none()
+        && req.release_at.is_none()
+        && !req.clear_release_at.unwrap_or(false)
+        && req.wrong_submission_penalty.is_none()
+        && !req.clear_wrong_submission_penalty.unwrap_or(false)
+    {
+        return Err(AppError::BadRequest(
+            "at least one field is required for update".to_string(),
+        ));
+    }
+    if let Some(penalty) = req.wrong_submission_penalty {
+        validate_contest_int_range(penalty, 0..=100_000, "wrong_submission_penalty")?;
+    }
+
+    let row = sqlx::query_as::<_, AdminContestChallengeItem>(
+        "WITH updated AS (
+            UPDATE contest_challenges
+            SET sort_order = COALESCE($3, sort_order),
+                release_at = CASE
+                    WHEN $4 THEN NULL
+                    ELSE COALESCE($5, release_at)
+                END,
+                wrong_submission_penalty = CASE
+                    WHEN $6 THEN NULL
+                    ELSE COALESCE($7, wrong_submission_penalty)
+                END
+            WHERE contest_id = $1 AND challenge_id = $2
+            RETURNING contest_id, challenge_id, sort_order, release_at, unlock_rules, rate_limit,
+                      wrong_submission_penalty
+         )
+         SELECT u.contest_id,
+                u.challenge_id,
+                c.title AS challenge_title,
+                c.category AS challenge_category,
+                c.difficulty AS challenge_difficulty,
+                u.sort_order,
+                u.release_at,
+                u.unlock_rules,
+                u.rate_limit,
+                u.wrong_submission_penalty
+         FROM updated u
+         JOIN challenges c ON c.id = u.challenge_id",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(req.sort_order)
+    .bind(req.clear_release_at.unwrap_or(false))
+    .bind(req.release_at)
+    .bind(req.clear_wrong_submission_penalty.unwrap_or(false))
+    .bind(req.wrong_submission_penalty)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest(
+        "contest challenge binding not found".to_string(),
+    ))?;
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest_challenge.update",
+        "contest_challenge",
+        Some(row.challenge_id),
+        json!({
+            "contest_id": row.contest_id,
+            "challenge_id": row.challenge_id,
+            "sort_order": row.sort_order,
+            "release_at": row.release_at,
+            "wrong_submission_penalty": row.wrong_submission_penalty
+        }),
+    )
+    .await;
+
+    invalidate_scoreboard_cache(state.as_ref(), contest_id).await;
+
+    Ok(Json(row))
+}
+
+async fn update_contest_challenge_unlock_rules(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path((contest_id, challenge_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateContestChallengeUnlockRulesRequest>,
+) -> AppResult<Json<AdminContestChallengeItem>> {
+    ensure_admin_or_judge(&current_user)?;
+
+    let unlock_rules =
+        normalize_unlock_rules(challenge_id, req.unlock_rules).map_err(AppError::BadRequest)?;
+
+    let mut nodes = load_contest_unlock_nodes(state.as_ref(), contest_id).await?;
+    let node = nodes
+        .iter_mut()
+        .find(|node| node.challenge_id == challenge_id)
+        .ok_or(AppError::BadRequest(
+            "contest challenge binding not found".to_string(),
+        ))?;
+    node.rules = unlock_rules.clone();
+    validate_unlock_graph(&nodes).map_err(AppError::BadRequest)?;
+
+    let row = sqlx::query_as::<_, AdminContestChallengeItem>(
+        "WITH updated AS (
+            UPDATE contest_challenges
+            SET unlock_rules = $3
+            WHERE contest_id = $1 AND challenge_id = $2
+            RETURNING contest_id, challenge_id, sort_order, release_at, unlock_rules, rate_limit,
+                      wrong_submission_penalty
+         )
+         SELECT u.contest_id,
+                u.challenge_id,
+                c.title AS challenge_title,
+                c.category AS challenge_category,
+                c.difficulty AS challenge_difficulty,
+                u.sort_order,
+                u.release_at,
+                u.unlock_rules,
+                u.rate_limit,
+                u.wrong_submission_penalty
+         FROM updated u
+         JOIN challenges c ON c.id = u.challenge_id",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(unlock_rules_to_value(&unlock_rules))
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest(
+        "contest challenge binding not found".to_string(),
+    ))?;
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest_challenge.unlock_rules.update",
+        "contest_challenge",
+        Some(row.challenge_id),
+        json!({
+            "contest_id": row.contest_id,
+            "challenge_id": row.challenge_id,
+            "unlock_rules": row.unlock_rules
+        }),
+    )
+    .await;
+
+    Ok(Json(row))
+}
+
+async fn update_contest_challenge_rate_limit(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path((contest_id, challenge_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateContestChallengeRateLimitRequest>,
+) -> AppResult<Json<AdminContestChallengeItem>> {
+    ensure_admin_or_judge(&current_user)?;
+
+    let rate_limit =
+        normalize_challenge_rate_limit(req.rate_limit).map_err(AppError::BadRequest)?;
+
+    let row = sqlx::query_as::<_, AdminContestChallengeItem>(
+        "WITH updated AS (
+            UPDATE contest_challenges
+            SET rate_limit = $3
+            WHERE contest_id = $1 AND challenge_id = $2
+            RETURNING contest_id, challenge_id, sort_order, release_at, unlock_rules, rate_limit,
+                      wrong_submission_penalty
+         )
+         SELECT u.contest_id,
+                u.challenge_id,
+                c.title AS challenge_title,
+                c.category AS challenge_category,
+                c.difficulty AS challenge_difficulty,
+                u.sort_order,
+                u.release_at,
+                u.unlock_rules,
+                u.rate_limit,
+                u.wrong_submission_penalty
+         FROM updated u
+         JOIN challenges c ON c.id = u.challenge_id",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(challenge_rate_limit_to_value(&rate_limit))
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest(
+        "contest challenge binding not found".to_string(),
+    ))?;
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest_challenge.rate_limit.update",
+        "contest_challenge",
+        Some(row.challenge_id),
+        json!({
+            "contest_id": row.contest_id,
+            "challenge_id": row.challenge_id,
+            "rate_limit": row.rate_limit
+        }),
+    )
+    .await;
+
+    Ok(Json(row))
+}
+
+async fn get_contest_unlock_graph(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+) -> AppResult<Json<AdminContestUnlockGraphResponse>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let challenges = load_admin_contest_challenges(state.as_ref(), contest_id).await?;
+    let nodes = load_contest_unlock_nodes(state.as_ref(), contest_id).await?;
+    let error = validate_unlock_graph(&nodes).err();
+
+    Ok(Json(AdminContestUnlockGraphResponse {
+        contest_id,
+        valid: error.is_none(),
+        error,
+        challenges,
+    }))
+}
+
+async fn remove_contest_challenge(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path((contest_id, challenge_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<StatusCode> {
+    ensure_admin_or_judge(&current_user)?;
+
+    // Refuse to strand challenges that depend on this one, unless the graph
+    // was already broken before the removal.
+    let nodes = load_contest_unlock_nodes(state.as_ref(), contest_id).await?;
+    if validate_unlock_graph(&nodes).is_ok() {
+        let remaining: Vec<_> = nodes
+            .into_iter()
+            .filter(|node| node.challenge_id != challenge_id)
+            .collect();
+        if let Err(err) = validate_unlock_graph(&remaining) {
+            return Err(AppError::Conflict(format!(
+                "challenge is still required by unlock rules: {}",
+                err
+            )));
+        }
+    }
+
+    let result =
+        sqlx::query("DELETE FROM contest_challenges WHERE contest_id = $1 AND challenge_id = $2")
+            .bind(contest_id)
+            .bind(challenge_id)
+            .execute(&state.db)
+            .await
+            .map_err(AppError::internal)?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::BadRequest(
+            "contest challenge binding not found".to_string(),
+        ));
+    }
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest_challenge.delete",
+        "contest_challenge",
+        Some(challenge_id),
+        json!({
+            "contest_id": contest_id,
+            "challenge_id": challenge_id
+        }),
+    )
+    .await;
+
+    invalidate_scoreboard_cache(state.as_ref(), contest_id).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_contest_announcements(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+    Query(query): Query<ContestAnnouncementsQuery>,
+) -> AppResult<Json<Vec<AdminContestAnnouncementItem>>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let limit = query.limit.unwrap_or(200).clamp(1, 1000);
+    let rows = sqlx::query_as::<_, AdminContestAnnouncementItem>(
+        "SELECT a.id,
+                a.contest_id,
+                a.title,
+                a.content,
+                a.is_published,
+                a.is_pinned,
+                a.published_at,
+                a.created_by,
+                cu.username AS created_by_username,
+                a.updated_by,
+                uu.username AS updated_by_username,
+                a.created_at,
+                a.updated_at
+         FROM contest_announcements a
+         LEFT JOIN users cu ON cu.id = a.created_by
+         LEFT JOIN users uu ON uu.id = a.updated_by
+         WHERE a.contest_id = $1
+         ORDER BY a.is_pinned DESC, COALESCE(a.published_at, a.created_at) DESC, a.created_at DESC
+         LIMIT $2",
+    )
+    .bind(contest_id)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(Json(rows))
+}
+
+async fn create_contest_announcement(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+    Json(req): Json<CreateContestAnnouncementRequest>,
+) -> AppResult<Json<AdminContestAnnouncementItem>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let title = trim_required(&req.title, "title")?;
+    let content = trim_required(&req.content, "content")?;
+    let is_published = req.is_published.unwrap_or(false);
+    let is_pinned = req.is_pinned.unwrap_or(false);
+    let published_at = if is_published { Some(Utc::now()) } else { None };
+
+    let announcement_id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO contest_announcements (
+            contest_id,
             title,
             content,
             is_published,
@@ -4425,32 +6786,510 @@ async fn create_contest_announcement(
             created_by,
             updated_by
          )
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
-         RETURNING id",
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
+         RETURNING id",
+    )
+    .bind(contest_id)
+    .bind(title)
+    .bind(content)
+    .bind(is_published)
+    .bind(is_pinned)
+    .bind(published_at)
+    .bind(current_user.user_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let item = load_contest_announcement_item(state.as_ref(), contest_id, announcement_id).await?;
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest.announcement.create",
+        "contest_announcement",
+        Some(item.id),
+        json!({
+            "contest_id": item.contest_id,
+            "title": &item.title,
+            "is_published": item.is_published,
+            "is_pinned": item.is_pinned
+        }),
+    )
+    .await;
+
+    if item.is_published {
+        publish_scoreboard_announcement(
+            state.as_ref(),
+            contest_id,
+            item.id,
+            &item.title,
+            item.published_at,
+        )
+        .await;
+    }
+
+    Ok(Json(item))
+}
+
+async fn update_contest_announcement(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path((contest_id, announcement_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateContestAnnouncementRequest>,
+) -> AppResult<Json<AdminContestAnnouncementItem>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let title = req
+        .title
+        .as_deref()
+        .and_then(normalize_optional_text)
+        .map(str::to_string);
+    let content = req
+        .content
+        .as_deref()
+        .and_then(normalize_optional_text)
+        .map(str::to_string);
+
+    if title.is_none() && content.is_none() && req.is_published.is_none() && req.is_pinned.is_none()
+    {
+        return Err(AppError::BadRequest(
+            "at least one field is required for update".to_string(),
+        ));
+    }
+
+    let row = sqlx::query_as::<_, AdminContestAnnouncementItem>(
+        "WITH updated AS (
+            UPDATE contest_announcements
+            SET title = COALESCE($3, title),
+                content = COALESCE($4, content),
+                is_published = COALESCE($5, is_published),
+                is_pinned = COALESCE($6, is_pinned),
+                published_at = CASE
+                    WHEN COALESCE($5, is_published) THEN COALESCE(published_at, NOW())
+                    ELSE NULL
+                END,
+                updated_by = $7,
+                updated_at = NOW()
+            WHERE contest_id = $1
+              AND id = $2
+            RETURNING id,
+                      contest_id,
+                      title,
+                      content,
+                      is_published,
+                      is_pinned,
+                      published_at,
+                      created_by,
+                      updated_by,
+                      created_at,
+                      updated_at
+         )
+         SELECT u.id,
+                u.contest_id,
+                u.title,
+                u.content,
+                u.is_published,
+                u.is_pinned,
+                u.published_at,
+                u.created_by,
+                cu.username AS created_by_username,
+                u.updated_by,
+                uu.username AS updated_by_username,
+                u.created_at,
+                u.updated_at
+         FROM updated u
+         LEFT JOIN users cu ON cu.id = u.created_by
+         LEFT JOIN users uu ON uu.id = u.updated_by",
+    )
+    .bind(contest_id)
+    .bind(announcement_id)
+    .bind(title)
+    .bind(content)
+    .bind(req.is_published)
+    .bind(req.is_pinned)
+    .bind(current_user.user_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest(
+        "contest announcement not found".to_string(),
+    ))?;
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest.announcement.update",
+        "contest_announcement",
+        Some(row.id),
+        json!({
+            "contest_id": row.contest_id,
+            "is_published": row.is_published,
+            "is_pinned": row.is_pinned
+        }),
+    )
+    .await;
+
+    if req.is_published == Some(true) {
+        publish_scoreboard_announcement(
+            state.as_ref(),
+            contest_id,
+            row.id,
+            &row.title,
+            row.published_at,
+        )
+        .await;
+    }
+
+    Ok(Json(row))
+}
+
+async fn delete_contest_announcement(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path((contest_id, announcement_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<StatusCode> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let deleted = sqlx::query_as::<_, AdminContestAnnouncementItem>(
+        "WITH deleted AS (
+            DELETE FROM contest_announcements
+            WHERE contest_id = $1
+              AND id = $2
+            RETURNING id,
+                      contest_id,
+                      title,
+                      content,
+                      is_published,
+                      is_pinned,
+                      published_at,
+                      created_by,
+                      updated_by,
+                      created_at,
+                      updated_at
+         )
+         SELECT d.id,
+                d.contest_id,
+                d.title,
+                d.content,
+                d.is_published,
+                d.is_pinned,
+                d.published_at,
+                d.created_by,
+                cu.username AS created_by_username,
+                d.updated_by,
+                uu.username AS updated_by_username,
+                d.created_at,
+                d.updated_at
+         FROM deleted d
+         LEFT JOIN users cu ON cu.id = d.created_by
+         LEFT JOIN users uu ON uu.id = d.updated_by",
+    )
+    .bind(contest_id)
+    .bind(announcement_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest(
+        "contest announcement not found".to_string(),
+    ))?;
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest.announcement.delete",
+        "contest_announcement",
+        Some(deleted.id),
+        json!({
+            "contest_id": deleted.contest_id,
+            "title": deleted.title
+        }),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_contest_registrations(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+    Query(query): Query<AdminContestRegistrationsQuery>,
+) -> AppResult<Json<Vec<AdminContestRegistrationItem>>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let status_filter = query
+        .status
+        .as_deref()
+        .map(|value| {
+            normalize_with_allowed(
+                value,
+                CONTEST_REGISTRATION_STATUS_ALLOWED,
+                "registration_status",
+            )
+        })
+        .transpose()?;
+    let limit = query.limit.unwrap_or(200).clamp(1, 1000);
+
+    let rows = sqlx::query_as::<_, AdminContestRegistrationItem>(
+        "SELECT r.id,
+                r.contest_id,
+                c.title AS contest_title,
+                r.team_id,
+                t.name AS team_name,
+                r.status,
+                r.division_id,
+                d.name AS division_name,
+                r.requested_by,
+                req_u.username AS requested_by_username,
+                r.requested_at,
+                r.reviewed_by,
+                rev_u.username AS reviewed_by_username,
+                r.reviewed_at,
+                r.review_note,
+                r.created_at,
+                r.updated_at
+         FROM contest_registrations r
+         JOIN contests c ON c.id = r.contest_id
+         JOIN teams t ON t.id = r.team_id
+         LEFT JOIN contest_divisions d ON d.id = r.division_id
+         LEFT JOIN users req_u ON req_u.id = r.requested_by
+         LEFT JOIN users rev_u ON rev_u.id = r.reviewed_by
+         WHERE r.contest_id = $1
+           AND ($2::text IS NULL OR r.status = $2)
+           AND ($3::uuid IS NULL OR r.division_id = $3)
+         ORDER BY r.requested_at DESC, r.created_at DESC
+         LIMIT $4",
+    )
+    .bind(contest_id)
+    .bind(status_filter)
+    .bind(query.division_id)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(Json(rows))
+}
+
+async fn update_contest_registration(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path((contest_id, registration_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateContestRegistrationRequest>,
+) -> AppResult<Json<AdminContestRegistrationItem>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let status = req
+        .status
+        .as_deref()
+        .map(|value| {
+            normalize_with_allowed(
+                value,
+                CONTEST_REGISTRATION_STATUS_ALLOWED,
+                "registration_status",
+            )
+        })
+        .transpose()?;
+    let clear_division = req.clear_division.unwrap_or(false);
+    if status.is_none() && req.division_id.is_none() && !clear_division {
+        return Err(AppError::BadRequest(
+            "at least one field is required for update".to_string(),
+        ));
+    }
+    if let Some(division_id) = req.division_id {
+        load_contest_division(state.as_ref(), contest_id, division_id).await?;
+    }
+
+    // A review resets the note; a division-only update keeps it unless given.
+    let review_note = req
+        .review_note
+        .as_deref()
+        .and_then(normalize_optional_text)
+        .map(str::to_string)
+        .or_else(|| status.as_ref().map(|_| String::new()));
+    if review_note
+        .as_deref()
+        .is_some_and(|note| note.chars().count() > 1000)
+    {
+        return Err(AppError::BadRequest(
+            "review_note must be at most 1000 characters".to_string(),
+        ));
+    }
+
+    let row = sqlx::query_as::<_, AdminContestRegistrationItem>(
+        "WITH updated AS (
+            UPDATE contest_registrations
+            SET status = COALESCE($3, status),
+                reviewed_by = CASE
+                    WHEN $3::text IS NULL THEN reviewed_by
+                    WHEN $3 = 'pending' THEN NULL
+                    ELSE $4
+                END,
+                reviewed_at = CASE
+                    WHEN $3::text IS NULL THEN reviewed_at
+                    WHEN $3 = 'pending' THEN NULL
+                    ELSE NOW()
+                END,
+                review_note = COALESCE($5, review_note),
+                division_id = CASE WHEN $7 THEN NULL ELSE COALESCE($6, division_id) END,
+                updated_at = NOW()
+            WHERE contest_id = $1
+              AND id = $2
+            RETURNING id,
+                      contest_id,
+                      team_id,
+                      status,
+                      division_id,
+                      requested_by,
+                      requested_at,
+                      reviewed_by,
+                      reviewed_at,
+                      review_note,
+                      created_at,
+                      updated_at
+         )
+         SELECT u.id,
+                u.contest_id,
+                c.title AS contest_title,
+                u.team_id,
+                t.name AS team_name,
+                u.status,
+                u.division_id,
+                d.name AS division_name,
+                u.requested_by,
+                req_u.username AS requested_by_username,
+                u.requested_at,
+                u.reviewed_by,
+                rev_u.username AS reviewed_by_username,
+                u.reviewed_at,
+                u.review_note,
+                u.created_at,
+                u.updated_at
+         FROM updated u
+         JOIN contests c ON c.id = u.contest_id
+         JOIN teams t ON t.id = u.team_id
+         LEFT JOIN contest_divisions d ON d.id = u.division_id
+         LEFT JOIN users req_u ON req_u.id = u.requested_by
+         LEFT JOIN users rev_u ON rev_u.id = u.reviewed_by",
+    )
+    .bind(contest_id)
+    .bind(registration_id)
+    .bind(&status)
+    .bind(current_user.user_id)
+    .bind(&review_note)
+    .bind(req.division_id)
+    .bind(clear_division)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| {
+        if is_contest_team_conflict(&err) {
+            AppError::Conflict(
+                "a team member is already registered for this contest with another team"
+                    .to_string(),
+            )
+        } else {
+            AppError::internal(err)
+        }
+    })?
+    .ok_or(AppError::BadRequest(
+        "contest registration not found".to_string(),
+    ))?;
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.contest.registration.update",
+        "contest_registration",
+        Some(row.id),
+        json!({
+            "contest_id": row.contest_id,
+            "team_id": row.team_id,
+            "status": row.status,
+            "division_id": row.division_id,
+            "review_note": row.review_note
+        }),
+    )
+    .await;
+
+    if req.division_id.is_some() || clear_division {
+        publish_scoreboard_update(state.as_ref(), contest_id, SCOREBOARD_EVENT_UPDATE).await;
+    }
+
+    Ok(Json(row))
+}
+
+async fn list_contest_divisions_admin(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+) -> AppResult<Json<Vec<ContestDivisionItem>>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let rows = list_contest_divisions(state.as_ref(), contest_id).await?;
+    Ok(Json(rows))
+}
+
+async fn create_contest_division(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+    Json(req): Json<CreateContestDivisionRequest>,
+) -> AppResult<Json<ContestDivisionItem>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let name = normalize_division_name(&req.name)?;
+    let description = normalize_division_description(req.description.as_deref().unwrap_or(""))?;
+
+    let division_count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM contest_divisions WHERE contest_id = $1",
     )
     .bind(contest_id)
-    .bind(title)
-    .bind(content)
-    .bind(is_published)
-    .bind(is_pinned)
-    .bind(published_at)
-    .bind(current_user.user_id)
     .fetch_one(&state.db)
     .await
     .map_err(AppError::internal)?;
+    if division_count >= MAX_CONTEST_DIVISIONS {
+        return Err(AppError::BadRequest(format!(
+            "a contest can have at most {} divisions",
+            MAX_CONTEST_DIVISIONS
+        )));
+    }
 
-    let item = load_contest_announcement_item(state.as_ref(), contest_id, announcement_id).await?;
+    let division_id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO contest_divisions (contest_id, name, description, self_selectable, sort_order)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING id",
+    )
+    .bind(contest_id)
+    .bind(&name)
+    .bind(&description)
+    .bind(req.self_selectable.unwrap_or(true))
+    .bind(req.sort_order.unwrap_or(division_count as i32))
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| {
+        if is_unique_violation(&err) {
+            AppError::Conflict("division name already exists in this contest".to_string())
+        } else {
+            AppError::internal(err)
+        }
+    })?;
+
+    let item = load_contest_division(state.as_ref(), contest_id, division_id).await?;
     record_audit_log(
         state.as_ref(),
         &current_user,
-        "admin.contest.announcement.create",
-        "contest_announcement",
+        "admin.contest.division.create",
+        "contest_division",
         Some(item.id),
         json!({
             "contest_id": item.contest_id,
-            "title": &item.title,
-            "is_published": item.is_published,
-            "is_pinned": item.is_pinned
+            "name": item.name,
+            "self_selectable": item.self_selectable
         }),
     )
     .await;
@@ -4458,225 +7297,247 @@ async fn create_contest_announcement(
     Ok(Json(item))
 }
 
-async fn update_contest_announcement(
+async fn update_contest_division(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
-    Path((contest_id, announcement_id)): Path<(Uuid, Uuid)>,
-    Json(req): Json<UpdateContestAnnouncementRequest>,
-) -> AppResult<Json<AdminContestAnnouncementItem>> {
+    Path((contest_id, division_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateContestDivisionRequest>,
+) -> AppResult<Json<ContestDivisionItem>> {
     ensure_admin_or_judge(&current_user)?;
-    ensure_contest_exists(state.as_ref(), contest_id).await?;
 
-    let title = req
-        .title
+    let name = req
+        .name
         .as_deref()
-        .and_then(normalize_optional_text)
-        .map(str::to_string);
-    let content = req
-        .content
+        .map(normalize_division_name)
+        .transpose()?;
+    let description = req
+        .description
         .as_deref()
-        .and_then(normalize_optional_text)
-        .map(str::to_string);
+        .map(normalize_division_description)
+        .transpose()?;
 
-    if title.is_none() && content.is_none() && req.is_published.is_none() && req.is_pinned.is_none()
+    if name.is_none()
+        && description.is_none()
+        && req.self_selectable.is_none()
+        && req.sort_order.is_none()
     {
         return Err(AppError::BadRequest(
             "at least one field is required for update".to_string(),
         ));
     }
 
-    let row = sqlx::query_as::<_, AdminContestAnnouncementItem>(
-        "WITH updated AS (
-            UPDATE contest_announcements
-            SET title = COALESCE($3, title),
-                content = COALESCE($4, content),
-                is_published = COALESCE($5, is_published),
-                is_pinned = COALESCE($6, is_pinned),
-                published_at = CASE
-                    WHEN COALESCE($5, is_published) THEN COALESCE(published_at, NOW())
-                    ELSE NULL
-                END,
-                updated_by = $7,
-                updated_at = NOW()
-            WHERE contest_id = $1
-              AND id = $2
-            RETURNING id,
-                      contest_id,
-                      title,
-                      content,
-                      is_published,
-                      is_pinned,
-                      published_at,
-                      created_by,
-                      updated_by,
-                      created_at,
-                      updated_at
-         )
-         SELECT u.id,
-                u.contest_id,
-                u.title,
-                u.content,
-                u.is_published,
-                u.is_pinned,
-                u.published_at,
-                u.created_by,
-                cu.username AS created_by_username,
-                u.updated_by,
-                uu.username AS updated_by_username,
-                u.created_at,
-                u.updated_at
-         FROM updated u
-         LEFT JOIN users cu ON cu.id = u.created_by
-         LEFT JOIN users uu ON uu.id = u.updated_by",
+    let updated = sqlx::query(
+        "UPDATE contest_divisions
+         SET name = COALESCE($3, name),
+             description = COALESCE($4, description),
+             self_selectable = COALESCE($5, self_selectable),
+             sort_order = COALESCE($6, sort_order),
+             updated_at = NOW()
+         WHERE contest_id = $1
+           AND id = $2",
     )
     .bind(contest_id)
-    .bind(announcement_id)
-    .bind(title)
-    .bind(content)
-    .bind(req.is_published)
-    .bind(req.is_pinned)
-    .bind(current_user.user_id)
-    .fetch_optional(&state.db)
+    .bind(division_id)
+    .bind(name)
+    .bind(description)
+    .bind(req.self_selectable)
+    .bind(req.sort_order)
+    .execute(&state.db)
     .await
-    .map_err(AppError::internal)?
-    .ok_or(AppError::BadRequest(
-        "contest announcement not found".to_string(),
-    ))?;
+    .map_err(|err| {
+        if is_unique_violation(&err) {
+            AppError::Conflict("division name already exists in this contest".to_string())
+        } else {
+            AppError::internal(err)
+        }
+    })?;
+
+    if updated.rows_affected() == 0 {
+        return Err(AppError::BadRequest(
+            "contest division not found".to_string(),
+        ));
+    }
 
+    let item = load_contest_division(state.as_ref(), contest_id, division_id).await?;
     record_audit_log(
         state.as_ref(),
         &current_user,
-        "admin.contest.announcement.update",
-        "contest_announcement",
-        Some(row.id),
+        "admin.contest.division.update",
+        "contest_division",
+        Some(item.id),
         json!({
-            "contest_id": row.contest_id,
-            "is_published": row.is_published,
-            "is_pinned": row.is_pinned
+            "contest_id": item.contest_id,
+            "name": item.name,
+            "self_selectable": item.self_selectable,
+            "sort_order": item.sort_order
         }),
     )
     .await;
 
-    Ok(Json(row))
+    Ok(Json(item))
 }
 
-async fn delete_contest_announcement(
+/// Registrations in the deleted division fall back to no division.
+async fn delete_contest_division(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
-    Path((contest_id, announcement_id)): Path<(Uuid, Uuid)>,
+    Path((contest_id, division_id)): Path<(Uuid, Uuid)>,
 ) -> AppResult<StatusCode> {
     ensure_admin_or_judge(&current_user)?;
-    ensure_contest_exists(state.as_ref(), contest_id).await?;
 
-    let deleted = sqlx::query_as::<_, AdminContestAnnouncementItem>(
-        "WITH deleted AS (
-            DELETE FROM contest_announcements
-            WHERE contest_id = $1
-              AND id = $2
-            RETURNING id,
-                      contest_id,
-                      title,
-                      content,
-                      is_published,
-                      is_pinned,
-                      published_at,
-                      created_by,
-                      updated_by,
-                      created_at,
-                      updated_at
-         )
-         SELECT d.id,
-                d.contest_id,
-                d.title,
-                d.content,
-                d.is_published,
-                d.is_pinned,
-                d.published_at,
-                d.created_by,
-                cu.username AS created_by_username,
-                d.updated_by,
-                uu.username AS updated_by_username,
-                d.created_at,
-                d.updated_at
-         FROM deleted d
-         LEFT JOIN users cu ON cu.id = d.created_by
-         LEFT JOIN users uu ON uu.id = d.updated_by",
+    let deleted = sqlx::query(
+        "DELETE FROM contest_divisions
+         WHERE contest_id = $1
+           AND id = $2",
     )
     .bind(contest_id)
-    .bind(announcement_id)
-    .fetch_optional(&state.db)
+    .bind(division_id)
+    .execute(&state.db)
     .await
-    .map_err(AppError::internal)?
-    .ok_or(AppError::BadRequest(
-        "contest announcement not found".to_string(),
-    ))?;
+    .map_err(AppError::internal)?;
+
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::BadRequest(
+            "contest division not found".to_string(),
+        ));
+    }
 
     record_audit_log(
         state.as_ref(),
         &current_user,
-        "admin.contest.announcement.delete",
-        "contest_announcement",
-        Some(deleted.id),
+        "admin.contest.division.delete",
+        "contest_division",
+        Some(division_id),
         json!({
-            "contest_id": deleted.contest_id,
-            "title": deleted.title
+            "contest_id": contest_id
         }),
     )
     .await;
 
+    invalidate_scoreboard_cache(state.as_ref(), contest_id).await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
-async fn list_contest_registrations(
+fn normalize_division_name(name: &str) -> AppResult<String> {
+    let name = trim_required(name, "name")?;
+    if name.chars().count() > MAX_CONTEST_DIVISION_NAME_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "division name must be at most {} characters",
+            MAX_CONTEST_DIVISION_NAME_CHARS
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn normalize_division_description(description: &str) -> AppResult<String> {
+    let description = description.trim();
+    if description.chars().count() > MAX_CONTEST_DIVISION_DESCRIPTION_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "division description must be at most {} characters",
+            MAX_CONTEST_DIVISION_DESCRIPTION_CHARS
+        )));
+    }
+    Ok(description.to_string())
+}
+
+async fn list_contest_hint_unlocks(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
     Path(contest_id): Path<Uuid>,
-    Query(query): Query<AdminContestRegistrationsQuery>,
-) -> AppResult<Json<Vec<AdminContestRegistrationItem>>> {
+    Query(query): Query<AdminHintUnlocksQuery>,
+) -> AppResult<Json<Vec<AdminHintUnlockItem>>> {
+    ensure_admin_or_judge(&current_user)?;
+    ensure_contest_exists(state.as_ref(), contest_id).await?;
+
+    let limit = query.limit.unwrap_or(200).clamp(1, 1000);
+    let rows = sqlx::query_as::<_, AdminHintUnlockItem>(
+        "SELECT u.id,
+                u.contest_id,
+                u.challenge_id,
+                c.title AS challenge_title,
+                u.hint_id,
+                u.team_id,
+                t.name AS team_name,
+                u.user_id,
+                usr.username,
+                u.cost,
+                u.unlocked_at
+         FROM challenge_hint_unlocks u
+         JOIN challenges c ON c.id = u.challenge_id
+         JOIN teams t ON t.id = u.team_id
+         LEFT JOIN users usr ON usr.id = u.user_id
+         WHERE u.contest_id = $1
+           AND ($2::uuid IS NULL OR u.challenge_id = $2)
+           AND ($3::uuid IS NULL OR u.team_id = $3)
+         ORDER BY u.unlocked_at DESC
+         LIMIT $4",
+    )
+    .bind(contest_id)
+    .bind(query.challenge_id)
+    .bind(query.team_id)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(Json(rows))
+}
+
+async fn list_flag_sharing_incidents(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Path(contest_id): Path<Uuid>,
+    Query(query): Query<AdminFlagSharingIncidentsQuery>,
+) -> AppResult<Json<Vec<AdminFlagSharingIncidentItem>>> {
     ensure_admin_or_judge(&current_user)?;
     ensure_contest_exists(state.as_ref(), contest_id).await?;
 
     let status_filter = query
         .status
         .as_deref()
-        .map(|value| {
-            normalize_with_allowed(
-                value,
-                CONTEST_REGISTRATION_STATUS_ALLOWED,
-                "registration_status",
-            )
-        })
+        .map(|value| normalize_with_allowed(value, FLAG_SHARING_INCIDENT_STATUS_ALLOWED, "status"))
         .transpose()?;
     let limit = query.limit.unwrap_or(200).clamp(1, 1000);
 
-    let rows = sqlx::query_as::<_, AdminContestRegistrationItem>(
-        "SELECT r.id,
-                r.contest_id,
-                c.title AS contest_title,
-                r.team_id,
-                t.name AS team_name,
-                r.status,
-                r.requested_by,
-                req_u.username AS requested_by_username,
-                r.requested_at,
-                r.reviewed_by,
-                rev_u.username AS reviewed_by_username,
-                r.reviewed_at,
-                r.review_note,
-                r.created_at,
-                r.updated_at
-         FROM contest_registrations r
-         JOIN contests c ON c.id = r.contest_id
-         JOIN teams t ON t.id = r.team_id
-         LEFT JOIN users req_u ON req_u.id = r.requested_by
-         LEFT JOIN users rev_u ON rev_u.id = r.reviewed_by
-         WHERE r.contest_id = $1
-           AND ($2::text IS NULL OR r.status = $2)
-         ORDER BY r.requested_at DESC, r.created_at DESC
-         LIMIT $3",
+    let rows = sqlx::query_as::<_, AdminFlagSharingIncidentItem>(
+        "SELECT i.id,
+                i.contest_id,
+                i.challenge_id,
+                c.title AS challenge_title,
+                i.submitter_team_id,
+                st.name AS submitter_team_name,
+                i.submitter_user_id,
+                su.username AS submitter_username,
+                i.owner_team_id,
+                ot.name AS owner_team_name,
+                i.first_submission_id,
+                i.flag_issued_at,
+                i.first_submitted_at,
+                i.last_submitted_at,
+                i.occurrence_count,
+                i.status,
+                i.review_note,
+                i.reviewed_by,
+                ru.username AS reviewed_by_username,
+                i.reviewed_at,
+                i.created_at,
+                i.updated_at
+         FROM flag_sharing_incidents i
+         JOIN challenges c ON c.id = i.challenge_id
+         JOIN teams st ON st.id = i.submitter_team_id
+         JOIN teams ot ON ot.id = i.owner_team_id
+         LEFT JOIN users su ON su.id = i.submitter_user_id
+         LEFT JOIN users ru ON ru.id = i.reviewed_by
+         WHERE i.contest_id = $1
+           AND ($2::text IS NULL OR i.status = $2)
+           AND ($3::uuid IS NULL OR i.challenge_id = $3)
+         ORDER BY i.last_submitted_at DESC, i.created_at DESC
+         LIMIT $4",
     )
     .bind(contest_id)
     .bind(status_filter)
+    .bind(query.challenge_id)
     .bind(limit)
     .fetch_all(&state.db)
     .await
@@ -4685,19 +7546,19 @@ async fn list_contest_registrations(
     Ok(Json(rows))
 }
 
-async fn update_contest_registration(
+async fn update_flag_sharing_incident(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
-    Path((contest_id, registration_id)): Path<(Uuid, Uuid)>,
-    Json(req): Json<UpdateContestRegistrationRequest>,
-) -> AppResult<Json<AdminContestRegistrationItem>> {
+    Path((contest_id, incident_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateFlagSharingIncidentRequest>,
+) -> AppResult<Json<AdminFlagSharingIncidentItem>> {
     ensure_admin_or_judge(&current_user)?;
     ensure_contest_exists(state.as_ref(), contest_id).await?;
 
     let status = normalize_with_allowed(
         req.status.as_str(),
-        CONTEST_REGISTRATION_STATUS_ALLOWED,
-        "registration_status",
+        FLAG_SHARING_INCIDENT_STATUS_ALLOWED,
+        "status",
     )?;
     let review_note = req
         .review_note
@@ -4711,51 +7572,49 @@ async fn update_contest_registration(
         ));
     }
 
-    let row = sqlx::query_as::<_, AdminContestRegistrationItem>(
+    let row = sqlx::query_as::<_, AdminFlagSharingIncidentItem>(
         "WITH updated AS (
-            UPDATE contest_registrations
+            UPDATE flag_sharing_incidents
             SET status = $3,
-                reviewed_by = CASE WHEN $3 = 'pending' THEN NULL ELSE $4 END,
-                reviewed_at = CASE WHEN $3 = 'pending' THEN NULL ELSE NOW() END,
+                reviewed_by = CASE WHEN $3 = 'open' THEN NULL ELSE $4 END,
+                reviewed_at = CASE WHEN $3 = 'open' THEN NULL ELSE NOW() END,
                 review_note = $5,
                 updated_at = NOW()
             WHERE contest_id = $1
               AND id = $2
-            RETURNING id,
-                      contest_id,
-                      team_id,
-                      status,
-                      requested_by,
-                      requested_at,
-                      reviewed_by,
-                      reviewed_at,
-                      review_note,
-                      created_at,
-                      updated_at
+            RETURNING *
          )
          SELECT u.id,
                 u.contest_id,
-                c.title AS contest_title,
-                u.team_id,
-                t.name AS team_name,
+                u.challenge_id,
+                c.title AS challenge_title,
+                u.submitter_team_id,
+                st.name AS submitter_team_name,
+                u.submitter_user_id,
+                su.username AS submitter_username,
+                u.owner_team_id,
+                ot.name AS owner_team_name,
+                u.first_submission_id,
+                u.flag_issued_at,
+                u.first_submitted_at,
+                u.last_submitted_at,
+                u.occurrence_count,
                 u.status,
-                u.requested_by,
-                req_u.username AS requested_by_username,
-                u.requested_at,
+                u.review_note,
                 u.reviewed_by,
-                rev_u.username AS reviewed_by_username,
+                ru.username AS reviewed_by_username,
                 u.reviewed_at,
-                u.review_note,
                 u.created_at,
                 u.updated_at
          FROM updated u
-         JOIN contests c ON c.id = u.contest_id
-         JOIN teams t ON t.id = u.team_id
-         LEFT JOIN users req_u ON req_u.id = u.requested_by
-         LEFT JOIN users rev_u ON rev_u.id = u.reviewed_by",
+         JOIN challenges c ON c.id = u.challenge_id
+         JOIN teams st ON st.id = u.submitter_team_id
+         JOIN teams ot ON ot.id = u.owner_team_id
+         LEFT JOIN users su ON su.id = u.submitter_user_id
+         LEFT JOIN users ru ON ru.id = u.reviewed_by",
     )
     .bind(contest_id)
-    .bind(registration_id)
+    .bind(incident_id)
     .bind(&status)
     .bind(current_user.user_id)
     .bind(&review_note)
@@ -4763,18 +7622,20 @@ async fn update_contest_registration(
     .await
     .map_err(AppError::internal)?
     .ok_or(AppError::BadRequest(
-        "contest registration not found".to_string(),
+        "flag sharing incident not found".to_string(),
     ))?;
 
     record_audit_log(
         state.as_ref(),
         &current_user,
-        "admin.contest.registration.update",
-        "contest_registration",
+        "admin.contest.flag_sharing_incident.update",
+        "flag_sharing_incident",
         Some(row.id),
         json!({
             "contest_id": row.contest_id,
-            "team_id": row.team_id,
+            "challenge_id": row.challenge_id,
+            "submitter_team_id": row.submitter_team_id,
+            "owner_team_id": row.owner_team_id,
             "status": row.status,
             "review_note": row.review_note
         }),
@@ -4820,7 +7681,7 @@ async fn list_instances(
          FROM instances i
          JOIN contests ct ON ct.id = i.contest_id
          JOIN challenges c ON c.id = i.challenge_id
-         JOIN teams t ON t.id = i.team_id
+         LEFT JOIN teams t ON t.id = i.team_id
          WHERE ($1::text IS NULL OR i.status = $1)
          ORDER BY i.updated_at DESC
          LIMIT $2",
@@ -4959,7 +7820,7 @@ async fn load_admin_instance_item(
          FROM instances i
          JOIN contests ct ON ct.id = i.contest_id
          JOIN challenges c ON c.id = i.challenge_id
-         JOIN teams t ON t.id = i.team_id
+         LEFT JOIN teams t ON t.id = i.team_id
          WHERE i.id = $1
          LIMIT 1",
     )
@@ -5416,6 +8277,369 @@ async fn list_audit_logs(
     Ok(Json(rows))
 }
 
+const ADMIN_SUBMISSION_SELECT_SQL: &str = "SELECT s.id,
+                s.contest_id,
+                ct.title AS contest_title,
+                s.challenge_id,
+                c.title AS challenge_title,
+                s.team_id,
+                t.name AS team_name,
+                s.user_id,
+                u.username,
+                s.submitted_flag,
+                s.verdict,
+                s.score_awarded,
+                s.blood_bonus,
+                s.penalty_points,
+                s.stage_key,
+                s.judger_message,
+                s.submitted_at,
+                s.judged_at
+         FROM submissions s
+         JOIN contests ct ON ct.id = s.contest_id
+         JOIN challenges c ON c.id = s.challenge_id
+         JOIN teams t ON t.id = s.team_id
+         JOIN users u ON u.id = s.user_id";
+
+/// WHERE conditions on `submissions s` for [`AdminSubmissionFilter`], bound
+/// as `$1..$8` by [`bind_admin_submission_filter`].
+const ADMIN_SUBMISSION_FILTER_SQL: &str = "($1::uuid IS NULL OR s.contest_id = $1)
+           AND ($2::uuid IS NULL OR s.challenge_id = $2)
+           AND ($3::uuid IS NULL OR s.team_id = $3)
+           AND ($4::uuid IS NULL OR s.user_id = $4)
+           AND ($5::text IS NULL OR s.verdict = $5)
+           AND ($6::timestamptz IS NULL OR s.submitted_at >= $6)
+           AND ($7::timestamptz IS NULL OR s.submitted_at < $7)
+           AND ($8::text IS NULL OR strpos(lower(s.submitted_flag), lower($8)) > 0)";
+
+fn admin_submission_filter(query: &AdminSubmissionsQuery) -> AppResult<AdminSubmissionFilter> {
+    let verdict = query
+        .verdict
+        .as_deref()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| normalize_with_allowed(value, SUBMISSION_VERDICT_ALLOWED, "verdict"))
+        .transpose()?;
+
+    if let (Some(from), Some(to)) = (query.submitted_from, query.submitted_to) {
+        if from >= to {
+            return Err(AppError::BadRequest(
+                "submitted_from must be earlier than submitted_to".to_string(),
+            ));
+        }
+    }
+
+    let flag_contains = query
+        .flag_contains
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string);
+    if flag_contains
+        .as_ref()
+        .is_some_and(|value| value.chars().count() > MAX_SUBMISSION_FLAG_FILTER_CHARS)
+    {
+        return Err(AppError::BadRequest(format!(
+            "flag_contains must be at most {} characters",
+            MAX_SUBMISSION_FLAG_FILTER_CHARS
+        )));
+    }
+
+    Ok(AdminSubmissionFilter {
+        contest_id: query.contest_id,
+        challenge_id: query.challenge_id,
+        team_id: query.team_id,
+        user_id: query.user_id,
+        verdict,
+        submitted_from: query.submitted_from,
+        submitted_to: query.submitted_to,
+        flag_contains,
+    })
+}
+
+fn bind_admin_submission_filter<'q, O>(
+    query: QueryAs<'q, Postgres, O, PgArguments>,
+    filter: &AdminSubmissionFilter,
+) -> QueryAs<'q, Postgres, O, PgArguments> {
+    query
+        .bind(filter.contest_id)
+        .bind(filter.challenge_id)
+        .bind(filter.team_id)
+        .bind(filter.user_id)
+        .bind(filter.verdict.clone())
+        .bind(filter.submitted_from)
+        .bind(filter.submitted_to)
+        .bind(filter.flag_contains.clone())
+}
+
+async fn list_admin_submissions(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Query(query): Query<AdminSubmissionsQuery>,
+) -> AppResult<Json<Vec<AdminSubmissionItem>>> {
+    ensure_admin_or_judge(&current_user)?;
+
+    let filter = admin_submission_filter(&query)?;
+    let limit = query.limit.unwrap_or(200).clamp(1, 1000);
+
+    let sql = format!(
+        "{}
+         WHERE {}
+           AND ($9::bigint IS NULL OR s.id < $9)
+         ORDER BY s.id DESC
+         LIMIT $10",
+        ADMIN_SUBMISSION_SELECT_SQL, ADMIN_SUBMISSION_FILTER_SQL
+    );
+    let rows =
+        bind_admin_submission_filter(sqlx::query_as::<_, AdminSubmissionItem>(&sql), &filter)
+            .bind(query.before_id)
+            .bind(limit)
+            .fetch_all(&state.db)
+            .await
+            .map_err(AppError::internal)?;
+
+    Ok(Json(rows))
+}
+
+async fn get_admin_submission_summary(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Query(query): Query<AdminSubmissionsQuery>,
+) -> AppResult<Json<AdminSubmissionSummaryResponse>> {
+    ensure_admin_or_judge(&current_user)?;
+
+    let filter = admin_submission_filter(&query)?;
+
+    let totals_sql = format!(
+        "SELECT COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE s.verdict = 'accepted') AS accepted,
+                COUNT(*) FILTER (WHERE s.verdict = 'wrong') AS wrong,
+                COUNT(*) FILTER (WHERE s.verdict = 'invalid') AS invalid,
+                COUNT(*) FILTER (WHERE s.verdict = 'rate_limited') AS rate_limited,
+                COUNT(*) FILTER (WHERE s.verdict = 'error') AS error,
+                COUNT(DISTINCT s.team_id) AS distinct_teams,
+                COUNT(DISTINCT s.user_id) AS distinct_users,
+                COALESCE(SUM(s.penalty_points), 0)::bigint AS penalty_points,
+                MIN(s.submitted_at) AS first_submitted_at,
+                MAX(s.submitted_at) AS last_submitted_at
+         FROM submissions s
+         WHERE {}",
+        ADMIN_SUBMISSION_FILTER_SQL
+    );
+    let totals = bind_admin_submission_filter(
+        sqlx::query_as::<_, AdminSubmissionTotals>(&totals_sql),
+        &filter,
+    )
+    .fetch_one(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let challenges_sql = format!(
+        "SELECT s.challenge_id,
+                c.title AS challenge_title,
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE s.verdict = 'accepted') AS accepted,
+                COUNT(*) FILTER (WHERE s.verdict = 'wrong') AS wrong,
+                COUNT(DISTINCT s.team_id) FILTER (WHERE s.verdict = 'accepted') AS solved_teams
+         FROM submissions s
+         JOIN challenges c ON c.id = s.challenge_id
+         WHERE {}
+         GROUP BY s.challenge_id, c.title
+         ORDER BY total DESC, c.title ASC",
+        ADMIN_SUBMISSION_FILTER_SQL
+    );
+    let challenges = bind_admin_submission_filter(
+        sqlx::query_as::<_, AdminSubmissionChallengeSummary>(&challenges_sql),
+        &filter,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(Json(AdminSubmissionSummaryResponse { totals, challenges }))
+}
+
+/// Streams every matching submission, oldest first, as CSV or a JSON array.
+/// Rows are read from a database cursor so large contests are not buffered.
+async fn export_admin_submissions(
+    State(state): State<Arc<AppState>>,
+    current_user: AuthenticatedUser,
+    Query(query): Query<AdminSubmissionsQuery>,
+) -> AppResult<Response> {
+    ensure_admin_or_judge(&current_user)?;
+
+    let filter = admin_submission_filter(&query)?;
+    let format = query
+        .format
+        .as_deref()
+        .map(|value| normalize_with_allowed(value, SUBMISSION_EXPORT_FORMAT_ALLOWED, "format"))
+        .transpose()?
+        .unwrap_or_else(|| "csv".to_string());
+
+    record_audit_log(
+        state.as_ref(),
+        &current_user,
+        "admin.submissions.export",
+        "submission",
+        filter.contest_id,
+        json!({
+            "format": format,
+            "filter": filter
+        }),
+    )
+    .await;
+
+    let csv = format == "csv";
+    // Errors are sent into the body so the response aborts instead of ending
+    // like a complete, but truncated, export.
+    let (sender, receiver) = mpsc::channel::<Result<Bytes, std::io::Error>>(64);
+    let state_clone = Arc::clone(&state);
+    let filter_clone = filter.clone();
+
+    tokio::spawn(async move {
+        let sql = format!(
+            "{}
+             WHERE {}
+             ORDER BY s.id ASC",
+            ADMIN_SUBMISSION_SELECT_SQL, ADMIN_SUBMISSION_FILTER_SQL
+        );
+        let mut rows = bind_admin_submission_filter(
+            sqlx::query_as::<_, AdminSubmissionItem>(&sql),
+            &filter_clone,
+        )
+        .fetch(&state_clone.db);
+
+        let header = if csv {
+            csv_line(
+                &[
+                    "id",
+                    "contest_id",
+                    "contest_title",
+                    "challenge_id",
+                    "challenge_title",
+                    "team_id",
+                    "team_name",
+                    "user_id",
+                    "username",
+                    "submitted_flag",
+                    "verdict",
+                    "score_awarded",
+                    "blood_bonus",
+                    "penalty_points",
+                    "stage_key",
+                    "judger_message",
+                    "submitted_at",
+                    "judged_at",
+                ]
+                .map(str::to_string),
+            )
+        } else {
+            "[".to_string()
+        };
+        if sender.send(Ok(Bytes::from(header))).await.is_err() {
+            return;
+        }
+
+        let mut first = true;
+        loop {
+            let row = match rows.try_next().await {
+                Ok(Some(row)) => row,
+                Ok(None) => break,
+                Err(err) => {
+                    warn!(error = %err, "failed to stream submissions export");
+                    let _ = sender.send(Err(std::io::Error::other(err))).await;
+                    return;
+                }
+            };
+
+            let chunk = if csv {
+                admin_submission_csv_line(&row)
+            } else {
+                let value = match serde_json::to_string(&row) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        warn!(error = %err, "failed to serialize submissions export row");
+                        let _ = sender.send(Err(std::io::Error::other(err))).await;
+                        return;
+                    }
+                };
+                if first {
+                    format!("\n{}", value)
+                } else {
+                    format!(",\n{}", value)
+                }
+            };
+            first = false;
+
+            if sender.send(Ok(Bytes::from(chunk))).await.is_err() {
+                return;
+            }
+        }
+
+        if !csv {
+            let _ = sender.send(Ok(Bytes::from_static(b"\n]\n"))).await;
+        }
+    });
+
+    let body_stream = stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|item| (item, receiver))
+    });
+
+    let (content_type, extension) = if csv {
+        ("text/csv; charset=utf-8", "csv")
+    } else {
+        ("application/json; charset=utf-8", "json")
+    };
+    let filename = format!(
+        "submissions-{}-{}.{}",
+        filter
+            .contest_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "all".to_string()),
+        Utc::now().format("%Y%m%d%H%M%S"),
+        extension
+    );
+
+    let mut response = Response::new(Body::from_stream(body_stream));
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    if let Ok(value) = HeaderValue::from_str(&format!("attachment; filename=\"{}\"", filename)) {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, value);
+    }
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+
+    Ok(response)
+}
+
+fn admin_submission_csv_line(row: &AdminSubmissionItem) -> String {
+    csv_line(&[
+        row.id.to_string(),
+        row.contest_id.to_string(),
+        csv_text_field(&row.contest_title),
+        row.challenge_id.to_string(),
+        csv_text_field(&row.challenge_title),
+        row.team_id.to_string(),
+        csv_text_field(&row.team_name),
+        row.user_id.to_string(),
+        csv_text_field(&row.username),
+        csv_text_field(&row.submitted_flag),
+        row.verdict.clone(),
+        row.score_awarded.to_string(),
+        row.blood_bonus.to_string(),
+        row.penalty_points.to_string(),
+        csv_text_field(row.stage_key.as_deref().unwrap_or("")),
+        csv_text_field(&row.judger_message),
+        row.submitted_at.to_rfc3339(),
+        row.judged_at
+            .map(|value| value.to_rfc3339())
+            .unwrap_or_default(),
+    ])
+}
+
 async fn list_runtime_alerts(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
@@ -5632,10 +8856,11 @@ async fn get_runtime_overview(
         .await
         .map_err(AppError::internal)?;
 
-    let total_teams = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM teams")
-        .fetch_one(&state.db)
-        .await
-        .map_err(AppError::internal)?;
+    let total_teams =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM teams WHERE solo_user_id IS NULL")
+            .fetch_one(&state.db)
+            .await
+            .map_err(AppError::internal)?;
 
     let total_contests = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM contests")
         .fetch_one(&state.db)
@@ -5722,7 +8947,7 @@ async fn get_runtime_overview(
          FROM instances i
          JOIN contests ct ON ct.id = i.contest_id
          JOIN challenges c ON c.id = i.challenge_id
-         JOIN teams t ON t.id = i.team_id
+         LEFT JOIN teams t ON t.id = i.team_id
          WHERE i.status = 'failed'
          ORDER BY i.updated_at DESC
          LIMIT 20",
@@ -5805,7 +9030,7 @@ async fn collect_runtime_alert_candidates(
          FROM instances i
          JOIN contests ct ON ct.id = i.contest_id
          JOIN challenges c ON c.id = i.challenge_id
-         JOIN teams t ON t.id = i.team_id
+         LEFT JOIN teams t ON t.id = i.team_id
          WHERE i.status = 'failed'
          ORDER BY i.updated_at DESC",
     )
@@ -5816,7 +9041,7 @@ async fn collect_runtime_alert_candidates(
     for row in failed_rows {
         let message = format!(
             "实例 {} / {} / {} 处于 failed 状态，最后更新时间 {}",
-            row.contest_title, row.challenge_title, row.team_name, row.updated_at
+            row.contest_title, row.challenge_title, row.team_label(), row.updated_at
         );
         candidates.push(build_runtime_alert_candidate(
             RUNTIME_ALERT_TYPE_INSTANCE_FAILED,
@@ -5842,7 +9067,7 @@ async fn collect_runtime_alert_candidates(
          FROM instances i
          JOIN contests ct ON ct.id = i.contest_id
          JOIN challenges c ON c.id = i.challenge_id
-         JOIN teams t ON t.id = i.team_id
+         LEFT JOIN teams t ON t.id = i.team_id
          WHERE i.status = 'running'
            AND i.expires_at IS NOT NULL
            AND i.expires_at > NOW()
@@ -5857,7 +9082,7 @@ async fn collect_runtime_alert_candidates(
         if let Some(expires_at) = row.expires_at {
             let message = format!(
                 "实例 {} / {} / {} 将在 {} 过期",
-                row.contest_title, row.challenge_title, row.team_name, expires_at
+                row.contest_title, row.challenge_title, row.team_label(), expires_at
             );
             candidates.push(build_runtime_alert_candidate(
                 RUNTIME_ALERT_TYPE_INSTANCE_EXPIRING_SOON,
@@ -5884,7 +9109,7 @@ async fn collect_runtime_alert_candidates(
          FROM instances i
          JOIN contests ct ON ct.id = i.contest_id
          JOIN challenges c ON c.id = i.challenge_id
-         JOIN teams t ON t.id = i.team_id
+         LEFT JOIN teams t ON t.id = i.team_id
          WHERE i.status <> 'destroyed'
            AND i.expires_at IS NOT NULL
            AND i.expires_at <= NOW()
@@ -5898,7 +9123,7 @@ async fn collect_runtime_alert_candidates(
         if let Some(expires_at) = row.expires_at {
             let message = format!(
                 "实例 {} / {} / {} 已在 {} 过期但尚未销毁（当前状态：{}）",
-                row.contest_title, row.challenge_title, row.team_name, expires_at, row.status
+                row.contest_title, row.challenge_title, row.team_label(), expires_at, row.status
             );
             candidates.push(build_runtime_alert_candidate(
                 RUNTIME_ALERT_TYPE_INSTANCE_EXPIRED_NOT_DESTROYED,
@@ -5925,7 +9150,7 @@ async fn collect_runtime_alert_candidates(
          FROM instances i
          JOIN contests ct ON ct.id = i.contest_id
          JOIN challenges c ON c.id = i.challenge_id
-         JOIN teams t ON t.id = i.team_id
+         LEFT JOIN teams t ON t.id = i.team_id
          WHERE i.status = 'running'
            AND i.last_heartbeat_at IS NOT NULL
            AND i.last_heartbeat_at <= NOW() - ($1::bigint * INTERVAL '1 second')
@@ -5942,7 +9167,7 @@ async fn collect_runtime_alert_candidates(
                 "实例 {} / {} / {} 心跳停留在 {}，超过 {} 分钟阈值，可能存在异常",
                 row.contest_title,
                 row.challenge_title,
-                row.team_name,
+                row.team_label(),
                 last_heartbeat_at,
                 heartbeat_stale_minutes
             );
@@ -6173,6 +9398,26 @@ async fn ensure_contest_exists(state: &AppState, contest_id: Uuid) -> AppResult<
     }
 }
 
+/// Registrations are keyed by team; switching between team and solo mode
+/// would strand them, so the mode is fixed once anyone has registered.
+async fn ensure_contest_has_no_registrations(state: &AppState, contest_id: Uuid) -> AppResult<()> {
+    let registered = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM contest_registrations WHERE contest_id = $1)",
+    )
+    .bind(contest_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    if registered {
+        Err(AppError::Conflict(
+            "participation_mode cannot be changed after registrations exist".to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 fn ensure_admin_or_judge(user: &AuthenticatedUser) -> AppResult<()> {
     if user.role == "admin" || user.role == "judge" {
         return Ok(());
@@ -6304,13 +9549,14 @@ fn validate_compose_runtime_configuration(
     compose_template: Option<&str>,
     metadata: &Value,
 ) -> AppResult<()> {
-    let requires_runtime = challenge_type == "dynamic" || challenge_type == "internal";
+    let requires_runtime =
+        challenge_type == "dynamic" || challenge_type == "internal" || challenge_type == "koth";
     let runtime_options = parse_runtime_metadata_options(metadata).map_err(AppError::BadRequest)?;
 
     if runtime_options.mode == RuntimeMode::SingleImage {
         if !requires_runtime {
             return Err(AppError::BadRequest(
-                "metadata.runtime.mode=single_image requires challenge_type=dynamic/internal"
+                "metadata.runtime.mode=single_image requires challenge_type=dynamic/internal/koth"
                     .to_string(),
             ));
         }
@@ -6329,7 +9575,8 @@ fn validate_compose_runtime_configuration(
             validate_compose_template_schema(template, metadata).map_err(AppError::BadRequest)
         }
         None if requires_runtime => Err(AppError::BadRequest(
-            "challenge runtime template is required for dynamic/internal challenge".to_string(),
+            "challenge runtime template is required for dynamic/internal/koth challenge"
+                .to_string(),
         )),
         None => Ok(()),
     }
@@ -6415,33 +9662,10 @@ fn normalize_tags(tags: Vec<String>) -> AppResult<Vec<String>> {
     Ok(out)
 }
 
-fn normalize_hints(hints: Vec<String>) -> AppResult<Vec<String>> {
-    if hints.len() > 20 {
-        return Err(AppError::BadRequest(
-            "hints must be at most 20 items".to_string(),
-        ));
-    }
-
-    let mut out: Vec<String> = Vec::new();
-    for hint in hints {
-        let normalized = hint.trim().to_string();
-        if normalized.is_empty() {
-            continue;
-        }
-        if normalized.chars().count() > 500 {
-            return Err(AppError::BadRequest(
-                "each hint must be at most 500 characters".to_string(),
-            ));
-        }
-        if !out.iter().any(|item| item == &normalized) {
-            out.push(normalized);
-        }
-    }
-
-    Ok(out)
-}
-
-fn challenge_item_from_snapshot_row(row: &ChallengeSnapshotRow) -> AdminChallengeItem {
+fn challenge_item_from_snapshot_row(
+    row: &ChallengeSnapshotRow,
+    hints: &[ChallengeSnapshotHint],
+) -> AdminChallengeItem {
     AdminChallengeItem {
         id: row.id,
         title: row.title.clone(),
@@ -6454,6 +9678,7 @@ fn challenge_item_from_snapshot_row(row: &ChallengeSnapshotRow) -> AdminChalleng
         status: row.status.clone(),
         is_visible: row.is_visible,
         tags: row.tags.clone(),
+        hints: hints.iter().map(|hint| hint.content.clone()).collect(),
         writeup_visibility: row.writeup_visibility.clone(),
         current_version: row.current_version,
         created_at: row.created_at,
@@ -6461,7 +9686,10 @@ fn challenge_item_from_snapshot_row(row: &ChallengeSnapshotRow) -> AdminChalleng
     }
 }
 
-fn challenge_snapshot_to_value(row: &ChallengeSnapshotRow) -> Value {
+fn challenge_snapshot_to_value(
+    row: &ChallengeSnapshotRow,
+    hints: &[ChallengeSnapshotHint],
+) -> Value {
     serde_json::to_value(ChallengeSnapshot {
         title: row.title.clone(),
         slug: row.slug.clone(),
@@ -6475,11 +9703,13 @@ fn challenge_snapshot_to_value(row: &ChallengeSnapshotRow) -> Value {
         flag_mode: row.flag_mode.clone(),
         status: row.status.clone(),
         flag_hash: row.flag_hash.clone(),
+        flag_rules: parse_flag_rules(&row.flag_rules),
+        stages: parse_challenge_stages(&row.stages),
         compose_template: row.compose_template.clone(),
         metadata: row.metadata.clone(),
         is_visible: row.is_visible,
         tags: row.tags.clone(),
-        hints: row.hints.clone(),
+        hints: Some(hints.to_vec()),
         writeup_visibility: row.writeup_visibility.clone(),
         writeup_content: row.writeup_content.clone(),
     })
@@ -7128,6 +10358,32 @@ fn validate_blood_bonus_percent(value: i32, field: &str) -> AppResult<i32> {
     Ok(value)
 }
 
+fn validate_wrong_backoff_bounds(base_seconds: i32, max_seconds: i32) -> AppResult<()> {
+    if max_seconds < base_seconds {
+        return Err(AppError::BadRequest(
+            "wrong_backoff_max_seconds must not be less than wrong_backoff_base_seconds"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_contest_int_range(
+    value: i32,
+    range: std::ops::RangeInclusive<i32>,
+    field: &str,
+) -> AppResult<i32> {
+    if !range.contains(&value) {
+        return Err(AppError::BadRequest(format!(
+            "{} must be between {} and {}",
+            field,
+            range.start(),
+            range.end()
+        )));
+    }
+    Ok(value)
+}
+
 fn is_unique_violation(err: &sqlx::Error) -> bool {
     match err {
         sqlx::Error::Database(db_err) => db_err.code().as_deref() == Some("23505"),
@@ -7151,7 +10407,7 @@ fn hash_password(password: &str) -> AppResult<String> {
     Ok(hash)
 }
 
-async fn record_audit_log(
+pub(crate) async fn record_audit_log(
     state: &AppState,
     current_user: &AuthenticatedUser,
     action: &str,
@@ -7177,7 +10433,7 @@ async fn record_audit_log(
             action,
             target_type,
             error = %err,
-            "failed to write admin audit log"
+            "failed to write audit log"
         );
     }
 }
@@ -0,0 +1,842 @@
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{AppError, AppResult},
+    routes::{
+        contest_access::ensure_user_contest_workspace_access,
+        scoreboard::{publish_scoreboard_update, SCOREBOARD_EVENT_UPDATE},
+        scoring::ScoreAdjustment,
+        webhook_verifier::{
+            parse_signed_endpoint_config, post_signed_json, truncate_message, WebhookVerifierConfig,
+        },
+    },
+    state::AppState,
+};
+
+pub(crate) const CONTEST_TYPE_ATTACK_DEFENSE: &str = "attack_defense";
+const AD_CHECKER_METADATA_KEY: &str = "ad_checker";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AdServiceStatus {
+    Up,
+    Mumble,
+    Down,
+    Error,
+}
+
+impl AdServiceStatus {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Mumble => "mumble",
+            Self::Down => "down",
+            Self::Error => "error",
+        }
+    }
+
+    /// Unknown answers count as a checker error rather than a team fault.
+    fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "up" => Self::Up,
+            "mumble" => Self::Mumble,
+            "down" => Self::Down,
+            _ => Self::Error,
+        }
+    }
+}
+
+/// Body POSTed to `metadata.ad_checker` once per service and round. The
+/// checker stores `flag` in the service at `target` and reports its health.
+#[derive(Debug, Serialize)]
+struct AdCheckerRequest<'a> {
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Uuid,
+    round: i32,
+    flag: &'a str,
+    target: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdCheckerResponse {
+    status: String,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub(crate) struct AdRoundItem {
+    pub id: i64,
+    pub round_number: i32,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AdRoundAdvanceSummary {
+    pub round: AdRoundItem,
+    pub services: i64,
+    pub services_up: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AttackDefenseTickSummary {
+    pub due: i64,
+    pub advanced: i64,
+    pub failed: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct AdContestRow {
+    ad_round_seconds: i32,
+    ad_flag_lifetime_rounds: i32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct AdParticipantRow {
+    pub team_id: Uuid,
+    pub team_name: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct AdServiceRow {
+    pub challenge_id: Uuid,
+    pub challenge_title: String,
+    metadata: Value,
+}
+
+#[derive(Debug, FromRow)]
+struct AdFlagLookupRow {
+    id: i64,
+    challenge_id: Uuid,
+    team_id: Uuid,
+    team_name: String,
+    round_number: i32,
+    current_round: i32,
+    ad_flag_lifetime_rounds: i32,
+    ad_attack_points: i32,
+}
+
+/// Result of submitting a flag in an attack-defense contest.
+#[derive(Debug)]
+pub(crate) enum AttackCapture {
+    Captured {
+        victim_team_name: String,
+        points: i32,
+    },
+    Duplicate,
+    Unknown,
+    OwnFlag,
+    OtherService,
+    Expired,
+}
+
+#[derive(Debug, FromRow)]
+struct AdServiceStatusRow {
+    team_id: Uuid,
+    team_name: String,
+    challenge_id: Uuid,
+    challenge_title: String,
+    target: Option<String>,
+    status: Option<String>,
+    message: Option<String>,
+    checked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+struct AdServiceStatusItem {
+    team_id: Uuid,
+    team_name: String,
+    challenge_id: Uuid,
+    challenge_title: String,
+    target: Option<String>,
+    status: Option<String>,
+    message: Option<String>,
+    checked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+struct AttackDefenseStatusResponse {
+    contest_id: Uuid,
+    round_seconds: i32,
+    flag_lifetime_rounds: i32,
+    round: Option<AdRoundItem>,
+    round_ends_at: Option<DateTime<Utc>>,
+    services: Vec<AdServiceStatusItem>,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route(
+        "/contests/{contest_id}/attack-defense",
+        get(get_attack_defense_status),
+    )
+}
+
+/// Current round and every team's service with its latest check. Checker
+/// messages are only shown for the caller's own services.
+async fn get_attack_defense_status(
+    State(state): State<Arc<AppState>>,
+    Path(contest_id): Path<Uuid>,
+    current_user: AuthenticatedUser,
+) -> AppResult<Json<AttackDefenseStatusResponse>> {
+    let team_id =
+        ensure_user_contest_workspace_access(state.as_ref(), contest_id, &current_user).await?;
+    let contest = load_ad_contest(state.as_ref(), contest_id).await?;
+
+    let round = sqlx::query_as::<_, AdRoundItem>(
+        "SELECT id, round_number, started_at, ended_at
+         FROM ad_rounds
+         WHERE contest_id = $1 AND ended_at IS NULL
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let rows = sqlx::query_as::<_, AdServiceStatusRow>(
+        "SELECT p.team_id,
+                p.team_name,
+                c.id AS challenge_id,
+                c.title AS challenge_title,
+                i.entrypoint_url AS target,
+                chk.status,
+                chk.message,
+                chk.checked_at
+         FROM (
+             SELECT t.id AS team_id, t.name AS team_name
+             FROM contest_registrations r
+             JOIN contests ct ON ct.id = r.contest_id
+             JOIN teams t ON t.id = r.team_id
+             WHERE r.contest_id = $1
+               AND (r.status = 'approved'
+                    OR (r.status = 'pending' AND ct.registration_requires_approval = FALSE))
+         ) p
+         CROSS JOIN contest_challenges cc
+         JOIN challenges c ON c.id = cc.challenge_id
+         LEFT JOIN instances i
+           ON i.contest_id = cc.contest_id
+          AND i.challenge_id = cc.challenge_id
+          AND i.team_id = p.team_id
+          AND i.status = 'running'
+         LEFT JOIN LATERAL (
+             SELECT s.status, s.message, s.checked_at
+             FROM ad_service_checks s
+             WHERE s.contest_id = cc.contest_id
+               AND s.challenge_id = cc.challenge_id
+               AND s.team_id = p.team_id
+             ORDER BY s.checked_at DESC, s.id DESC
+             LIMIT 1
+         ) chk ON TRUE
+         WHERE cc.contest_id = $1
+           AND c.is_visible = TRUE
+           AND (cc.release_at IS NULL OR cc.release_at <= NOW())
+         ORDER BY p.team_name ASC, cc.sort_order ASC, c.title ASC",
+    )
+    .bind(contest_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let services = rows
+        .into_iter()
+        .map(|row| {
+            let own = team_id.is_none_or(|value| value == row.team_id);
+            AdServiceStatusItem {
+                team_id: row.team_id,
+                team_name: row.team_name,
+                challenge_id: row.challenge_id,
+                challenge_title: row.challenge_title,
+                target: row.target,
+                status: row.status,
+                message: if own { row.message } else { None },
+                checked_at: row.checked_at,
+            }
+        })
+        .collect();
+
+    let round_ends_at = round
+        .as_ref()
+        .map(|item| item.started_at + Duration::seconds(contest.ad_round_seconds as i64));
+
+    Ok(Json(AttackDefenseStatusResponse {
+        contest_id,
+        round_seconds: contest.ad_round_seconds,
+        flag_lifetime_rounds: contest.ad_flag_lifetime_rounds,
+        round,
+        round_ends_at,
+        services,
+    }))
+}
+
+async fn load_ad_contest(state: &AppState, contest_id: Uuid) -> AppResult<AdContestRow> {
+    let row = sqlx::query_as::<_, (String, i32, i32)>(
+        "SELECT contest_type, ad_round_seconds, ad_flag_lifetime_rounds
+         FROM contests
+         WHERE id = $1
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest("contest not found".to_string()))?;
+
+    if row.0 != CONTEST_TYPE_ATTACK_DEFENSE {
+        return Err(AppError::BadRequest(
+            "contest is not an attack-defense contest".to_string(),
+        ));
+    }
+
+    Ok(AdContestRow {
+        ad_round_seconds: row.1,
+        ad_flag_lifetime_rounds: row.2,
+    })
+}
+
+pub(crate) async fn ensure_attack_defense_contest(
+    state: &AppState,
+    contest_id: Uuid,
+) -> AppResult<()> {
+    load_ad_contest(state, contest_id).await.map(|_| ())
+}
+
+/// Teams whose registration lets them into the contest workspace.
+pub(crate) async fn load_ad_participants(
+    state: &AppState,
+    contest_id: Uuid,
+) -> AppResult<Vec<AdParticipantRow>> {
+    sqlx::query_as::<_, AdParticipantRow>(
+        "SELECT t.id AS team_id,
+                t.name AS team_name
+         FROM contest_registrations r
+         JOIN contests ct ON ct.id = r.contest_id
+         JOIN teams t ON t.id = r.team_id
+         WHERE r.contest_id = $1
+           AND (r.status = 'approved'
+                OR (r.status = 'pending' AND ct.registration_requires_approval = FALSE))
+         ORDER BY t.name ASC",
+    )
+    .bind(contest_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)
+}
+
+/// Visible and released challenges of the contest; each is one service.
+pub(crate) async fn load_ad_services(
+    state: &AppState,
+    contest_id: Uuid,
+) -> AppResult<Vec<AdServiceRow>> {
+    sqlx::query_as::<_, AdServiceRow>(
+        "SELECT c.id AS challenge_id,
+                c.title AS challenge_title,
+                c.metadata
+         FROM contest_challenges cc
+         JOIN challenges c ON c.id = cc.challenge_id
+         WHERE cc.contest_id = $1
+           AND c.is_visible = TRUE
+           AND (cc.release_at IS NULL OR cc.release_at <= NOW())
+         ORDER BY cc.sort_order ASC, c.title ASC",
+    )
+    .bind(contest_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)
+}
+
+pub(crate) async fn load_ad_rounds(
+    state: &AppState,
+    contest_id: Uuid,
+    limit: i64,
+) -> AppResult<Vec<AdRoundItem>> {
+    load_ad_contest(state, contest_id).await?;
+
+    sqlx::query_as::<_, AdRoundItem>(
+        "SELECT id, round_number, started_at, ended_at
+         FROM ad_rounds
+         WHERE contest_id = $1
+         ORDER BY round_number DESC
+         LIMIT $2",
+    )
+    .bind(contest_id)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)
+}
+
+/// Starts rounds for every running attack-defense contest whose current
+/// round has lasted `ad_round_seconds`, and closes the open round of
+/// contests that are no longer running.
+pub(crate) async fn run_attack_defense_ticker(
+    state: &AppState,
+) -> AppResult<AttackDefenseTickSummary> {
+    sqlx::query(
+        "UPDATE ad_rounds r
+         SET ended_at = NOW()
+         FROM contests ct
+         WHERE ct.id = r.contest_id
+           AND r.ended_at IS NULL
+           AND (ct.status <> 'running' OR NOW() >= ct.end_at)",
+    )
+    .execute(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let due = sqlx::query_scalar::<_, Uuid>(
+        "SELECT ct.id
+         FROM contests ct
+         WHERE ct.contest_type = 'attack_defense'
+           AND ct.status = 'running'
+           AND NOW() >= ct.start_at
+           AND NOW() < ct.end_at
+           AND NOT EXISTS (
+               SELECT 1
+               FROM ad_rounds r
+               WHERE r.contest_id = ct.id
+                 AND r.ended_at IS NULL
+                 AND r.started_at > NOW() - make_interval(secs => ct.ad_round_seconds)
+           )",
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let mut summary = AttackDefenseTickSummary {
+        due: due.len() as i64,
+        advanced: 0,
+        failed: 0,
+    };
+    for contest_id in due {
+        match advance_attack_defense_round(state, contest_id, false).await {
+            Ok(Some(_)) => summary.advanced += 1,
+            Ok(None) => {}
+            Err(err) => {
+                summary.failed += 1;
+                warn!(contest_id = %contest_id, error = %err, "attack-defense round advance failed");
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Closes the open round, opens the next one and has every service checker
+/// place the new flags. Without `force` nothing happens while the open round
+/// is younger than `ad_round_seconds`, so concurrent tickers advance once.
+pub(crate) async fn advance_attack_defense_round(
+    state: &AppState,
+    contest_id: Uuid,
+    force: bool,
+) -> AppResult<Option<AdRoundAdvanceSummary>> {
+    let contest = load_ad_contest(state, contest_id).await?;
+
+    let mut tx = state.db.begin().await.map_err(AppError::internal)?;
+    sqlx::query("SELECT id FROM contests WHERE id = $1 FOR UPDATE")
+        .bind(contest_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::internal)?;
+
+    let open = sqlx::query_as::<_, AdRoundItem>(
+        "SELECT id, round_number, started_at, ended_at
+         FROM ad_rounds
+         WHERE contest_id = $1 AND ended_at IS NULL
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(AppError::internal)?;
+
+    if let Some(open) = open.as_ref() {
+        let due_at = open.started_at + Duration::seconds(contest.ad_round_seconds as i64);
+        if !force && Utc::now() < due_at {
+            return Ok(None);
+        }
+    }
+
+    sqlx::query(
+        "UPDATE ad_rounds
+         SET ended_at = NOW()
+         WHERE contest_id = $1 AND ended_at IS NULL",
+    )
+    .bind(contest_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(AppError::internal)?;
+
+    let round = sqlx::query_as::<_, AdRoundItem>(
+        "INSERT INTO ad_rounds (contest_id, round_number)
+         SELECT $1, COALESCE(MAX(round_number), 0) + 1
+         FROM ad_rounds
+         WHERE contest_id = $1
+         RETURNING id, round_number, started_at, ended_at",
+    )
+    .bind(contest_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(AppError::internal)?;
+
+    tx.commit().await.map_err(AppError::internal)?;
+
+    let participants = load_ad_participants(state, contest_id).await?;
+    let services = load_ad_services(state, contest_id).await?;
+    let targets = load_running_targets(state, contest_id).await?;
+
+    let checks = participants.iter().flat_map(|team| {
+        services.iter().map(|service| {
+            let target = targets
+                .get(&(service.challenge_id, team.team_id))
+                .map(String::as_str);
+            place_flag_and_check(state, contest_id, &round, service, team.team_id, target)
+        })
+    });
+    let results = join_all(checks).await;
+
+    let mut summary = AdRoundAdvanceSummary {
+        round,
+        services: 0,
+        services_up: 0,
+    };
+    for result in results {
+        match result {
+            Ok(status) => {
+                summary.services += 1;
+                if status == AdServiceStatus::Up {
+                    summary.services_up += 1;
+                }
+            }
+            Err(err) => {
+                warn!(contest_id = %contest_id, error = %err, "attack-defense flag placement failed");
+            }
+        }
+    }
+
+    publish_scoreboard_update(state, contest_id, SCOREBOARD_EVENT_UPDATE).await;
+    Ok(Some(summary))
+}
+
+async fn load_running_targets(
+    state: &AppState,
+    contest_id: Uuid,
+) -> AppResult<HashMap<(Uuid, Uuid), String>> {
+    let rows = sqlx::query_as::<_, (Uuid, Uuid, String)>(
+        "SELECT challenge_id, team_id, entrypoint_url
+         FROM instances
+         WHERE contest_id = $1 AND team_id IS NOT NULL AND status = 'running'",
+    )
+    .bind(contest_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(challenge_id, team_id, target)| ((challenge_id, team_id), target))
+        .collect())
+}
+
+async fn place_flag_and_check(
+    state: &AppState,
+    contest_id: Uuid,
+    round: &AdRoundItem,
+    service: &AdServiceRow,
+    team_id: Uuid,
+    target: Option<&str>,
+) -> AppResult<AdServiceStatus> {
+    let flag = generate_ad_flag(service.challenge_id);
+    sqlx::query(
+        "INSERT INTO ad_flags (contest_id, round_id, challenge_id, team_id, flag_value)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(contest_id)
+    .bind(round.id)
+    .bind(service.challenge_id)
+    .bind(team_id)
+    .bind(&flag)
+    .execute(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let started = Instant::now();
+    let (status, message) = match (
+        parse_signed_endpoint_config(&service.metadata, AD_CHECKER_METADATA_KEY),
+        target,
+    ) {
+        (Err(err), _) => (AdServiceStatus::Error, err),
+        (Ok(_), None) => (
+            AdServiceStatus::Down,
+            "service instance is not running".to_string(),
+        ),
+        (Ok(config), Some(target)) => {
+            run_ad_checker(
+                &config,
+                &AdCheckerRequest {
+                    contest_id,
+                    challenge_id: service.challenge_id,
+                    team_id,
+                    round: round.round_number,
+                    flag: &flag,
+                    target,
+                },
+            )
+            .await
+        }
+    };
+
+    sqlx::query(
+        "INSERT INTO ad_service_checks (
+            contest_id,
+            round_id,
+            challenge_id,
+            team_id,
+            status,
+            message,
+            elapsed_ms
+         )
+         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(contest_id)
+    .bind(round.id)
+    .bind(service.challenge_id)
+    .bind(team_id)
+    .bind(status.as_str())
+    .bind(truncate_message(&message))
+    .bind(started.elapsed().as_millis().min(i32::MAX as u128) as i32)
+    .execute(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(status)
+}
+
+/// Checker failures are recorded as `error`, which earns no SLA points but
+/// is kept apart from a service the team broke.
+async fn run_ad_checker(
+    config: &WebhookVerifierConfig,
+    request: &AdCheckerRequest<'_>,
+) -> (AdServiceStatus, String) {
+    match post_signed_json::<_, AdCheckerResponse>(config, request, "service checker").await {
+        Ok((body, _)) => {
+            let status = AdServiceStatus::parse(&body.status);
+            let message = body
+                .message
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| match status {
+                    AdServiceStatus::Error => {
+                        format!("service checker returned unknown status '{}'", body.status)
+                    }
+                    _ => String::new(),
+                });
+            (status, message)
+        }
+        Err(err) => (AdServiceStatus::Error, err.message),
+    }
+}
+
+pub(crate) fn generate_ad_flag(challenge_id: Uuid) -> String {
+    let challenge_prefix = challenge_id.as_simple().to_string();
+    let random_part = Uuid::new_v4().as_simple().to_string();
+    format!("ad{{{}-{}}}", &challenge_prefix[..8], random_part)
+}
+
+/// A flag placed in `flag_round` can be stolen until `lifetime` rounds have
+/// started since.
+pub(crate) fn flag_is_live(flag_round: i32, current_round: i32, lifetime: i32) -> bool {
+    flag_round <= current_round && current_round - flag_round < lifetime.max(1)
+}
+
+/// Records a stolen flag submitted by `team_id` against `challenge_id`.
+pub(crate) async fn capture_attack_flag(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Uuid,
+    submitted_flag: &str,
+) -> AppResult<AttackCapture> {
+    let flag = sqlx::query_as::<_, AdFlagLookupRow>(
+        "SELECT f.id,
+                f.challenge_id,
+                f.team_id,
+                t.name AS team_name,
+                r.round_number,
+                (SELECT MAX(round_number) FROM ad_rounds WHERE contest_id = f.contest_id)
+                    AS current_round,
+                ct.ad_flag_lifetime_rounds,
+                ct.ad_attack_points
+         FROM ad_flags f
+         JOIN ad_rounds r ON r.id = f.round_id
+         JOIN contests ct ON ct.id = f.contest_id
+         JOIN teams t ON t.id = f.team_id
+         WHERE f.contest_id = $1 AND f.flag_value = $2
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .bind(submitted_flag)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let Some(flag) = flag else {
+        return Ok(AttackCapture::Unknown);
+    };
+    if flag.team_id == team_id {
+        return Ok(AttackCapture::OwnFlag);
+    }
+    if flag.challenge_id != challenge_id {
+        return Ok(AttackCapture::OtherService);
+    }
+    if !flag_is_live(
+        flag.round_number,
+        flag.current_round,
+        flag.ad_flag_lifetime_rounds,
+    ) {
+        return Ok(AttackCapture::Expired);
+    }
+
+    let inserted = sqlx::query_scalar::<_, i64>(
+        "INSERT INTO ad_attacks (contest_id, flag_id, attacker_team_id)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (flag_id, attacker_team_id) DO NOTHING
+         RETURNING id",
+    )
+    .bind(contest_id)
+    .bind(flag.id)
+    .bind(team_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    if inserted.is_none() {
+        return Ok(AttackCapture::Duplicate);
+    }
+
+    Ok(AttackCapture::Captured {
+        victim_team_name: flag.team_name,
+        points: flag.ad_attack_points,
+    })
+}
+
+/// Attack, defense and SLA points as score adjustments, valued with the
+/// contest's current point settings:
+/// - attack: every stolen flag, at submission time;
+/// - defense: every flag not stolen (before `cutoff`) from a service that
+///   was up, once its round has ended;
+/// - SLA: every `up` check, at the start of its round.
+pub(crate) async fn load_attack_defense_adjustments(
+    state: &AppState,
+    contest_id: Uuid,
+    cutoff: Option<DateTime<Utc>>,
+) -> AppResult<Vec<ScoreAdjustment>> {
+    sqlx::query_as::<_, ScoreAdjustment>(
+        "SELECT a.attacker_team_id AS team_id,
+                t.name AS team_name,
+                ct.ad_attack_points::bigint AS points,
+                a.submitted_at AS occurred_at,
+                'tick' AS source
+         FROM ad_attacks a
+         JOIN contests ct ON ct.id = a.contest_id
+         JOIN teams t ON t.id = a.attacker_team_id
+         WHERE a.contest_id = $1
+           AND ct.ad_attack_points > 0
+           AND ($2::timestamptz IS NULL OR a.submitted_at < $2)
+         UNION ALL
+         SELECT f.team_id,
+                t.name AS team_name,
+                ct.ad_defense_points::bigint AS points,
+                r.ended_at AS occurred_at,
+                'tick' AS source
+         FROM ad_flags f
+         JOIN ad_rounds r ON r.id = f.round_id
+         JOIN contests ct ON ct.id = f.contest_id
+         JOIN teams t ON t.id = f.team_id
+         JOIN ad_service_checks s
+           ON s.round_id = f.round_id
+          AND s.challenge_id = f.challenge_id
+          AND s.team_id = f.team_id
+          AND s.status = 'up'
+         WHERE f.contest_id = $1
+           AND ct.ad_defense_points > 0
+           AND r.ended_at IS NOT NULL
+           AND ($2::timestamptz IS NULL OR r.ended_at < $2)
+           AND NOT EXISTS (
+               SELECT 1
+               FROM ad_attacks a
+               WHERE a.flag_id = f.id
+                 AND ($2::timestamptz IS NULL OR a.submitted_at < $2)
+           )
+         UNION ALL
+         SELECT s.team_id,
+                t.name AS team_name,
+                ct.ad_sla_points::bigint AS points,
+                r.started_at AS occurred_at,
+                'tick' AS source
+         FROM ad_service_checks s
+         JOIN ad_rounds r ON r.id = s.round_id
+         JOIN contests ct ON ct.id = s.contest_id
+         JOIN teams t ON t.id = s.team_id
+         WHERE s.contest_id = $1
+           AND s.status = 'up'
+           AND ct.ad_sla_points > 0
+           AND ($2::timestamptz IS NULL OR r.started_at < $2)
+         ORDER BY occurred_at ASC",
+    )
+    .bind(contest_id)
+    .bind(cutoff)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{flag_is_live, generate_ad_flag, AdServiceStatus};
+
+    #[test]
+    fn flags_live_for_lifetime_rounds() {
+        assert!(flag_is_live(3, 3, 2));
+        assert!(flag_is_live(3, 4, 2));
+        assert!(!flag_is_live(3, 5, 2));
+        assert!(!flag_is_live(4, 3, 2));
+        assert!(flag_is_live(1, 1, 0));
+    }
+
+    #[test]
+    fn generated_flags_are_unique_and_tagged() {
+        let challenge_id = Uuid::new_v4();
+        let lhs = generate_ad_flag(challenge_id);
+        let rhs = generate_ad_flag(challenge_id);
+        assert_ne!(lhs, rhs);
+        assert!(lhs.starts_with(&format!(
+            "ad{{{}-",
+            &challenge_id.as_simple().to_string()[..8]
+        )));
+        assert!(lhs.ends_with('}'));
+    }
+
+    #[test]
+    fn unknown_checker_status_is_an_error() {
+        assert_eq!(AdServiceStatus::parse(" UP "), AdServiceStatus::Up);
+        assert_eq!(AdServiceStatus::parse("mumble"), AdServiceStatus::Mumble);
+        assert_eq!(AdServiceStatus::parse("corrupt"), AdServiceStatus::Error);
+    }
+}
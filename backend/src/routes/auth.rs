@@ -24,6 +24,7 @@ use crate::{
     error::{AppError, AppResult},
     mailer::{send_outbound_email, OutboundEmail},
     password_policy::{enforce_password_policy, PasswordContext, PasswordPolicySnapshot},
+    routes::contest_access::sync_solo_team_name,
     state::AppState,
 };
 
@@ -458,6 +459,7 @@ async fn update_profile(
 
     let email_verification_enabled = state.config.auth_email_verification_enabled;
 
+    let mut tx = state.db.begin().await.map_err(AppError::internal)?;
     let updated = sqlx::query_as::<_, AuthUser>(
         "UPDATE users
          SET username = COALESCE($2, username),
@@ -482,7 +484,7 @@ async fn update_profile(
     .bind(&username)
     .bind(&email)
     .bind(email_verification_enabled)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *tx)
     .await
     .map_err(|err| {
         if is_unique_violation(&err) {
@@ -493,7 +495,12 @@ async fn update_profile(
     })?
     .ok_or(AppError::Unauthorized)?;
 
-    let email_changed = updated.email.to_ascii_lowercase() != original_user.email.to_ascii_lowercase();
+    if username.is_some() {
+        sync_solo_team_name(&mut tx, current_user.user_id).await?;
+    }
+    tx.commit().await.map_err(AppError::internal)?;
+
+    let email_changed = !updated.email.eq_ignore_ascii_case(&original_user.email);
     if email_verification_enabled && email_changed {
         let refreshed_user = fetch_active_user_with_secret(state.as_ref(), current_user.user_id).await?;
         send_email_verification_flow(
@@ -906,6 +913,7 @@ async fn delete_account(
             SELECT 1
             FROM teams
             WHERE captain_user_id = $1
+              AND solo_user_id IS NULL
          )",
     )
     .bind(user.id)
@@ -929,6 +937,7 @@ async fn delete_account(
     let deleted_email = format!("deleted+{}@deleted.local", user_simple);
     let deleted_password_hash = hash_password(Uuid::new_v4().to_string().as_str())?;
 
+    let mut tx = state.db.begin().await.map_err(AppError::internal)?;
     sqlx::query(
         "UPDATE users
          SET username = $2,
@@ -945,9 +954,11 @@ async fn delete_account(
     .bind(&deleted_username)
     .bind(&deleted_email)
     .bind(&deleted_password_hash)
-    .execute(&state.db)
+    .execute(&mut *tx)
     .await
     .map_err(AppError::internal)?;
+    sync_solo_team_name(&mut tx, user.id).await?;
+    tx.commit().await.map_err(AppError::internal)?;
 
     auth::revoke_all_user_sessions(state.as_ref(), user.id).await?;
 
@@ -0,0 +1,113 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    challenge_unlocks::{parse_unlock_rules, unlock_rules_satisfied, UnlockNode},
+    error::{AppError, AppResult},
+    state::AppState,
+};
+
+#[derive(Debug, FromRow)]
+struct UnlockNodeRow {
+    challenge_id: Uuid,
+    category: String,
+    unlock_rules: Value,
+}
+
+pub(crate) async fn load_contest_unlock_nodes(
+    state: &AppState,
+    contest_id: Uuid,
+) -> AppResult<Vec<UnlockNode>> {
+    let rows = sqlx::query_as::<_, UnlockNodeRow>(
+        "SELECT cc.challenge_id,
+                c.category,
+                cc.unlock_rules
+         FROM contest_challenges cc
+         JOIN challenges c ON c.id = cc.challenge_id
+         WHERE cc.contest_id = $1",
+    )
+    .bind(contest_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| UnlockNode {
+            challenge_id: row.challenge_id,
+            category: row.category,
+            rules: parse_unlock_rules(&row.unlock_rules),
+        })
+        .collect())
+}
+
+/// Challenges the team has fully solved; multi-stage challenges count once
+/// every stage is done.
+async fn load_team_completed_challenges(
+    state: &AppState,
+    contest_id: Uuid,
+    team_id: Uuid,
+) -> AppResult<HashSet<Uuid>> {
+    let rows = sqlx::query_scalar::<_, Uuid>(
+        "SELECT s.challenge_id
+         FROM submissions s
+         JOIN challenges c ON c.id = s.challenge_id
+         WHERE s.contest_id = $1
+           AND s.team_id = $2
+           AND s.verdict = 'accepted'
+         GROUP BY s.challenge_id, c.id
+         HAVING jsonb_array_length(c.stages) = 0
+             OR COUNT(DISTINCT s.stage_key) >= jsonb_array_length(c.stages)",
+    )
+    .bind(contest_id)
+    .bind(team_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// Contest challenges whose unlock rules the team does not meet yet.
+pub(crate) async fn load_locked_challenge_ids(
+    state: &AppState,
+    contest_id: Uuid,
+    team_id: Uuid,
+) -> AppResult<HashSet<Uuid>> {
+    let nodes = load_contest_unlock_nodes(state, contest_id).await?;
+    if nodes.iter().all(|node| node.rules.is_empty()) {
+        return Ok(HashSet::new());
+    }
+
+    let solved = load_team_completed_challenges(state, contest_id, team_id).await?;
+    let categories: HashMap<Uuid, String> = nodes
+        .iter()
+        .map(|node| (node.challenge_id, node.category.to_lowercase()))
+        .collect();
+
+    Ok(nodes
+        .iter()
+        .filter(|node| !solved.contains(&node.challenge_id))
+        .filter(|node| !unlock_rules_satisfied(&node.rules, &solved, &categories))
+        .map(|node| node.challenge_id)
+        .collect())
+}
+
+pub(crate) async fn ensure_challenge_unlocked(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Uuid,
+) -> AppResult<()> {
+    let locked = load_locked_challenge_ids(state, contest_id, team_id).await?;
+    if locked.contains(&challenge_id) {
+        return Err(AppError::BadRequest(
+            "challenge is locked until its prerequisites are solved".to_string(),
+        ));
+    }
+
+    Ok(())
+}
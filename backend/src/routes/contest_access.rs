@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgConnection};
 use uuid::Uuid;
 
 use crate::{
@@ -8,11 +8,28 @@ use crate::{
     state::AppState,
 };
 
+pub(crate) const CONTEST_PARTICIPATION_TEAM: &str = "team";
+pub(crate) const CONTEST_PARTICIPATION_SOLO: &str = "solo";
+
 #[derive(Debug, Clone, FromRow)]
 pub(crate) struct ContestGateRow {
+    pub id: Uuid,
     pub visibility: String,
     pub status: String,
     pub registration_requires_approval: bool,
+    pub participation_mode: String,
+    pub public_scoreboard: bool,
+    pub public_challenge_stats: bool,
+    pub public_scoreboard_rate_limit: i32,
+}
+
+impl ContestGateRow {
+    /// Solo contests rank every user on their own. Each user competes through
+    /// a personal team (`teams.solo_user_id`) that has no `team_members` rows,
+    /// so registrations, submissions and instances stay keyed by team.
+    pub(crate) fn is_solo(&self) -> bool {
+        self.participation_mode == CONTEST_PARTICIPATION_SOLO
+    }
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -23,6 +40,7 @@ struct TeamMembershipRow {
 #[derive(Debug, Clone, FromRow)]
 pub(crate) struct ContestRegistrationRow {
     pub status: String,
+    pub division_id: Option<Uuid>,
     pub review_note: String,
     pub requested_at: DateTime<Utc>,
     pub reviewed_at: Option<DateTime<Utc>>,
@@ -51,11 +69,25 @@ pub(crate) fn ensure_contest_visibility(
     Ok(())
 }
 
+/// Public scoreboards are readable without registering, but only while the
+/// contest itself is publicly visible.
+pub(crate) fn is_public_scoreboard(contest: &ContestGateRow) -> bool {
+    contest.public_scoreboard
+        && contest.visibility != "private"
+        && contest.status != "draft"
+        && contest.status != "archived"
+}
+
 pub(crate) async fn load_contest_gate(state: &AppState, contest_id: Uuid) -> AppResult<ContestGateRow> {
     sqlx::query_as::<_, ContestGateRow>(
-        "SELECT visibility,
+        "SELECT id,
+                visibility,
                 status,
-                registration_requires_approval
+                registration_requires_approval,
+                participation_mode,
+                public_scoreboard,
+                public_challenge_stats,
+                public_scoreboard_rate_limit
          FROM contests
          WHERE id = $1
          LIMIT 1",
@@ -67,11 +99,185 @@ pub(crate) async fn load_contest_gate(state: &AppState, contest_id: Uuid) -> App
     .ok_or(AppError::BadRequest("contest not found".to_string()))
 }
 
-pub(crate) async fn get_user_team_id_optional(state: &AppState, user_id: Uuid) -> AppResult<Option<Uuid>> {
-    let row = sqlx::query_as::<_, TeamMembershipRow>(
+/// All regular teams of the user, oldest membership first.
+pub(crate) async fn list_user_team_ids(state: &AppState, user_id: Uuid) -> AppResult<Vec<Uuid>> {
+    let rows = sqlx::query_as::<_, TeamMembershipRow>(
         "SELECT team_id
          FROM team_members
          WHERE user_id = $1
+         ORDER BY joined_at ASC, team_id ASC",
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(rows.into_iter().map(|item| item.team_id).collect())
+}
+
+/// The user's team that is registered for the contest. Users may be in
+/// several teams, but at most one of them can be registered per contest;
+/// rejected registrations do not count.
+async fn get_registered_team_id_optional(
+    state: &AppState,
+    contest_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<Option<Uuid>> {
+    let row = sqlx::query_as::<_, TeamMembershipRow>(
+        "SELECT tm.team_id
+         FROM team_members tm
+         JOIN contest_registrations r ON r.team_id = tm.team_id
+         WHERE tm.user_id = $1
+           AND r.contest_id = $2
+           AND r.status <> 'rejected'
+         ORDER BY r.requested_at ASC
+         LIMIT 1",
+    )
+    .bind(user_id)
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(row.map(|item| item.team_id))
+}
+
+/// Resolves the user's team for a contest: the team registered for it, or
+/// the user's only team when none is registered yet. Users in several teams
+/// pick one when registering.
+pub(crate) async fn get_user_team_id_optional(
+    state: &AppState,
+    contest_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<Option<Uuid>> {
+    if let Some(team_id) = get_registered_team_id_optional(state, contest_id, user_id).await? {
+        return Ok(Some(team_id));
+    }
+
+    let team_ids = list_user_team_ids(state, user_id).await?;
+    Ok(match team_ids.as_slice() {
+        [team_id] => Some(*team_id),
+        _ => None,
+    })
+}
+
+pub(crate) async fn ensure_user_has_team(
+    state: &AppState,
+    contest_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<Uuid> {
+    if let Some(team_id) = get_user_team_id_optional(state, contest_id, user_id).await? {
+        return Ok(team_id);
+    }
+
+    if list_user_team_ids(state, user_id).await?.is_empty() {
+        return Err(AppError::BadRequest(
+            "join or create a team before entering the contest".to_string(),
+        ));
+    }
+
+    Err(AppError::BadRequest(
+        "contest registration required before entering workspace".to_string(),
+    ))
+}
+
+/// Picks the team a user registers for a team contest. An existing
+/// registration wins; otherwise `requested_team_id` is required when the
+/// user belongs to more than one team.
+pub(crate) async fn select_registration_team(
+    state: &AppState,
+    contest_id: Uuid,
+    user_id: Uuid,
+    requested_team_id: Option<Uuid>,
+) -> AppResult<Uuid> {
+    if let Some(team_id) = get_registered_team_id_optional(state, contest_id, user_id).await? {
+        if requested_team_id.is_some_and(|requested| requested != team_id) {
+            return Err(AppError::Conflict(
+                "another team of yours is already registered for this contest".to_string(),
+            ));
+        }
+        return Ok(team_id);
+    }
+
+    let team_ids = list_user_team_ids(state, user_id).await?;
+    let team_id = match (requested_team_id, team_ids.as_slice()) {
+        (_, []) => {
+            return Err(AppError::BadRequest(
+                "join or create a team before entering the contest".to_string(),
+            ))
+        }
+        (Some(requested), _) if team_ids.contains(&requested) => requested,
+        (Some(_), _) => {
+            return Err(AppError::BadRequest(
+                "you are not a member of this team".to_string(),
+            ))
+        }
+        (None, [team_id]) => *team_id,
+        (None, _) => {
+            return Err(AppError::BadRequest(
+                "team_id is required when you belong to several teams".to_string(),
+            ))
+        }
+    };
+
+    ensure_team_roster_free_for_contest(state, contest_id, team_id).await?;
+    Ok(team_id)
+}
+
+/// Fails when a member of `team_id` already plays the contest with another
+/// team, which would make their contest team ambiguous. The database
+/// enforces the same rule when the registration is written; this check only
+/// gives the common case a friendly error.
+pub(crate) async fn ensure_team_roster_free_for_contest(
+    state: &AppState,
+    contest_id: Uuid,
+    team_id: Uuid,
+) -> AppResult<()> {
+    let conflict = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(
+             SELECT 1
+             FROM team_members tm
+             JOIN team_members other
+               ON other.user_id = tm.user_id
+              AND other.team_id <> tm.team_id
+             JOIN contest_registrations r ON r.team_id = other.team_id
+             WHERE tm.team_id = $1
+               AND r.contest_id = $2
+               AND r.status <> 'rejected'
+         )",
+    )
+    .bind(team_id)
+    .bind(contest_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    if conflict {
+        return Err(AppError::Conflict(
+            "a team member is already registered for this contest with another team".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Whether a write was refused by the database rule that a user plays each
+/// contest with at most one team.
+pub(crate) fn is_contest_team_conflict(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Database(db_err) => db_err.code().as_deref() == Some("23P01"),
+        _ => false,
+    }
+}
+
+pub(crate) async fn get_solo_team_id_optional(
+    state: &AppState,
+    user_id: Uuid,
+) -> AppResult<Option<Uuid>> {
+    let row = sqlx::query_as::<_, TeamMembershipRow>(
+        "SELECT id AS team_id
+         FROM teams
+         WHERE solo_user_id = $1
          LIMIT 1",
     )
     .bind(user_id)
@@ -82,14 +288,90 @@ pub(crate) async fn get_user_team_id_optional(state: &AppState, user_id: Uuid) -
     Ok(row.map(|item| item.team_id))
 }
 
-pub(crate) async fn ensure_user_has_team(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
-    get_user_team_id_optional(state, user_id)
+/// Returns the user's personal team, creating it (named after the user) on
+/// first use.
+pub(crate) async fn ensure_solo_team(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
+    if let Some(team_id) = get_solo_team_id_optional(state, user_id).await? {
+        return Ok(team_id);
+    }
+
+    sqlx::query_as::<_, TeamMembershipRow>(
+        "INSERT INTO teams (name, captain_user_id, solo_user_id)
+         SELECT username, id, id
+         FROM users
+         WHERE id = $1
+         ON CONFLICT (solo_user_id) DO UPDATE SET name = EXCLUDED.name
+         RETURNING id AS team_id",
+    )
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .map(|row| row.team_id)
+    .ok_or(AppError::BadRequest("user not found".to_string()))
+}
+
+/// Renames the user's personal team, if any, after their current username.
+/// Call in the transaction that changes the username.
+pub(crate) async fn sync_solo_team_name(conn: &mut PgConnection, user_id: Uuid) -> AppResult<()> {
+    sqlx::query(
+        "UPDATE teams t
+         SET name = u.username,
+             updated_at = NOW()
+         FROM users u
+         WHERE u.id = $1
+           AND t.solo_user_id = u.id
+           AND t.name <> u.username",
+    )
+    .bind(user_id)
+    .execute(conn)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(())
+}
+
+/// The team the user competes with in this contest, if any: their personal
+/// team in solo contests, otherwise the regular team chosen for the contest.
+pub(crate) async fn get_contest_team_id_optional(
+    state: &AppState,
+    contest: &ContestGateRow,
+    user_id: Uuid,
+) -> AppResult<Option<Uuid>> {
+    if contest.is_solo() {
+        get_solo_team_id_optional(state, user_id).await
+    } else {
+        get_user_team_id_optional(state, contest.id, user_id).await
+    }
+}
+
+/// Like [`get_contest_team_id_optional`], but fails when the user has not
+/// joined the contest yet. Personal teams are only created on registration.
+pub(crate) async fn ensure_contest_team(
+    state: &AppState,
+    contest: &ContestGateRow,
+    user_id: Uuid,
+) -> AppResult<Uuid> {
+    if !contest.is_solo() {
+        return ensure_user_has_team(state, contest.id, user_id).await;
+    }
+
+    get_solo_team_id_optional(state, user_id)
         .await?
         .ok_or(AppError::BadRequest(
-            "join or create a team before entering the contest".to_string(),
+            "contest registration required before entering workspace".to_string(),
         ))
 }
 
+pub(crate) async fn resolve_contest_team_id(
+    state: &AppState,
+    contest_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<Uuid> {
+    let contest = load_contest_gate(state, contest_id).await?;
+    ensure_contest_team(state, &contest, user_id).await
+}
+
 pub(crate) async fn load_contest_registration(
     state: &AppState,
     contest_id: Uuid,
@@ -97,6 +379,7 @@ pub(crate) async fn load_contest_registration(
 ) -> AppResult<Option<ContestRegistrationRow>> {
     sqlx::query_as::<_, ContestRegistrationRow>(
         "SELECT status,
+                division_id,
                 review_note,
                 requested_at,
                 reviewed_at
@@ -147,7 +430,7 @@ pub(crate) async fn ensure_user_contest_workspace_access(
         return Ok(None);
     }
 
-    let team_id = ensure_user_has_team(state, current_user.user_id).await?;
+    let team_id = ensure_contest_team(state, &contest, current_user.user_id).await?;
     let registration = load_contest_registration(state, contest_id, team_id).await?;
     ensure_registration_status(registration.as_ref(), contest.registration_requires_approval)?;
     Ok(Some(team_id))
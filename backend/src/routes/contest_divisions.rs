@@ -0,0 +1,119 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    state::AppState,
+};
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub(crate) struct ContestDivisionItem {
+    pub id: Uuid,
+    pub contest_id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub self_selectable: bool,
+    pub sort_order: i32,
+    pub team_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+const CONTEST_DIVISION_SELECT_SQL: &str = "SELECT d.id,
+                d.contest_id,
+                d.name,
+                d.description,
+                d.self_selectable,
+                d.sort_order,
+                (SELECT COUNT(*) FROM contest_registrations r WHERE r.division_id = d.id) AS team_count,
+                d.created_at,
+                d.updated_at
+         FROM contest_divisions d";
+
+pub(crate) async fn list_contest_divisions(
+    state: &AppState,
+    contest_id: Uuid,
+) -> AppResult<Vec<ContestDivisionItem>> {
+    let sql = format!(
+        "{}
+         WHERE d.contest_id = $1
+         ORDER BY d.sort_order ASC, d.created_at ASC",
+        CONTEST_DIVISION_SELECT_SQL
+    );
+    sqlx::query_as::<_, ContestDivisionItem>(&sql)
+        .bind(contest_id)
+        .fetch_all(&state.db)
+        .await
+        .map_err(AppError::internal)
+}
+
+pub(crate) async fn list_contest_division_ids(
+    state: &AppState,
+    contest_id: Uuid,
+) -> AppResult<Vec<Uuid>> {
+    sqlx::query_scalar::<_, Uuid>(
+        "SELECT id
+         FROM contest_divisions
+         WHERE contest_id = $1
+         ORDER BY sort_order ASC, created_at ASC",
+    )
+    .bind(contest_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)
+}
+
+pub(crate) async fn load_contest_division(
+    state: &AppState,
+    contest_id: Uuid,
+    division_id: Uuid,
+) -> AppResult<ContestDivisionItem> {
+    let sql = format!(
+        "{}
+         WHERE d.contest_id = $1
+           AND d.id = $2
+         LIMIT 1",
+        CONTEST_DIVISION_SELECT_SQL
+    );
+    sqlx::query_as::<_, ContestDivisionItem>(&sql)
+        .bind(contest_id)
+        .bind(division_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(AppError::internal)?
+        .ok_or(AppError::BadRequest(
+            "contest division not found".to_string(),
+        ))
+}
+
+/// Teams registered in `division_id`, or `None` when no division filter is
+/// requested. Scoreboards keep contest-wide challenge values and only drop
+/// the teams outside the division, so ranks restart within each division.
+pub(crate) async fn load_division_team_ids(
+    state: &AppState,
+    contest_id: Uuid,
+    division_id: Option<Uuid>,
+) -> AppResult<Option<HashSet<Uuid>>> {
+    let Some(division_id) = division_id else {
+        return Ok(None);
+    };
+    load_contest_division(state, contest_id, division_id).await?;
+
+    let team_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT team_id
+         FROM contest_registrations
+         WHERE contest_id = $1
+           AND division_id = $2",
+    )
+    .bind(contest_id)
+    .bind(division_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(Some(team_ids.into_iter().collect()))
+}
@@ -1,25 +1,40 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::header,
     response::IntoResponse,
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use sqlx::FromRow;
 use tokio::fs;
 use uuid::Uuid;
 
 use crate::{
     auth::AuthenticatedUser,
+    challenge_stages::parse_challenge_stages,
     error::{AppError, AppResult},
+    routes::admin::record_audit_log,
+    routes::challenge_unlocks::{ensure_challenge_unlocked, load_locked_challenge_ids},
     routes::contest_access::{
-        ensure_contest_visibility, ensure_registration_status, ensure_user_contest_workspace_access,
-        ensure_user_has_team, get_user_team_id_optional, is_privileged_role,
-        load_contest_gate, load_contest_registration, ContestRegistrationRow,
+        ensure_contest_team, ensure_contest_visibility, ensure_registration_status,
+        ensure_solo_team, ensure_user_contest_workspace_access, get_contest_team_id_optional,
+        is_contest_team_conflict, is_privileged_role, list_user_team_ids, load_contest_gate,
+        load_contest_registration, select_registration_team, ContestRegistrationRow,
+    },
+    routes::contest_divisions::{
+        list_contest_divisions, load_contest_division, ContestDivisionItem,
+    },
+    routes::scoreboard::{
+        publish_scoreboard_update, resolve_scoreboard_view, SCOREBOARD_EVENT_UPDATE,
     },
     state::AppState,
 };
@@ -32,8 +47,13 @@ struct ContestListItem {
     description: String,
     poster_url: Option<String>,
     status: String,
+    contest_type: String,
     scoring_mode: String,
+    scoring_formula: String,
     dynamic_decay: i32,
+    participation_mode: String,
+    public_scoreboard: bool,
+    public_challenge_stats: bool,
     latest_announcement_title: Option<String>,
     latest_announcement_content: Option<String>,
     latest_announcement_published_at: Option<DateTime<Utc>>,
@@ -41,19 +61,96 @@ struct ContestListItem {
     end_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, FromRow)]
+struct ContestChallengeRow {
+    id: Uuid,
+    title: String,
+    category: String,
+    difficulty: String,
+    description: String,
+    challenge_type: String,
+    static_score: i32,
+    stages: Value,
+    release_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
 struct ContestChallengeItem {
     id: Uuid,
     title: String,
     category: String,
     difficulty: String,
     description: String,
+    /// Contents of the hints the caller can read, in order.
     hints: Vec<String>,
+    hint_items: Vec<ContestChallengeHintItem>,
     challenge_type: String,
     static_score: i32,
+    stages: Vec<ContestChallengeStageItem>,
     release_at: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug, Serialize)]
+struct ContestChallengeStageItem {
+    key: String,
+    title: String,
+    points: i32,
+    solved: bool,
+}
+
+/// A hint as seen by a player; `content` of a paid hint stays hidden until the
+/// team unlocks it, free hints are readable once released.
+#[derive(Debug, Serialize)]
+struct ContestChallengeHintItem {
+    id: Uuid,
+    sort_order: i32,
+    cost: i32,
+    release_at: Option<DateTime<Utc>>,
+    released: bool,
+    unlocked: bool,
+    content: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct ContestChallengeHintRow {
+    id: Uuid,
+    challenge_id: Uuid,
+    sort_order: i32,
+    content: String,
+    cost: i32,
+    release_at: Option<DateTime<Utc>>,
+    unlocked: bool,
+}
+
+#[derive(Debug, FromRow)]
+struct ChallengeHintUnlockTargetRow {
+    content: String,
+    cost: i32,
+    release_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, FromRow)]
+struct ChallengeHintUnlockRow {
+    cost: i32,
+    unlocked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct UnlockChallengeHintResponse {
+    hint_id: Uuid,
+    challenge_id: Uuid,
+    content: String,
+    cost: i32,
+    unlocked_at: DateTime<Utc>,
+    already_unlocked: bool,
+}
+
+#[derive(Debug, FromRow)]
+struct SolvedStageRow {
+    challenge_id: Uuid,
+    stage_key: String,
+}
+
 #[derive(Debug, Serialize)]
 struct ContestChallengeAttachmentItem {
     id: Uuid,
@@ -92,6 +189,69 @@ struct ContestAnnouncementItem {
     created_at: DateTime<Utc>,
 }
 
+/// An admin award or penalty as seen by the affected team.
+#[derive(Debug, Serialize, FromRow)]
+struct ContestScoreAdjustmentItem {
+    id: Uuid,
+    points: i32,
+    reason: String,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContestSubmissionsQuery {
+    challenge_id: Option<Uuid>,
+    verdict: Option<String>,
+    before_id: Option<i64>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, FromRow)]
+struct ContestSubmissionRow {
+    id: i64,
+    challenge_id: Uuid,
+    challenge_title: String,
+    stage_key: Option<String>,
+    user_id: Uuid,
+    username: String,
+    submitted_flag: String,
+    verdict: String,
+    score_awarded: i32,
+    blood_bonus: i32,
+    penalty_points: i32,
+    judger_message: String,
+    submitted_at: DateTime<Utc>,
+}
+
+/// A submission of the viewer's team. Points are withheld (`null`) for
+/// submissions made after the freeze while the scoreboard is frozen.
+#[derive(Debug, Serialize)]
+struct ContestSubmissionItem {
+    id: i64,
+    challenge_id: Uuid,
+    challenge_title: String,
+    stage_key: Option<String>,
+    user_id: Uuid,
+    username: String,
+    submitted_flag: String,
+    verdict: String,
+    score_awarded: Option<i32>,
+    blood_bonus: Option<i32>,
+    penalty_points: Option<i32>,
+    message: String,
+    submitted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContestSubmissionsResponse {
+    contest_id: Uuid,
+    team_id: Option<Uuid>,
+    frozen: bool,
+    freeze_at: Option<DateTime<Utc>>,
+    items: Vec<ContestSubmissionItem>,
+    next_before_id: Option<i64>,
+}
+
 #[derive(Debug, FromRow)]
 struct ContestChallengeAccessRow {
     contest_status: String,
@@ -107,10 +267,18 @@ struct ContestPosterAccessRow {
     poster_content_type: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct ContestRegistrationRequest {
+    team_id: Option<Uuid>,
+    division_id: Option<Uuid>,
+}
+
 #[derive(Debug, Serialize)]
 struct ContestRegistrationStatusResponse {
     contest_id: Uuid,
+    participation_mode: String,
     team_id: Option<Uuid>,
+    division_id: Option<Uuid>,
     registration_requires_approval: bool,
     registration_status: String,
     review_note: String,
@@ -127,6 +295,10 @@ pub fn router() -> Router<Arc<AppState>> {
             "/contests/{contest_id}/registration",
             get(get_contest_registration).post(apply_contest_registration),
         )
+        .route(
+            "/contests/{contest_id}/divisions",
+            get(list_contest_divisions_for_user),
+        )
         .route(
             "/contests/{contest_id}/challenges",
             get(list_contest_challenges),
@@ -135,6 +307,14 @@ pub fn router() -> Router<Arc<AppState>> {
             "/contests/{contest_id}/announcements",
             get(list_contest_announcements),
         )
+        .route(
+            "/contests/{contest_id}/score-adjustments",
+            get(list_contest_score_adjustments),
+        )
+        .route(
+            "/contests/{contest_id}/submissions",
+            get(list_contest_submissions),
+        )
         .route(
             "/contests/{contest_id}/challenges/{challenge_id}/attachments",
             get(list_contest_challenge_attachments),
@@ -143,6 +323,10 @@ pub fn router() -> Router<Arc<AppState>> {
             "/contests/{contest_id}/challenges/{challenge_id}/attachments/{attachment_id}",
             get(download_contest_challenge_attachment),
         )
+        .route(
+            "/contests/{contest_id}/challenges/{challenge_id}/hints/{hint_id}/unlock",
+            post(unlock_contest_challenge_hint),
+        )
 }
 
 async fn list_contests(
@@ -158,8 +342,13 @@ async fn list_contests(
                     ELSE '/api/v1/contests/' || c.id::text || '/poster'
                 END AS poster_url,
                 c.status,
+                c.contest_type,
                 c.scoring_mode,
+                c.scoring_formula,
                 c.dynamic_decay,
+                c.participation_mode,
+                c.public_scoreboard,
+                c.public_challenge_stats,
                 latest_announcement.title AS latest_announcement_title,
                 latest_announcement.content AS latest_announcement_content,
                 COALESCE(latest_announcement.published_at, latest_announcement.created_at) AS latest_announcement_published_at,
@@ -254,7 +443,9 @@ async fn get_contest_registration(
     if is_privileged_role(&current_user.role) {
         return Ok(Json(ContestRegistrationStatusResponse {
             contest_id,
+            participation_mode: contest.participation_mode.clone(),
             team_id: None,
+            division_id: None,
             registration_requires_approval: contest.registration_requires_approval,
             registration_status: "approved".to_string(),
             review_note: "privileged role bypass".to_string(),
@@ -264,13 +455,27 @@ async fn get_contest_registration(
         }));
     }
 
-    let team_id = get_user_team_id_optional(state.as_ref(), current_user.user_id).await?;
+    let team_id =
+        get_contest_team_id_optional(state.as_ref(), &contest, current_user.user_id).await?;
     let Some(team_id) = team_id else {
+        // A personal team only exists once the user has registered, and users
+        // in several teams pick one when registering.
+        let registration_status = if contest.is_solo()
+            || !list_user_team_ids(state.as_ref(), current_user.user_id)
+                .await?
+                .is_empty()
+        {
+            "not_registered"
+        } else {
+            "no_team"
+        };
         return Ok(Json(ContestRegistrationStatusResponse {
             contest_id,
+            participation_mode: contest.participation_mode.clone(),
             team_id: None,
+            division_id: None,
             registration_requires_approval: contest.registration_requires_approval,
-            registration_status: "no_team".to_string(),
+            registration_status: registration_status.to_string(),
             review_note: "".to_string(),
             requested_at: None,
             reviewed_at: None,
@@ -279,6 +484,7 @@ async fn get_contest_registration(
     };
 
     let registration = load_contest_registration(state.as_ref(), contest_id, team_id).await?;
+    let division_id = registration.as_ref().and_then(|row| row.division_id);
     let (registration_status, review_note, requested_at, reviewed_at, can_enter_workspace) =
         if let Some(row) = registration {
             let can_enter = ensure_registration_status(
@@ -299,7 +505,9 @@ async fn get_contest_registration(
 
     Ok(Json(ContestRegistrationStatusResponse {
         contest_id,
+        participation_mode: contest.participation_mode.clone(),
         team_id: Some(team_id),
+        division_id,
         registration_requires_approval: contest.registration_requires_approval,
         registration_status,
         review_note,
@@ -313,6 +521,7 @@ async fn apply_contest_registration(
     State(state): State<Arc<AppState>>,
     Path(contest_id): Path<Uuid>,
     current_user: AuthenticatedUser,
+    req: Option<Json<ContestRegistrationRequest>>,
 ) -> AppResult<Json<ContestRegistrationStatusResponse>> {
     let contest = load_contest_gate(state.as_ref(), contest_id).await?;
     ensure_contest_visibility(&contest, &current_user)?;
@@ -323,31 +532,54 @@ async fn apply_contest_registration(
         ));
     }
 
-    let team_id = ensure_user_has_team(state.as_ref(), current_user.user_id).await?;
+    let req = req.map(|Json(req)| req);
+    let requested_team_id = req.as_ref().and_then(|req| req.team_id);
+    let division_id = req.as_ref().and_then(|req| req.division_id);
+
+    if let Some(division_id) = division_id {
+        let division = load_contest_division(state.as_ref(), contest_id, division_id).await?;
+        if !division.self_selectable {
+            return Err(AppError::BadRequest(
+                "this division is assigned by admins".to_string(),
+            ));
+        }
+    }
+
+    let team_id = if contest.is_solo() {
+        ensure_solo_team(state.as_ref(), current_user.user_id).await?
+    } else {
+        select_registration_team(
+            state.as_ref(),
+            contest_id,
+            current_user.user_id,
+            requested_team_id,
+        )
+        .await?
+    };
     let existing = load_contest_registration(state.as_ref(), contest_id, team_id).await?;
 
-    let row = if let Some(existing_row) = existing {
-        if existing_row.status == "approved" {
+    let row = match existing {
+        Some(existing_row) if existing_row.status == "approved" => {
+            if division_id.is_some_and(|division_id| existing_row.division_id != Some(division_id))
+            {
+                return Err(AppError::Conflict(
+                    "division of an approved registration can only be changed by admins"
+                        .to_string(),
+                ));
+            }
             existing_row
-        } else {
+        }
+        _ => {
             upsert_contest_registration(
                 state.as_ref(),
                 contest_id,
                 team_id,
                 current_user.user_id,
+                division_id,
                 contest.registration_requires_approval,
             )
             .await?
         }
-    } else {
-        upsert_contest_registration(
-            state.as_ref(),
-            contest_id,
-            team_id,
-            current_user.user_id,
-            contest.registration_requires_approval,
-        )
-        .await?
     };
 
     let can_enter_workspace =
@@ -355,7 +587,9 @@ async fn apply_contest_registration(
 
     Ok(Json(ContestRegistrationStatusResponse {
         contest_id,
+        participation_mode: contest.participation_mode.clone(),
         team_id: Some(team_id),
+        division_id: row.division_id,
         registration_requires_approval: contest.registration_requires_approval,
         registration_status: row.status,
         review_note: row.review_note,
@@ -365,11 +599,24 @@ async fn apply_contest_registration(
     }))
 }
 
+async fn list_contest_divisions_for_user(
+    State(state): State<Arc<AppState>>,
+    Path(contest_id): Path<Uuid>,
+    current_user: AuthenticatedUser,
+) -> AppResult<Json<Vec<ContestDivisionItem>>> {
+    let contest = load_contest_gate(state.as_ref(), contest_id).await?;
+    ensure_contest_visibility(&contest, &current_user)?;
+
+    let divisions = list_contest_divisions(state.as_ref(), contest_id).await?;
+    Ok(Json(divisions))
+}
+
 async fn upsert_contest_registration(
     state: &AppState,
     contest_id: Uuid,
     team_id: Uuid,
     user_id: Uuid,
+    division_id: Option<Uuid>,
     registration_requires_approval: bool,
 ) -> AppResult<ContestRegistrationRow> {
     let status = if registration_requires_approval {
@@ -392,32 +639,44 @@ async fn upsert_contest_registration(
             requested_at,
             reviewed_by,
             reviewed_at,
-            review_note
+            review_note,
+            division_id
          )
          VALUES (
             $1, $2, $3, $4, NOW(), NULL,
             CASE WHEN $3 = 'approved' THEN NOW() ELSE NULL END,
-            $5
+            $5, $6
          )
          ON CONFLICT (contest_id, team_id)
          DO UPDATE
          SET status = EXCLUDED.status,
+             division_id = COALESCE(EXCLUDED.division_id, contest_registrations.division_id),
              requested_by = EXCLUDED.requested_by,
              requested_at = NOW(),
              reviewed_by = NULL,
              reviewed_at = CASE WHEN EXCLUDED.status = 'approved' THEN NOW() ELSE NULL END,
              review_note = EXCLUDED.review_note,
              updated_at = NOW()
-         RETURNING status, review_note, requested_at, reviewed_at",
+         RETURNING status, division_id, review_note, requested_at, reviewed_at",
     )
     .bind(contest_id)
     .bind(team_id)
     .bind(status)
     .bind(user_id)
     .bind(review_note)
+    .bind(division_id)
     .fetch_one(&state.db)
     .await
-    .map_err(AppError::internal)
+    .map_err(|err| {
+        if is_contest_team_conflict(&err) {
+            AppError::Conflict(
+                "a team member is already registered for this contest with another team"
+                    .to_string(),
+            )
+        } else {
+            AppError::internal(err)
+        }
+    })
 }
 
 async fn list_contest_challenges(
@@ -425,17 +684,18 @@ async fn list_contest_challenges(
     Path(contest_id): Path<Uuid>,
     current_user: AuthenticatedUser,
 ) -> AppResult<Json<Vec<ContestChallengeItem>>> {
-    ensure_user_contest_workspace_access(state.as_ref(), contest_id, &current_user).await?;
+    let workspace_team_id =
+        ensure_user_contest_workspace_access(state.as_ref(), contest_id, &current_user).await?;
 
-    let challenge_items = sqlx::query_as::<_, ContestChallengeItem>(
+    let rows = sqlx::query_as::<_, ContestChallengeRow>(
         "SELECT c.id,
                 c.title,
                 c.category,
                 c.difficulty,
                 c.description,
-                c.hints,
                 c.challenge_type,
                 c.static_score,
+                c.stages,
                 cc.release_at
          FROM contest_challenges cc
          JOIN challenges c ON c.id = cc.challenge_id
@@ -451,6 +711,121 @@ async fn list_contest_challenges(
     .await
     .map_err(AppError::internal)?;
 
+    // Challenges whose prerequisites are not met stay hidden, like unreleased ones.
+    let locked = match workspace_team_id {
+        Some(team_id) => load_locked_challenge_ids(state.as_ref(), contest_id, team_id).await?,
+        None => HashSet::new(),
+    };
+    let rows: Vec<ContestChallengeRow> = rows
+        .into_iter()
+        .filter(|row| !locked.contains(&row.id))
+        .collect();
+
+    let contest = load_contest_gate(state.as_ref(), contest_id).await?;
+    let team_id =
+        get_contest_team_id_optional(state.as_ref(), &contest, current_user.user_id).await?;
+    let solved_stages: HashSet<(Uuid, String)> = match team_id {
+        Some(team_id) => sqlx::query_as::<_, SolvedStageRow>(
+            "SELECT DISTINCT challenge_id, stage_key
+             FROM submissions
+             WHERE contest_id = $1
+               AND team_id = $2
+               AND verdict = 'accepted'
+               AND stage_key IS NOT NULL",
+        )
+        .bind(contest_id)
+        .bind(team_id)
+        .fetch_all(&state.db)
+        .await
+        .map_err(AppError::internal)?
+        .into_iter()
+        .map(|row| (row.challenge_id, row.stage_key))
+        .collect(),
+        None => HashSet::new(),
+    };
+
+    let show_all_hints = is_privileged_role(&current_user.role);
+    let now = Utc::now();
+    let hint_rows = sqlx::query_as::<_, ContestChallengeHintRow>(
+        "SELECT h.id,
+                h.challenge_id,
+                h.sort_order,
+                h.content,
+                h.cost,
+                h.release_at,
+                EXISTS (
+                    SELECT 1
+                    FROM challenge_hint_unlocks u
+                    WHERE u.contest_id = $1
+                      AND u.hint_id = h.id
+                      AND u.team_id = $2
+                ) AS unlocked
+         FROM challenge_hints h
+         JOIN contest_challenges cc ON cc.challenge_id = h.challenge_id
+         WHERE cc.contest_id = $1
+         ORDER BY h.sort_order ASC, h.created_at ASC",
+    )
+    .bind(contest_id)
+    .bind(team_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let mut hints_by_challenge: HashMap<Uuid, Vec<ContestChallengeHintItem>> = HashMap::new();
+    for hint in hint_rows {
+        let released = hint.release_at.map(|value| value <= now).unwrap_or(true);
+        let content = if hint.unlocked || show_all_hints || (released && hint.cost == 0) {
+            Some(hint.content)
+        } else {
+            None
+        };
+        hints_by_challenge
+            .entry(hint.challenge_id)
+            .or_default()
+            .push(ContestChallengeHintItem {
+                id: hint.id,
+                sort_order: hint.sort_order,
+                cost: hint.cost,
+                release_at: hint.release_at,
+                released,
+                unlocked: hint.unlocked,
+                content,
+            });
+    }
+
+    let challenge_items = rows
+        .into_iter()
+        .map(|row| {
+            let stages = parse_challenge_stages(&row.stages)
+                .into_iter()
+                .map(|stage| ContestChallengeStageItem {
+                    solved: solved_stages.contains(&(row.id, stage.key.clone())),
+                    key: stage.key,
+                    title: stage.title,
+                    points: stage.points,
+                })
+                .collect();
+
+            let hint_items = hints_by_challenge.remove(&row.id).unwrap_or_default();
+            ContestChallengeItem {
+                id: row.id,
+                title: row.title,
+                category: row.category,
+                difficulty: row.difficulty,
+                description: row.description,
+                hints: hint_items
+                    .iter()
+                    .filter_map(|hint| hint.content.clone())
+                    .collect(),
+                hint_items,
+                challenge_type: row.challenge_type,
+                static_score: row.static_score,
+                stages,
+                release_at: row.release_at,
+            }
+        })
+        .collect();
+
     Ok(Json(challenge_items))
 }
 
@@ -482,6 +857,150 @@ async fn list_contest_announcements(
     Ok(Json(rows))
 }
 
+async fn list_contest_score_adjustments(
+    State(state): State<Arc<AppState>>,
+    Path(contest_id): Path<Uuid>,
+    current_user: AuthenticatedUser,
+) -> AppResult<Json<Vec<ContestScoreAdjustmentItem>>> {
+    let Some(team_id) =
+        ensure_user_contest_workspace_access(state.as_ref(), contest_id, &current_user).await?
+    else {
+        return Ok(Json(Vec::new()));
+    };
+
+    let rows = sqlx::query_as::<_, ContestScoreAdjustmentItem>(
+        "SELECT id,
+                points,
+                reason,
+                created_at
+         FROM contest_score_adjustments
+         WHERE contest_id = $1
+           AND team_id = $2
+         ORDER BY created_at DESC",
+    )
+    .bind(contest_id)
+    .bind(team_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(Json(rows))
+}
+
+async fn list_contest_submissions(
+    State(state): State<Arc<AppState>>,
+    Path(contest_id): Path<Uuid>,
+    Query(query): Query<ContestSubmissionsQuery>,
+    current_user: AuthenticatedUser,
+) -> AppResult<Json<ContestSubmissionsResponse>> {
+    let team_id =
+        ensure_user_contest_workspace_access(state.as_ref(), contest_id, &current_user).await?;
+    let view = resolve_scoreboard_view(state.as_ref(), contest_id, &current_user.role).await?;
+
+    let mut response = ContestSubmissionsResponse {
+        contest_id,
+        team_id,
+        frozen: view.frozen(),
+        freeze_at: view.freeze_at,
+        items: Vec::new(),
+        next_before_id: None,
+    };
+    let Some(team_id) = team_id else {
+        return Ok(Json(response));
+    };
+
+    let verdict = query
+        .verdict
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty());
+    if let Some(verdict) = verdict.as_deref() {
+        if !matches!(
+            verdict,
+            "accepted" | "wrong" | "invalid" | "rate_limited" | "error"
+        ) {
+            return Err(AppError::BadRequest(
+                "verdict must be one of accepted, wrong, invalid, rate_limited, error".to_string(),
+            ));
+        }
+    }
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    // Submissions for challenges the team can no longer see stay hidden too.
+    let locked: Vec<Uuid> = load_locked_challenge_ids(state.as_ref(), contest_id, team_id)
+        .await?
+        .into_iter()
+        .collect();
+
+    let rows = sqlx::query_as::<_, ContestSubmissionRow>(
+        "SELECT s.id,
+                s.challenge_id,
+                c.title AS challenge_title,
+                s.stage_key,
+                s.user_id,
+                u.username,
+                s.submitted_flag,
+                s.verdict,
+                s.score_awarded,
+                s.blood_bonus,
+                s.penalty_points,
+                s.judger_message,
+                s.submitted_at
+         FROM submissions s
+         JOIN contest_challenges cc
+           ON cc.contest_id = s.contest_id AND cc.challenge_id = s.challenge_id
+         JOIN challenges c ON c.id = s.challenge_id
+         JOIN users u ON u.id = s.user_id
+         WHERE s.contest_id = $1
+           AND s.team_id = $2
+           AND c.is_visible = TRUE
+           AND (cc.release_at IS NULL OR cc.release_at <= NOW())
+           AND NOT (s.challenge_id = ANY($3::uuid[]))
+           AND ($4::uuid IS NULL OR s.challenge_id = $4)
+           AND ($5::text IS NULL OR s.verdict = $5)
+           AND ($6::bigint IS NULL OR s.id < $6)
+         ORDER BY s.id DESC
+         LIMIT $7",
+    )
+    .bind(contest_id)
+    .bind(team_id)
+    .bind(locked)
+    .bind(query.challenge_id)
+    .bind(verdict)
+    .bind(query.before_id)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    if rows.len() as i64 == limit {
+        response.next_before_id = rows.last().map(|row| row.id);
+    }
+    response.items = rows
+        .into_iter()
+        .map(|row| {
+            let hidden = view.cutoff.is_some_and(|cutoff| row.submitted_at >= cutoff);
+            let points = |value: i32| if hidden { None } else { Some(value) };
+            ContestSubmissionItem {
+                id: row.id,
+                challenge_id: row.challenge_id,
+                challenge_title: row.challenge_title,
+                stage_key: row.stage_key,
+                user_id: row.user_id,
+                username: row.username,
+                submitted_flag: row.submitted_flag,
+                verdict: row.verdict,
+                score_awarded: points(row.score_awarded),
+                blood_bonus: points(row.blood_bonus),
+                penalty_points: points(row.penalty_points),
+                message: row.judger_message,
+                submitted_at: row.submitted_at,
+            }
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
 async fn list_contest_challenge_attachments(
     State(state): State<Arc<AppState>>,
     Path((contest_id, challenge_id)): Path<(Uuid, Uuid)>,
@@ -571,13 +1090,123 @@ async fn download_contest_challenge_attachment(
     ))
 }
 
+async fn unlock_contest_challenge_hint(
+    State(state): State<Arc<AppState>>,
+    Path((contest_id, challenge_id, hint_id)): Path<(Uuid, Uuid, Uuid)>,
+    current_user: AuthenticatedUser,
+) -> AppResult<Json<UnlockChallengeHintResponse>> {
+    ensure_contest_challenge_access(state.as_ref(), contest_id, challenge_id, &current_user).await?;
+
+    if is_privileged_role(&current_user.role) {
+        return Err(AppError::BadRequest(
+            "privileged role does not need to unlock hints".to_string(),
+        ));
+    }
+
+    let contest = load_contest_gate(state.as_ref(), contest_id).await?;
+    if contest.status != "running" {
+        return Err(AppError::BadRequest(
+            "hints can only be unlocked while the contest is running".to_string(),
+        ));
+    }
+
+    let team_id = ensure_contest_team(state.as_ref(), &contest, current_user.user_id).await?;
+
+    let hint = sqlx::query_as::<_, ChallengeHintUnlockTargetRow>(
+        "SELECT content, cost, release_at
+         FROM challenge_hints
+         WHERE id = $1
+           AND challenge_id = $2
+         LIMIT 1",
+    )
+    .bind(hint_id)
+    .bind(challenge_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest("challenge hint not found".to_string()))?;
+
+    if let Some(release_at) = hint.release_at {
+        if release_at > Utc::now() {
+            return Err(AppError::BadRequest(
+                "challenge hint has not been released yet".to_string(),
+            ));
+        }
+    }
+
+    let inserted = sqlx::query_as::<_, ChallengeHintUnlockRow>(
+        "INSERT INTO challenge_hint_unlocks (contest_id, challenge_id, hint_id, team_id, user_id, cost)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT (contest_id, hint_id, team_id) DO NOTHING
+         RETURNING cost, unlocked_at",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(hint_id)
+    .bind(team_id)
+    .bind(current_user.user_id)
+    .bind(hint.cost)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let already_unlocked = inserted.is_none();
+    let unlock = match inserted {
+        Some(row) => row,
+        None => sqlx::query_as::<_, ChallengeHintUnlockRow>(
+            "SELECT cost, unlocked_at
+             FROM challenge_hint_unlocks
+             WHERE contest_id = $1
+               AND hint_id = $2
+               AND team_id = $3
+             LIMIT 1",
+        )
+        .bind(contest_id)
+        .bind(hint_id)
+        .bind(team_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(AppError::internal)?,
+    };
+
+    if !already_unlocked {
+        record_audit_log(
+            state.as_ref(),
+            &current_user,
+            "contest.hint.unlock",
+            "challenge_hint",
+            Some(hint_id),
+            json!({
+                "contest_id": contest_id,
+                "challenge_id": challenge_id,
+                "team_id": team_id,
+                "cost": unlock.cost
+            }),
+        )
+        .await;
+
+        if unlock.cost > 0 {
+            publish_scoreboard_update(state.as_ref(), contest_id, SCOREBOARD_EVENT_UPDATE).await;
+        }
+    }
+
+    Ok(Json(UnlockChallengeHintResponse {
+        hint_id,
+        challenge_id,
+        content: hint.content,
+        cost: unlock.cost,
+        unlocked_at: unlock.unlocked_at,
+        already_unlocked,
+    }))
+}
+
 async fn ensure_contest_challenge_access(
     state: &AppState,
     contest_id: Uuid,
     challenge_id: Uuid,
     current_user: &AuthenticatedUser,
 ) -> AppResult<()> {
-    ensure_user_contest_workspace_access(state, contest_id, current_user).await?;
+    let team_id = ensure_user_contest_workspace_access(state, contest_id, current_user).await?;
 
     let row = sqlx::query_as::<_, ContestChallengeAccessRow>(
         "SELECT ct.status AS contest_status,
@@ -620,6 +1249,10 @@ async fn ensure_contest_challenge_access(
         }
     }
 
+    if let Some(team_id) = team_id {
+        ensure_challenge_unlocked(state, contest_id, challenge_id, team_id).await?;
+    }
+
     Ok(())
 }
 
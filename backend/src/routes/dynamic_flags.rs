@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use sqlx::FromRow;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    state::AppState,
+};
+
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct DynamicFlagOwnerRow {
+    pub team_id: Uuid,
+    pub issued_at: DateTime<Utc>,
+}
+
+fn dynamic_flag_cache_key(contest_id: Uuid, challenge_id: Uuid, team_id: Uuid) -> String {
+    format!("flag:dynamic:{}:{}:{}", contest_id, challenge_id, team_id)
+}
+
+/// Returns the team's dynamic flag, issuing one if needed. Postgres is the
+/// source of truth; Redis only caches the value for the judge hot path.
+pub(crate) async fn provision_dynamic_flag(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Uuid,
+) -> AppResult<String> {
+    if let Some(flag) =
+        load_persisted_dynamic_flag(state, contest_id, challenge_id, team_id).await?
+    {
+        cache_dynamic_flag(state, contest_id, challenge_id, team_id, &flag).await;
+        return Ok(flag);
+    }
+
+    // Flags issued before they were persisted only live in Redis; adopt them
+    // so running instances keep their flag.
+    let key = dynamic_flag_cache_key(contest_id, challenge_id, team_id);
+    let mut redis_conn = state.redis.clone();
+    let legacy: Option<String> = redis_conn.get(&key).await.map_err(AppError::internal)?;
+
+    let candidate = legacy.unwrap_or_else(|| {
+        let challenge_prefix = challenge_id.as_simple().to_string();
+        let random_part = Uuid::new_v4().as_simple().to_string();
+        format!("ctf{{{}-{}}}", &challenge_prefix[..8], &random_part[..12])
+    });
+
+    let inserted = sqlx::query_scalar::<_, String>(
+        "INSERT INTO dynamic_flags (contest_id, challenge_id, team_id, flag_value)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (contest_id, challenge_id, team_id) DO NOTHING
+         RETURNING flag_value",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(team_id)
+    .bind(&candidate)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let flag = match inserted {
+        Some(flag) => flag,
+        None => load_persisted_dynamic_flag(state, contest_id, challenge_id, team_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::internal(anyhow::anyhow!("dynamic flag vanished after conflict"))
+            })?,
+    };
+
+    cache_dynamic_flag(state, contest_id, challenge_id, team_id, &flag).await;
+    Ok(flag)
+}
+
+/// Looks up the issued flag for judging, refilling the Redis cache from
+/// Postgres when it has been flushed.
+pub(crate) async fn load_dynamic_flag(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Uuid,
+) -> AppResult<Option<String>> {
+    let key = dynamic_flag_cache_key(contest_id, challenge_id, team_id);
+    let mut redis_conn = state.redis.clone();
+
+    match redis_conn.get::<_, Option<String>>(&key).await {
+        Ok(Some(flag)) => return Ok(Some(flag)),
+        Ok(None) => {}
+        Err(err) => {
+            warn!(
+                contest_id = %contest_id,
+                challenge_id = %challenge_id,
+                team_id = %team_id,
+                error = %err,
+                "failed to read dynamic flag cache, falling back to database"
+            );
+        }
+    }
+
+    let flag = load_persisted_dynamic_flag(state, contest_id, challenge_id, team_id).await?;
+    if let Some(flag) = flag.as_deref() {
+        cache_dynamic_flag(state, contest_id, challenge_id, team_id, flag).await;
+    }
+    Ok(flag)
+}
+
+/// Finds another team that was issued exactly `flag_value` for this challenge.
+pub(crate) async fn find_dynamic_flag_owner(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    exclude_team_id: Uuid,
+    flag_value: &str,
+) -> AppResult<Option<DynamicFlagOwnerRow>> {
+    sqlx::query_as::<_, DynamicFlagOwnerRow>(
+        "SELECT team_id, issued_at
+         FROM dynamic_flags
+         WHERE contest_id = $1
+           AND challenge_id = $2
+           AND flag_value = $3
+           AND team_id <> $4
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(flag_value)
+    .bind(exclude_team_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)
+}
+
+async fn load_persisted_dynamic_flag(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Uuid,
+) -> AppResult<Option<String>> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT flag_value
+         FROM dynamic_flags
+         WHERE contest_id = $1
+           AND challenge_id = $2
+           AND team_id = $3
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(team_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)
+}
+
+async fn cache_dynamic_flag(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Uuid,
+    flag: &str,
+) {
+    let key = dynamic_flag_cache_key(contest_id, challenge_id, team_id);
+    let mut redis_conn = state.redis.clone();
+    let result: Result<(), redis::RedisError> = redis_conn.set(&key, flag).await;
+    if let Err(err) = result {
+        warn!(
+            contest_id = %contest_id,
+            challenge_id = %challenge_id,
+            team_id = %team_id,
+            error = %err,
+            "failed to cache dynamic flag"
+        );
+    }
+}
@@ -13,7 +13,6 @@ use axum::{
 };
 use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
-use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::FromRow;
@@ -28,7 +27,12 @@ use uuid::Uuid;
 use crate::{
     auth::AuthenticatedUser,
     error::{AppError, AppResult},
-    routes::contest_access::ensure_team_contest_workspace_access,
+    routes::{
+        attack_defense::CONTEST_TYPE_ATTACK_DEFENSE,
+        challenge_unlocks::ensure_challenge_unlocked,
+        contest_access::{ensure_team_contest_workspace_access, resolve_contest_team_id},
+        dynamic_flags::provision_dynamic_flag, koth::CHALLENGE_TYPE_KOTH,
+    },
     runtime_template::{
         build_single_image_compose_template, parse_runtime_metadata_options,
         render_compose_template_variables, validate_compose_template_schema, RuntimeAccessMode,
@@ -87,7 +91,7 @@ struct InstanceResponse {
     id: Uuid,
     contest_id: Uuid,
     challenge_id: Uuid,
-    team_id: Uuid,
+    team_id: Option<Uuid>,
     status: String,
     subnet: String,
     compose_project_name: String,
@@ -128,15 +132,12 @@ struct WireguardAccessMeta {
     config_host_port: u16,
 }
 
-#[derive(Debug, FromRow)]
-struct TeamMembershipRow {
-    team_id: Uuid,
-}
-
 #[derive(Debug, FromRow)]
 struct RuntimePolicyRow {
     contest_status: String,
     contest_visibility: String,
+    contest_type: String,
+    contest_end_at: DateTime<Utc>,
     challenge_type: String,
     flag_mode: String,
     compose_template: Option<String>,
@@ -157,7 +158,7 @@ struct InstanceRow {
     id: Uuid,
     contest_id: Uuid,
     challenge_id: Uuid,
-    team_id: Uuid,
+    team_id: Option<Uuid>,
     status: String,
     subnet: String,
     compose_project_name: String,
@@ -241,7 +242,8 @@ async fn start_instance(
     current_user: AuthenticatedUser,
     Json(req): Json<InstanceActionRequest>,
 ) -> AppResult<Json<InstanceResponse>> {
-    let team_id = fetch_user_team_id(state.as_ref(), current_user.user_id).await?;
+    let team_id =
+        resolve_contest_team_id(state.as_ref(), req.contest_id, current_user.user_id).await?;
     ensure_team_contest_workspace_access(
         state.as_ref(),
         req.contest_id,
@@ -252,13 +254,21 @@ async fn start_instance(
     let policy = fetch_runtime_policy(state.as_ref(), req.contest_id, req.challenge_id).await?;
 
     validate_runtime_policy(&policy, &current_user.role, true)?;
-    let compose_source = compose_source_from_policy(policy)?;
-
+    if !is_privileged_role(&current_user.role) {
+        ensure_challenge_unlocked(state.as_ref(), req.contest_id, req.challenge_id, team_id)
+            .await?;
+    }
     let now = Utc::now();
-    let expires_at = now + Duration::hours(INSTANCE_TTL_HOURS);
+    let expires_at = instance_expires_at(&policy, now);
+    let compose_source = compose_source_from_policy(policy)?;
 
-    if let Some(instance) =
-        fetch_instance_row(state.as_ref(), req.contest_id, req.challenge_id, team_id).await?
+    if let Some(instance) = fetch_instance_row(
+        state.as_ref(),
+        req.contest_id,
+        req.challenge_id,
+        Some(team_id),
+    )
+    .await?
     {
         if instance.status == "running" && !is_expired(&instance, now) {
             return Ok(Json(instance_to_response(
@@ -269,10 +279,110 @@ async fn start_instance(
         }
     }
 
-    let pending = ensure_instance_pending(
+    let running = launch_instance(
         state.as_ref(),
         req.contest_id,
         req.challenge_id,
+        Some(team_id),
+        &compose_source,
+        now,
+        expires_at,
+    )
+    .await?;
+    Ok(Json(instance_to_response(
+        state.as_ref(),
+        running,
+        "instance started".to_string(),
+    )))
+}
+
+/// Starts a team's service of an attack-defense contest on behalf of an
+/// organizer and returns its entrypoint. Running instances are kept.
+pub(crate) async fn deploy_team_service(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Uuid,
+) -> AppResult<String> {
+    let policy = fetch_runtime_policy(state, contest_id, challenge_id).await?;
+    validate_runtime_policy(&policy, "admin", false)?;
+
+    let now = Utc::now();
+    let expires_at = instance_expires_at(&policy, now);
+    let compose_source = compose_source_from_policy(policy)?;
+
+    if let Some(instance) =
+        fetch_instance_row(state, contest_id, challenge_id, Some(team_id)).await?
+    {
+        if instance.status == "running" && !is_expired(&instance, now) {
+            return Ok(instance.entrypoint_url);
+        }
+    }
+
+    let running = launch_instance(
+        state,
+        contest_id,
+        challenge_id,
+        Some(team_id),
+        &compose_source,
+        now,
+        expires_at,
+    )
+    .await?;
+    Ok(running.entrypoint_url)
+}
+
+/// Starts the shared deployment of a king-of-the-hill challenge and returns
+/// its entrypoint. A running deployment is kept.
+pub(crate) async fn deploy_shared_service(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+) -> AppResult<String> {
+    let policy = fetch_runtime_policy(state, contest_id, challenge_id).await?;
+    if policy.challenge_type != CHALLENGE_TYPE_KOTH {
+        return Err(AppError::BadRequest(
+            "challenge is not a king-of-the-hill challenge".to_string(),
+        ));
+    }
+    ensure_runtime_template(&policy)?;
+
+    let now = Utc::now();
+    let expires_at = instance_expires_at(&policy, now);
+    let compose_source = compose_source_from_policy(policy)?;
+
+    if let Some(instance) = fetch_instance_row(state, contest_id, challenge_id, None).await? {
+        if instance.status == "running" && !is_expired(&instance, now) {
+            return Ok(instance.entrypoint_url);
+        }
+    }
+
+    let running = launch_instance(
+        state,
+        contest_id,
+        challenge_id,
+        None,
+        &compose_source,
+        now,
+        expires_at,
+    )
+    .await?;
+    Ok(running.entrypoint_url)
+}
+
+async fn launch_instance(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Option<Uuid>,
+    compose_source: &ComposeRenderSource,
+    now: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+) -> AppResult<InstanceRow> {
+    let pending = ensure_instance_pending(
+        state,
+        contest_id,
+        challenge_id,
         team_id,
         now,
         expires_at,
@@ -280,20 +390,26 @@ async fn start_instance(
     )
     .await?;
 
-    let compose_file = persist_compose_file(state.as_ref(), &pending, &compose_source).await?;
-    if let Err(err) =
-        compose_up_with_self_heal(state.as_ref(), &pending, &compose_file, false).await
-    {
-        let _ = update_instance_status(state.as_ref(), pending.id, "failed").await;
+    let compose_file = persist_compose_file(state, &pending, compose_source).await?;
+    if let Err(err) = compose_up_with_self_heal(state, &pending, &compose_file, false).await {
+        let _ = update_instance_status(state, pending.id, "failed").await;
         return Err(err);
     }
 
-    let running = mark_instance_running(state.as_ref(), pending.id, now, expires_at).await?;
-    Ok(Json(instance_to_response(
-        state.as_ref(),
-        running,
-        "instance started".to_string(),
-    )))
+    mark_instance_running(state, pending.id, now, expires_at).await
+}
+
+/// Attack-defense services and king-of-the-hill hills stay up for the whole
+/// contest instead of the usual instance TTL.
+fn instance_expires_at(policy: &RuntimePolicyRow, now: DateTime<Utc>) -> DateTime<Utc> {
+    let ttl_expiry = now + Duration::hours(INSTANCE_TTL_HOURS);
+    if policy.contest_type == CONTEST_TYPE_ATTACK_DEFENSE
+        || policy.challenge_type == CHALLENGE_TYPE_KOTH
+    {
+        policy.contest_end_at.max(ttl_expiry)
+    } else {
+        ttl_expiry
+    }
 }
 
 async fn stop_instance(
@@ -301,7 +417,8 @@ async fn stop_instance(
     current_user: AuthenticatedUser,
     Json(req): Json<InstanceActionRequest>,
 ) -> AppResult<Json<InstanceResponse>> {
-    let team_id = fetch_user_team_id(state.as_ref(), current_user.user_id).await?;
+    let team_id =
+        resolve_contest_team_id(state.as_ref(), req.contest_id, current_user.user_id).await?;
     ensure_team_contest_workspace_access(
         state.as_ref(),
         req.contest_id,
@@ -310,9 +427,14 @@ async fn stop_instance(
     )
     .await?;
 
-    let instance = fetch_instance_row(state.as_ref(), req.contest_id, req.challenge_id, team_id)
-        .await?
-        .ok_or(AppError::BadRequest("instance not found".to_string()))?;
+    let instance = fetch_instance_row(
+        state.as_ref(),
+        req.contest_id,
+        req.challenge_id,
+        Some(team_id),
+    )
+    .await?
+    .ok_or(AppError::BadRequest("instance not found".to_string()))?;
 
     if instance.status == "destroyed" {
         return Err(AppError::BadRequest(
@@ -345,7 +467,8 @@ async fn reset_instance(
     current_user: AuthenticatedUser,
     Json(req): Json<InstanceActionRequest>,
 ) -> AppResult<Json<InstanceResponse>> {
-    let team_id = fetch_user_team_id(state.as_ref(), current_user.user_id).await?;
+    let team_id =
+        resolve_contest_team_id(state.as_ref(), req.contest_id, current_user.user_id).await?;
     ensure_team_contest_workspace_access(
         state.as_ref(),
         req.contest_id,
@@ -356,16 +479,15 @@ async fn reset_instance(
     let policy = fetch_runtime_policy(state.as_ref(), req.contest_id, req.challenge_id).await?;
 
     validate_runtime_policy(&policy, &current_user.role, true)?;
-    let compose_source = compose_source_from_policy(policy)?;
-
     let now = Utc::now();
-    let expires_at = now + Duration::hours(INSTANCE_TTL_HOURS);
+    let expires_at = instance_expires_at(&policy, now);
+    let compose_source = compose_source_from_policy(policy)?;
 
     let pending = ensure_instance_pending(
         state.as_ref(),
         req.contest_id,
         req.challenge_id,
-        team_id,
+        Some(team_id),
         now,
         expires_at,
         compose_source.entrypoint_mode,
@@ -381,7 +503,7 @@ async fn reset_instance(
             instance_id = %pending.id,
             contest_id = %pending.contest_id,
             challenge_id = %pending.challenge_id,
-            team_id = %pending.team_id,
+            team_id = ?pending.team_id,
             error = %err,
             "compose down during reset failed; continue to up"
         );
@@ -406,7 +528,8 @@ async fn destroy_instance(
     current_user: AuthenticatedUser,
     Json(req): Json<InstanceActionRequest>,
 ) -> AppResult<Json<InstanceResponse>> {
-    let team_id = fetch_user_team_id(state.as_ref(), current_user.user_id).await?;
+    let team_id =
+        resolve_contest_team_id(state.as_ref(), req.contest_id, current_user.user_id).await?;
     ensure_team_contest_workspace_access(
         state.as_ref(),
         req.contest_id,
@@ -415,9 +538,14 @@ async fn destroy_instance(
     )
     .await?;
 
-    let instance = fetch_instance_row(state.as_ref(), req.contest_id, req.challenge_id, team_id)
-        .await?
-        .ok_or(AppError::BadRequest("instance not found".to_string()))?;
+    let instance = fetch_instance_row(
+        state.as_ref(),
+        req.contest_id,
+        req.challenge_id,
+        Some(team_id),
+    )
+    .await?
+    .ok_or(AppError::BadRequest("instance not found".to_string()))?;
 
     if instance.status != "destroyed" {
         let compose_file = ensure_compose_file_for_existing(state.as_ref(), &instance).await?;
@@ -474,7 +602,8 @@ async fn heartbeat_instance(
     current_user: AuthenticatedUser,
     Json(req): Json<InstanceActionRequest>,
 ) -> AppResult<Json<InstanceResponse>> {
-    let team_id = fetch_user_team_id(state.as_ref(), current_user.user_id).await?;
+    let team_id =
+        resolve_contest_team_id(state.as_ref(), req.contest_id, current_user.user_id).await?;
     ensure_team_contest_workspace_access(
         state.as_ref(),
         req.contest_id,
@@ -525,7 +654,7 @@ async fn get_instance_by_challenge(
     current_user: AuthenticatedUser,
     AxumPath((contest_id, challenge_id)): AxumPath<(Uuid, Uuid)>,
 ) -> AppResult<Json<InstanceResponse>> {
-    let team_id = fetch_user_team_id(state.as_ref(), current_user.user_id).await?;
+    let team_id = resolve_contest_team_id(state.as_ref(), contest_id, current_user.user_id).await?;
     ensure_team_contest_workspace_access(
         state.as_ref(),
         contest_id,
@@ -534,7 +663,7 @@ async fn get_instance_by_challenge(
     )
     .await?;
 
-    let instance = fetch_instance_row(state.as_ref(), contest_id, challenge_id, team_id)
+    let instance = fetch_instance_row(state.as_ref(), contest_id, challenge_id, Some(team_id))
         .await?
         .ok_or(AppError::BadRequest("instance not found".to_string()))?;
 
@@ -550,7 +679,7 @@ async fn get_instance_wireguard_config(
     current_user: AuthenticatedUser,
     AxumPath((contest_id, challenge_id)): AxumPath<(Uuid, Uuid)>,
 ) -> AppResult<Json<WireguardConfigResponse>> {
-    let team_id = fetch_user_team_id(state.as_ref(), current_user.user_id).await?;
+    let team_id = resolve_contest_team_id(state.as_ref(), contest_id, current_user.user_id).await?;
     ensure_team_contest_workspace_access(
         state.as_ref(),
         contest_id,
@@ -559,7 +688,7 @@ async fn get_instance_wireguard_config(
     )
     .await?;
 
-    let instance = fetch_instance_row(state.as_ref(), contest_id, challenge_id, team_id)
+    let instance = fetch_instance_row(state.as_ref(), contest_id, challenge_id, Some(team_id))
         .await?
         .ok_or(AppError::BadRequest("instance not found".to_string()))?;
 
@@ -594,21 +723,6 @@ async fn get_instance_wireguard_config(
     }))
 }
 
-async fn fetch_user_team_id(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
-    let team = sqlx::query_as::<_, TeamMembershipRow>(
-        "SELECT team_id FROM team_members WHERE user_id = $1 LIMIT 1",
-    )
-    .bind(user_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(AppError::internal)?
-    .ok_or(AppError::BadRequest(
-        "join or create a team before entering the contest".to_string(),
-    ))?;
-
-    Ok(team.team_id)
-}
-
 async fn fetch_runtime_policy(
     state: &AppState,
     contest_id: Uuid,
@@ -617,6 +731,8 @@ async fn fetch_runtime_policy(
     sqlx::query_as::<_, RuntimePolicyRow>(
         "SELECT ct.status AS contest_status,
                 ct.visibility AS contest_visibility,
+                ct.contest_type,
+                ct.end_at AS contest_end_at,
                 c.challenge_type,
                 c.flag_mode,
                 c.compose_template,
@@ -692,12 +808,22 @@ fn validate_runtime_policy(
         return Err(AppError::BadRequest("contest is not running".to_string()));
     }
 
+    if policy.challenge_type == CHALLENGE_TYPE_KOTH {
+        return Err(AppError::BadRequest(
+            "king-of-the-hill challenges run on a shared deployment".to_string(),
+        ));
+    }
+
     if policy.challenge_type != "dynamic" && policy.challenge_type != "internal" {
         return Err(AppError::BadRequest(
             "challenge type does not require runtime instance".to_string(),
         ));
     }
 
+    ensure_runtime_template(policy)
+}
+
+fn ensure_runtime_template(policy: &RuntimePolicyRow) -> AppResult<()> {
     let runtime_options =
         parse_runtime_metadata_options(&policy.metadata).map_err(AppError::BadRequest)?;
     if runtime_options.mode == RuntimeMode::Compose
@@ -829,11 +955,13 @@ fn is_privileged_role(role: &str) -> bool {
     role == "admin" || role == "judge"
 }
 
+/// `team_id = None` selects the shared deployment of a king-of-the-hill
+/// challenge.
 async fn fetch_instance_row(
     state: &AppState,
     contest_id: Uuid,
     challenge_id: Uuid,
-    team_id: Uuid,
+    team_id: Option<Uuid>,
 ) -> AppResult<Option<InstanceRow>> {
     sqlx::query_as::<_, InstanceRow>(
         "SELECT id,
@@ -851,7 +979,7 @@ async fn fetch_instance_row(
                 destroyed_at,
                 last_heartbeat_at
          FROM instances
-         WHERE contest_id = $1 AND challenge_id = $2 AND team_id = $3
+         WHERE contest_id = $1 AND challenge_id = $2 AND team_id IS NOT DISTINCT FROM $3
          LIMIT 1",
     )
     .bind(contest_id)
@@ -866,7 +994,7 @@ async fn ensure_instance_pending(
     state: &AppState,
     contest_id: Uuid,
     challenge_id: Uuid,
-    team_id: Uuid,
+    team_id: Option<Uuid>,
     now: DateTime<Utc>,
     expires_at: DateTime<Utc>,
     entrypoint_mode: RuntimeEntrypointMode,
@@ -921,7 +1049,7 @@ async fn ensure_instance_pending(
 struct InsertInstanceRowParams<'a> {
     contest_id: Uuid,
     challenge_id: Uuid,
-    team_id: Uuid,
+    team_id: Option<Uuid>,
     subnet: &'a str,
     compose_project_name: &'a str,
     entrypoint_url: &'a str,
@@ -1435,7 +1563,7 @@ pub(crate) async fn run_expired_instance_reaper(
                     instance_id = %instance.id,
                     contest_id = %instance.contest_id,
                     challenge_id = %instance.challenge_id,
-                    team_id = %instance.team_id,
+                    team_id = ?instance.team_id,
                     error = %err,
                     "instance reaper failed to prepare compose file"
                 );
@@ -1456,7 +1584,7 @@ pub(crate) async fn run_expired_instance_reaper(
                 instance_id = %instance.id,
                 contest_id = %instance.contest_id,
                 challenge_id = %instance.challenge_id,
-                team_id = %instance.team_id,
+                team_id = ?instance.team_id,
                 compose_project_name = %instance.compose_project_name,
                 error = %err,
                 "instance reaper failed during compose down"
@@ -1515,7 +1643,7 @@ pub(crate) async fn run_stale_instance_reaper(
                     instance_id = %instance.id,
                     contest_id = %instance.contest_id,
                     challenge_id = %instance.challenge_id,
-                    team_id = %instance.team_id,
+                    team_id = ?instance.team_id,
                     error = %err,
                     "stale instance reaper failed to prepare compose file"
                 );
@@ -1530,7 +1658,7 @@ pub(crate) async fn run_stale_instance_reaper(
                 instance_id = %instance.id,
                 contest_id = %instance.contest_id,
                 challenge_id = %instance.challenge_id,
-                team_id = %instance.team_id,
+                team_id = ?instance.team_id,
                 compose_project_name = %instance.compose_project_name,
                 error = %err,
                 "stale instance reaper failed during compose down"
@@ -1600,7 +1728,7 @@ async fn destroy_instance_candidates(
                     instance_id = %instance.id,
                     contest_id = %instance.contest_id,
                     challenge_id = %instance.challenge_id,
-                    team_id = %instance.team_id,
+                    team_id = ?instance.team_id,
                     error = %err,
                     "failed to prepare compose file for force destroy"
                 );
@@ -1615,7 +1743,7 @@ async fn destroy_instance_candidates(
                 instance_id = %instance.id,
                 contest_id = %instance.contest_id,
                 challenge_id = %instance.challenge_id,
-                team_id = %instance.team_id,
+                team_id = ?instance.team_id,
                 compose_project_name = %instance.compose_project_name,
                 error = %err,
                 "failed to compose down during force destroy"
@@ -1648,10 +1776,10 @@ async fn allocate_subnet(
     state: &AppState,
     contest_id: Uuid,
     challenge_id: Uuid,
-    team_id: Uuid,
+    team_id: Option<Uuid>,
 ) -> AppResult<String> {
     let span = (SUBNET_SECOND_OCTET_END - SUBNET_SECOND_OCTET_START + 1) as usize * 256;
-    let seed = subnet_seed(contest_id, challenge_id, team_id) as usize;
+    let seed = subnet_seed(contest_id, challenge_id, team_id.unwrap_or_default()) as usize;
 
     for offset in 0..span {
         let idx = (seed + offset) % span;
@@ -1690,12 +1818,15 @@ fn subnet_seed(contest_id: Uuid, challenge_id: Uuid, team_id: Uuid) -> u32 {
     seed
 }
 
-fn compose_project_name(contest_id: Uuid, challenge_id: Uuid, team_id: Uuid) -> String {
+fn compose_project_name(contest_id: Uuid, challenge_id: Uuid, team_id: Option<Uuid>) -> String {
     let contest = contest_id.as_simple().to_string();
     let challenge = challenge_id.as_simple().to_string();
-    let team = team_id.as_simple().to_string();
+    let team = match team_id {
+        Some(team_id) => team_id.as_simple().to_string()[..8].to_string(),
+        None => "shared".to_string(),
+    };
 
-    let mut name = format!("ctf_{}_{}_{}", &contest[..8], &challenge[..8], &team[..8]);
+    let mut name = format!("ctf_{}_{}_{}", &contest[..8], &challenge[..8], team);
 
     if name.len() > 96 {
         name.truncate(96);
@@ -1927,7 +2058,10 @@ fn render_compose_template(
     heartbeat_interval_seconds: u64,
 ) -> String {
     let network_name = format!("{}_net", instance.compose_project_name);
-    let team_id = instance.team_id.to_string();
+    let team_id = instance
+        .team_id
+        .map(|value| value.to_string())
+        .unwrap_or_default();
     let contest_id = instance.contest_id.to_string();
     let challenge_id = instance.challenge_id.to_string();
     let entrypoint_host = subnet_host_ip(&instance.subnet, 2).unwrap_or_default();
@@ -2062,24 +2196,9 @@ async fn provision_dynamic_flag_if_needed(
         return Ok(None);
     }
 
-    let key = format!("flag:dynamic:{}:{}:{}", contest_id, challenge_id, team_id);
-    let mut redis_conn = state.redis.clone();
-
-    let existing: Option<String> = redis_conn.get(&key).await.map_err(AppError::internal)?;
-    if existing.is_some() {
-        return Ok(existing);
-    }
-
-    let challenge_prefix = challenge_id.as_simple().to_string();
-    let random_part = Uuid::new_v4().as_simple().to_string();
-    let generated = format!("ctf{{{}-{}}}", &challenge_prefix[..8], &random_part[..12]);
-
-    let _: () = redis_conn
-        .set(&key, generated.as_str())
+    provision_dynamic_flag(state, contest_id, challenge_id, team_id)
         .await
-        .map_err(AppError::internal)?;
-
-    Ok(Some(generated))
+        .map(Some)
 }
 
 async fn persist_compose_file(
@@ -2087,14 +2206,19 @@ async fn persist_compose_file(
     instance: &InstanceRow,
     source: &ComposeRenderSource,
 ) -> AppResult<PathBuf> {
-    let dynamic_flag = provision_dynamic_flag_if_needed(
-        state,
-        &source.flag_mode,
-        instance.contest_id,
-        instance.challenge_id,
-        instance.team_id,
-    )
-    .await?;
+    let dynamic_flag = match instance.team_id {
+        Some(team_id) => {
+            provision_dynamic_flag_if_needed(
+                state,
+                &source.flag_mode,
+                instance.contest_id,
+                instance.challenge_id,
+                team_id,
+            )
+            .await?
+        }
+        None => None,
+    };
 
     let heartbeat_report_url = state.config.instance_heartbeat_report_url.trim();
     let heartbeat_report_interval_seconds = state
@@ -2262,7 +2386,7 @@ async fn compose_up_with_self_heal(
             instance_id = %instance.id,
             contest_id = %instance.contest_id,
             challenge_id = %instance.challenge_id,
-            team_id = %instance.team_id,
+            team_id = ?instance.team_id,
             compose_project_name = %instance.compose_project_name,
             error = %initial_err,
             "instance compose up failed, starting self-heal retry"
@@ -2275,7 +2399,7 @@ async fn compose_up_with_self_heal(
                 instance_id = %instance.id,
                 contest_id = %instance.contest_id,
                 challenge_id = %instance.challenge_id,
-                team_id = %instance.team_id,
+                team_id = ?instance.team_id,
                 compose_project_name = %instance.compose_project_name,
                 error = %down_err,
                 "instance self-heal cleanup failed before retry"
@@ -2538,7 +2662,10 @@ fn instance_ssh_gateway_username() -> String {
 fn instance_ssh_gateway_password(state: &AppState, instance: &InstanceRow) -> String {
     let seed = format!(
         "{}:{}:{}:{}",
-        state.config.jwt_secret, instance.id, instance.team_id, instance.challenge_id
+        state.config.jwt_secret,
+        instance.id,
+        instance.team_id.unwrap_or_default(),
+        instance.challenge_id
     );
     let digest = Uuid::new_v5(&Uuid::NAMESPACE_URL, seed.as_bytes())
         .as_simple()
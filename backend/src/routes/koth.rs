@@ -0,0 +1,631 @@
+use std::{sync::Arc, time::Instant};
+
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use futures_util::future::join_all;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use sqlx::FromRow;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{AppError, AppResult},
+    routes::{
+        attack_defense::{load_ad_participants, AdParticipantRow},
+        contest_access::ensure_user_contest_workspace_access,
+        scoreboard::{publish_scoreboard_update, SCOREBOARD_EVENT_UPDATE},
+        scoring::ScoreAdjustment,
+        webhook_verifier::{
+            parse_signed_endpoint_config, post_signed_json, truncate_message, WebhookVerifierConfig,
+        },
+    },
+    state::AppState,
+};
+
+pub(crate) const CHALLENGE_TYPE_KOTH: &str = "koth";
+const KOTH_CHECKER_METADATA_KEY: &str = "koth_checker";
+/// Label the team token key is derived under, so tokens never share a key
+/// with JWTs even though both come from `jwt_secret`.
+const KOTH_TOKEN_KEY_LABEL: &[u8] = b"koth-team-token";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KothTickStatus {
+    Up,
+    Down,
+    Error,
+}
+
+impl KothTickStatus {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Error => "error",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "up" => Self::Up,
+            "down" => Self::Down,
+            _ => Self::Error,
+        }
+    }
+}
+
+/// Body POSTed to `metadata.koth_checker` once per tick. The checker reads
+/// the owner token currently planted on the hill at `target`.
+#[derive(Debug, Serialize)]
+struct KothCheckerRequest<'a> {
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    tick: i32,
+    target: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct KothCheckerResponse {
+    status: String,
+    #[serde(default)]
+    owner_token: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KothTickerSummary {
+    pub due: i64,
+    pub ticked: i64,
+    pub failed: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct KothContestRow {
+    start_at: DateTime<Utc>,
+    end_at: DateTime<Utc>,
+    koth_tick_seconds: i32,
+    koth_token_salt: Uuid,
+}
+
+#[derive(Debug, FromRow)]
+struct KothHillRow {
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    metadata: Value,
+    static_score: i32,
+    contest_start_at: DateTime<Utc>,
+    koth_tick_seconds: i32,
+    koth_token_salt: Uuid,
+    target: Option<String>,
+    last_tick_number: Option<i32>,
+}
+
+#[derive(Debug, FromRow)]
+struct KothHillStatusRow {
+    challenge_id: Uuid,
+    challenge_title: String,
+    static_score: i32,
+    target: Option<String>,
+    status: Option<String>,
+    owner_team_id: Option<Uuid>,
+    owner_team_name: Option<String>,
+    ticked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+struct KothHillItem {
+    challenge_id: Uuid,
+    challenge_title: String,
+    points_per_tick: i32,
+    target: Option<String>,
+    status: Option<String>,
+    owner_team_id: Option<Uuid>,
+    owner_team_name: Option<String>,
+    last_tick_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+struct KothStatusResponse {
+    contest_id: Uuid,
+    tick_seconds: i32,
+    next_tick_at: Option<DateTime<Utc>>,
+    team_token: Option<String>,
+    hills: Vec<KothHillItem>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub(crate) struct KothTickItem {
+    pub id: i64,
+    pub challenge_id: Uuid,
+    pub challenge_title: String,
+    pub tick_number: i32,
+    pub owner_team_id: Option<Uuid>,
+    pub owner_team_name: Option<String>,
+    pub status: String,
+    pub points: i32,
+    pub message: String,
+    pub elapsed_ms: i32,
+    pub ticked_at: DateTime<Utc>,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/contests/{contest_id}/koth", get(get_koth_status))
+}
+
+/// Every king-of-the-hill challenge of the contest with its current owner,
+/// plus the token the caller's team plants on a hill to claim it.
+async fn get_koth_status(
+    State(state): State<Arc<AppState>>,
+    Path(contest_id): Path<Uuid>,
+    current_user: AuthenticatedUser,
+) -> AppResult<Json<KothStatusResponse>> {
+    let team_id =
+        ensure_user_contest_workspace_access(state.as_ref(), contest_id, &current_user).await?;
+
+    let contest = sqlx::query_as::<_, KothContestRow>(
+        "SELECT start_at, end_at, koth_tick_seconds, koth_token_salt
+         FROM contests
+         WHERE id = $1
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest("contest not found".to_string()))?;
+
+    let rows = sqlx::query_as::<_, KothHillStatusRow>(
+        "SELECT c.id AS challenge_id,
+                c.title AS challenge_title,
+                c.static_score,
+                i.entrypoint_url AS target,
+                tick.status,
+                tick.owner_team_id,
+                t.name AS owner_team_name,
+                tick.ticked_at
+         FROM contest_challenges cc
+         JOIN challenges c ON c.id = cc.challenge_id
+         LEFT JOIN instances i
+           ON i.contest_id = cc.contest_id
+          AND i.challenge_id = cc.challenge_id
+          AND i.team_id IS NULL
+          AND i.status = 'running'
+         LEFT JOIN LATERAL (
+             SELECT k.status, k.owner_team_id, k.ticked_at
+             FROM koth_ticks k
+             WHERE k.contest_id = cc.contest_id
+               AND k.challenge_id = cc.challenge_id
+             ORDER BY k.tick_number DESC
+             LIMIT 1
+         ) tick ON TRUE
+         LEFT JOIN teams t ON t.id = tick.owner_team_id
+         WHERE cc.contest_id = $1
+           AND c.challenge_type = 'koth'
+           AND c.is_visible = TRUE
+           AND (cc.release_at IS NULL OR cc.release_at <= NOW())
+         ORDER BY cc.sort_order ASC, c.title ASC",
+    )
+    .bind(contest_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let now = Utc::now();
+    let next_tick_at = (now >= contest.start_at && now < contest.end_at).then(|| {
+        let next = koth_tick_number(contest.start_at, now, contest.koth_tick_seconds) as i64 + 1;
+        contest.start_at + Duration::seconds(next * contest.koth_tick_seconds.max(1) as i64)
+    });
+
+    let hills = rows
+        .into_iter()
+        .map(|row| KothHillItem {
+            challenge_id: row.challenge_id,
+            challenge_title: row.challenge_title,
+            points_per_tick: row.static_score,
+            target: row.target,
+            status: row.status,
+            owner_team_id: row.owner_team_id,
+            owner_team_name: row.owner_team_name,
+            last_tick_at: row.ticked_at,
+        })
+        .collect();
+
+    Ok(Json(KothStatusResponse {
+        contest_id,
+        tick_seconds: contest.koth_tick_seconds,
+        next_tick_at,
+        team_token: team_id.map(|team_id| {
+            koth_team_token(state.as_ref(), contest_id, contest.koth_token_salt, team_id)
+        }),
+        hills,
+    }))
+}
+
+/// Token a team writes onto a hill to claim it; stable until the contest's
+/// tokens are rotated.
+pub(crate) fn koth_team_token(
+    state: &AppState,
+    contest_id: Uuid,
+    token_salt: Uuid,
+    team_id: Uuid,
+) -> String {
+    derive_koth_token(&state.config.jwt_secret, contest_id, token_salt, team_id)
+}
+
+/// HMAC-SHA256 over the contest, its token salt and the team, keyed by a key
+/// derived from `secret` under [`KOTH_TOKEN_KEY_LABEL`].
+fn derive_koth_token(secret: &str, contest_id: Uuid, token_salt: Uuid, team_id: Uuid) -> String {
+    let mut key =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+    key.update(KOTH_TOKEN_KEY_LABEL);
+    let key = key.finalize().into_bytes();
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key).expect("hmac accepts any key length");
+    mac.update(contest_id.as_bytes());
+    mac.update(token_salt.as_bytes());
+    mac.update(team_id.as_bytes());
+
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest[..16]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("koth{{{}}}", hex)
+}
+
+/// Replaces the contest's token salt, which invalidates every team token
+/// handed out so far.
+pub(crate) async fn rotate_koth_team_tokens(state: &AppState, contest_id: Uuid) -> AppResult<()> {
+    sqlx::query(
+        "UPDATE contests
+         SET koth_token_salt = gen_random_uuid(),
+             updated_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(contest_id)
+    .execute(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(())
+}
+
+/// Ticks are numbered from the contest start, so every backend agrees on
+/// which tick is due and each tick is recorded once.
+pub(crate) fn koth_tick_number(
+    start_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    tick_seconds: i32,
+) -> i32 {
+    let elapsed = (now - start_at).num_seconds().max(0);
+    (elapsed / tick_seconds.max(1) as i64).min(i32::MAX as i64) as i32
+}
+
+/// Runs the checker of every hill whose current tick has not been recorded
+/// yet, in running contests only.
+pub(crate) async fn run_koth_ticker(state: &AppState) -> AppResult<KothTickerSummary> {
+    let hills = sqlx::query_as::<_, KothHillRow>(
+        "SELECT cc.contest_id,
+                c.id AS challenge_id,
+                c.metadata,
+                c.static_score,
+                ct.start_at AS contest_start_at,
+                ct.koth_tick_seconds,
+                ct.koth_token_salt,
+                i.entrypoint_url AS target,
+                (SELECT MAX(k.tick_number)
+                 FROM koth_ticks k
+                 WHERE k.contest_id = cc.contest_id
+                   AND k.challenge_id = cc.challenge_id) AS last_tick_number
+         FROM contest_challenges cc
+         JOIN contests ct ON ct.id = cc.contest_id
+         JOIN challenges c ON c.id = cc.challenge_id
+         LEFT JOIN instances i
+           ON i.contest_id = cc.contest_id
+          AND i.challenge_id = cc.challenge_id
+          AND i.team_id IS NULL
+          AND i.status = 'running'
+         WHERE c.challenge_type = 'koth'
+           AND c.is_visible = TRUE
+           AND (cc.release_at IS NULL OR cc.release_at <= NOW())
+           AND ct.status = 'running'
+           AND NOW() >= ct.start_at
+           AND NOW() < ct.end_at",
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    let now = Utc::now();
+    let due: Vec<(KothHillRow, i32)> = hills
+        .into_iter()
+        .filter_map(|hill| {
+            let tick = koth_tick_number(hill.contest_start_at, now, hill.koth_tick_seconds);
+            let recorded = hill.last_tick_number.is_some_and(|last| last >= tick);
+            (!recorded).then_some((hill, tick))
+        })
+        .collect();
+
+    let mut summary = KothTickerSummary {
+        due: due.len() as i64,
+        ticked: 0,
+        failed: 0,
+    };
+    let results = join_all(
+        due.iter()
+            .map(|(hill, tick)| run_koth_tick(state, hill, *tick)),
+    )
+    .await;
+    for ((hill, _), result) in due.iter().zip(results) {
+        match result {
+            Ok(_) => summary.ticked += 1,
+            Err(err) => {
+                summary.failed += 1;
+                warn!(
+                    contest_id = %hill.contest_id,
+                    challenge_id = %hill.challenge_id,
+                    error = %err,
+                    "king-of-the-hill tick failed"
+                );
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Asks the checker who holds the hill and records the tick; the owner earns
+/// the challenge's `static_score` while the hill is up.
+async fn run_koth_tick(state: &AppState, hill: &KothHillRow, tick: i32) -> AppResult<bool> {
+    let started = Instant::now();
+    let (status, owner_token, message) = match (
+        parse_signed_endpoint_config(&hill.metadata, KOTH_CHECKER_METADATA_KEY),
+        hill.target.as_deref(),
+    ) {
+        (Err(err), _) => (KothTickStatus::Error, None, err),
+        (Ok(_), None) => (
+            KothTickStatus::Down,
+            None,
+            "shared deployment is not running".to_string(),
+        ),
+        (Ok(config), Some(target)) => {
+            run_koth_checker(
+                &config,
+                &KothCheckerRequest {
+                    contest_id: hill.contest_id,
+                    challenge_id: hill.challenge_id,
+                    tick,
+                    target,
+                },
+            )
+            .await
+        }
+    };
+
+    let mut message = message;
+    let owner = match owner_token.as_deref() {
+        Some(token) if status == KothTickStatus::Up => {
+            let participants = load_ad_participants(state, hill.contest_id).await?;
+            let owner = resolve_koth_owner(state, hill, &participants, token);
+            if owner.is_none() && message.is_empty() {
+                message = "owner token does not match any team".to_string();
+            }
+            owner
+        }
+        _ => None,
+    };
+    let points = if owner.is_some() {
+        hill.static_score.max(0)
+    } else {
+        0
+    };
+
+    let inserted = sqlx::query_scalar::<_, i64>(
+        "INSERT INTO koth_ticks (
+            contest_id,
+            challenge_id,
+            tick_number,
+            owner_team_id,
+            status,
+            points,
+            message,
+            elapsed_ms
+         )
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+         ON CONFLICT (contest_id, challenge_id, tick_number) DO NOTHING
+         RETURNING id",
+    )
+    .bind(hill.contest_id)
+    .bind(hill.challenge_id)
+    .bind(tick)
+    .bind(owner)
+    .bind(status.as_str())
+    .bind(points)
+    .bind(truncate_message(&message))
+    .bind(started.elapsed().as_millis().min(i32::MAX as u128) as i32)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    if inserted.is_some() && points > 0 {
+        publish_scoreboard_update(state, hill.contest_id, SCOREBOARD_EVENT_UPDATE).await;
+    }
+    Ok(inserted.is_some())
+}
+
+fn resolve_koth_owner(
+    state: &AppState,
+    hill: &KothHillRow,
+    participants: &[AdParticipantRow],
+    token: &str,
+) -> Option<Uuid> {
+    let token = token.trim();
+    participants
+        .iter()
+        .find(|team| {
+            koth_team_token(state, hill.contest_id, hill.koth_token_salt, team.team_id) == token
+        })
+        .map(|team| team.team_id)
+}
+
+/// Transport and protocol failures are recorded as `error` ticks, which
+/// award nobody.
+async fn run_koth_checker(
+    config: &WebhookVerifierConfig,
+    request: &KothCheckerRequest<'_>,
+) -> (KothTickStatus, Option<String>, String) {
+    match post_signed_json::<_, KothCheckerResponse>(config, request, "koth checker").await {
+        Ok((body, _)) => {
+            let status = KothTickStatus::parse(&body.status);
+            let owner_token = body
+                .owner_token
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty());
+            let message = body
+                .message
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| match status {
+                    KothTickStatus::Error => {
+                        format!("koth checker returned unknown status '{}'", body.status)
+                    }
+                    _ => String::new(),
+                });
+            (status, owner_token, message)
+        }
+        Err(err) => (KothTickStatus::Error, None, err.message),
+    }
+}
+
+pub(crate) async fn load_koth_ticks(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Option<Uuid>,
+    limit: i64,
+) -> AppResult<Vec<KothTickItem>> {
+    sqlx::query_as::<_, KothTickItem>(
+        "SELECT k.id,
+                k.challenge_id,
+                c.title AS challenge_title,
+                k.tick_number,
+                k.owner_team_id,
+                t.name AS owner_team_name,
+                k.status,
+                k.points,
+                k.message,
+                k.elapsed_ms,
+                k.ticked_at
+         FROM koth_ticks k
+         JOIN challenges c ON c.id = k.challenge_id
+         LEFT JOIN teams t ON t.id = k.owner_team_id
+         WHERE k.contest_id = $1
+           AND ($2::uuid IS NULL OR k.challenge_id = $2)
+         ORDER BY k.ticked_at DESC, k.id DESC
+         LIMIT $3",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)
+}
+
+/// Points earned by holding hills, one adjustment per owned tick.
+pub(crate) async fn load_koth_adjustments(
+    state: &AppState,
+    contest_id: Uuid,
+    cutoff: Option<DateTime<Utc>>,
+) -> AppResult<Vec<ScoreAdjustment>> {
+    sqlx::query_as::<_, ScoreAdjustment>(
+        "SELECT k.owner_team_id AS team_id,
+                t.name AS team_name,
+                k.points::bigint AS points,
+                k.ticked_at AS occurred_at,
+                'tick' AS source
+         FROM koth_ticks k
+         JOIN teams t ON t.id = k.owner_team_id
+         WHERE k.contest_id = $1
+           AND k.points > 0
+           AND ($2::timestamptz IS NULL OR k.ticked_at < $2)
+         ORDER BY k.ticked_at ASC, k.id ASC",
+    )
+    .bind(contest_id)
+    .bind(cutoff)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use uuid::Uuid;
+
+    use super::{derive_koth_token, koth_tick_number, KothTickStatus};
+
+    #[test]
+    fn ticks_count_from_contest_start() {
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(koth_tick_number(start, start, 60), 0);
+        assert_eq!(
+            koth_tick_number(start, start + Duration::seconds(59), 60),
+            0
+        );
+        assert_eq!(
+            koth_tick_number(start, start + Duration::seconds(60), 60),
+            1
+        );
+        assert_eq!(koth_tick_number(start, start - Duration::seconds(5), 60), 0);
+    }
+
+    #[test]
+    fn team_tokens_are_stable_and_distinct() {
+        let contest_id = Uuid::new_v4();
+        let salt = Uuid::new_v4();
+        let lhs = Uuid::new_v4();
+        let rhs = Uuid::new_v4();
+        assert_eq!(
+            derive_koth_token("secret", contest_id, salt, lhs),
+            derive_koth_token("secret", contest_id, salt, lhs)
+        );
+        assert_ne!(
+            derive_koth_token("secret", contest_id, salt, lhs),
+            derive_koth_token("secret", contest_id, salt, rhs)
+        );
+        assert!(derive_koth_token("secret", contest_id, salt, lhs).starts_with("koth{"));
+    }
+
+    #[test]
+    fn team_tokens_change_when_rotated() {
+        let contest_id = Uuid::from_u128(1);
+        let team_id = Uuid::from_u128(2);
+        let salt = Uuid::from_u128(3);
+        let token = derive_koth_token("secret", contest_id, salt, team_id);
+
+        assert_eq!(token.len(), "koth{}".len() + 32);
+        assert_ne!(
+            token,
+            derive_koth_token("secret", contest_id, Uuid::from_u128(4), team_id)
+        );
+        assert_ne!(
+            token,
+            derive_koth_token("other-secret", contest_id, salt, team_id)
+        );
+    }
+
+    #[test]
+    fn unknown_checker_status_is_an_error() {
+        assert_eq!(KothTickStatus::parse(" Up "), KothTickStatus::Up);
+        assert_eq!(KothTickStatus::parse("down"), KothTickStatus::Down);
+        assert_eq!(KothTickStatus::parse("mumble"), KothTickStatus::Error);
+    }
+}
@@ -1,13 +1,21 @@
 pub(crate) mod admin;
+pub(crate) mod attack_defense;
+pub(crate) mod challenge_unlocks;
 pub(crate) mod contest_access;
+pub(crate) mod contest_divisions;
 mod auth;
 mod contests;
+pub(crate) mod dynamic_flags;
 mod health;
 pub(crate) mod instances;
-mod scoreboard;
+pub(crate) mod koth;
+pub(crate) mod scoreboard;
+pub(crate) mod scoreboard_cache;
+pub(crate) mod scoring;
 mod site;
-mod submissions;
+pub(crate) mod submissions;
 mod teams;
+pub(crate) mod webhook_verifier;
 
 use std::sync::Arc;
 
@@ -20,8 +28,10 @@ pub fn router() -> Router<Arc<AppState>> {
         .nest("/api/v1", health::router())
         .nest("/api/v1", auth::router())
         .nest("/api/v1", admin::router())
+        .nest("/api/v1", attack_defense::router())
         .nest("/api/v1", contests::router())
         .nest("/api/v1", instances::router())
+        .nest("/api/v1", koth::router())
         .nest("/api/v1", scoreboard::router())
         .nest("/api/v1", site::router())
         .nest("/api/v1", submissions::router())
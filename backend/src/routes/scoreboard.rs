@@ -1,20 +1,23 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
+    iter::Peekable,
+    net::{IpAddr, SocketAddr},
     sync::Arc,
 };
 
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        Path, Query, State,
+        ConnectInfo, Path, Query, State,
     },
-    http::HeaderMap,
-    response::IntoResponse,
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
     routing::get,
     Json, Router,
 };
 use chrono::{DateTime, Utc};
 use futures_util::StreamExt;
+use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use tracing::warn;
@@ -22,12 +25,34 @@ use uuid::Uuid;
 
 use crate::{
     auth::{self, AuthenticatedUser},
+    csv_export::{csv_line, csv_text_field},
     error::{AppError, AppResult},
-    routes::contest_access::ensure_user_contest_workspace_access,
+    routes::{
+        challenge_unlocks::load_locked_challenge_ids,
+        contest_access::{
+            ensure_user_contest_workspace_access, is_privileged_role, is_public_scoreboard,
+            load_contest_gate, ContestGateRow,
+        },
+        contest_divisions::{list_contest_division_ids, load_division_team_ids},
+        scoreboard_cache::{invalidate_scoreboard_cache, load_cached_scoreboard},
+        scoring::{
+            apply_score_floor, challenge_value, load_contest_solves, ChallengeCompletionTracker,
+            ScoreAdjustment, SolveRecord,
+        },
+    },
     state::AppState,
 };
 
-#[derive(Debug, Clone, Serialize)]
+pub(crate) const SCOREBOARD_EVENT_UPDATE: &str = "scoreboard_update";
+pub(crate) const SCOREBOARD_EVENT_REVEAL: &str = "scoreboard_reveal";
+pub(crate) const SCOREBOARD_EVENT_ANNOUNCEMENT: &str = "announcement";
+/// Freeze view given to spectators of a public scoreboard.
+const SCOREBOARD_SPECTATOR_ROLE: &str = "spectator";
+const SPECTATOR_RATE_WINDOW_SECS: i64 = 60;
+/// How long the last published standings are kept for diffing.
+const SCOREBOARD_STANDINGS_TTL_SECS: u64 = 7 * 24 * 3600;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ScoreboardEntry {
     rank: usize,
     team_id: Uuid,
@@ -37,16 +62,70 @@ struct ScoreboardEntry {
     last_submit_at: Option<DateTime<Utc>>,
 }
 
+/// One message on the scoreboard socket. `entries` is the full standings,
+/// sent on connect and afterwards only to sockets that asked for it (never
+/// for announcements); `deltas` describe what changed since the previous
+/// message.
 #[derive(Debug, Serialize)]
-struct ScoreboardPushPayload {
-    event: &'static str,
+struct ScoreboardPushPayload<'a> {
+    event: &'a str,
     contest_id: Uuid,
-    entries: Vec<ScoreboardEntry>,
+    division_id: Option<Uuid>,
+    frozen: bool,
+    freeze_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entries: Option<Vec<ScoreboardEntry>>,
+    deltas: Vec<ScoreboardDelta>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ScoreboardDelta {
+    Solve {
+        team_id: Uuid,
+        team_name: String,
+        challenge_id: Uuid,
+        challenge_title: String,
+        points: i64,
+        submitted_at: DateTime<Utc>,
+    },
+    FirstBlood {
+        team_id: Uuid,
+        team_name: String,
+        challenge_id: Uuid,
+        challenge_title: String,
+        submitted_at: DateTime<Utc>,
+    },
+    RankChange {
+        team_id: Uuid,
+        team_name: String,
+        previous_rank: Option<usize>,
+        rank: usize,
+        score: i64,
+    },
+    Announcement {
+        announcement_id: Uuid,
+        title: String,
+        published_at: Option<DateTime<Utc>>,
+    },
+}
+
+/// A newly credited solve, reported by the judge. `points` is what the judge
+/// awarded; the published delta carries what the rankings credit instead.
+#[derive(Debug, Clone)]
+pub(crate) struct ScoreboardSolve {
+    pub team_id: Uuid,
+    pub challenge_id: Uuid,
+    pub challenge_title: String,
+    pub stage_key: Option<String>,
+    pub points: i64,
+    pub first_blood: bool,
+    pub submitted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ScoreboardTimelineSnapshot {
-    trigger_submission_id: i64,
+    trigger_submission_id: Option<i64>,
     timestamp: DateTime<Utc>,
     entries: Vec<ScoreboardEntry>,
 }
@@ -54,134 +133,293 @@ struct ScoreboardTimelineSnapshot {
 #[derive(Debug, Serialize)]
 struct ScoreboardTimelineResponse {
     contest_id: Uuid,
+    division_id: Option<Uuid>,
     generated_at: DateTime<Utc>,
+    frozen: bool,
+    freeze_at: Option<DateTime<Utc>>,
     snapshots: Vec<ScoreboardTimelineSnapshot>,
     latest_entries: Vec<ScoreboardEntry>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ScoreboardRankingChallenge {
     challenge_id: Uuid,
     challenge_title: String,
     challenge_slug: String,
     marker: String,
-    score_awarded: i32,
+    score_awarded: i64,
     submitted_at: DateTime<Utc>,
+    stages: Vec<ScoreboardRankingStage>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScoreboardRankingStage {
+    stage_key: String,
+    stage_title: String,
+    score_awarded: i64,
+    submitted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 struct ScoreboardRankingCategory {
     category: String,
     solved_count: i64,
     challenges: Vec<ScoreboardRankingChallenge>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct ScoreboardRankingEntry {
     rank: usize,
     subject_id: Uuid,
     subject_name: String,
     total_score: i64,
+    score_adjustment: i64,
     solved_count: i64,
     last_submit_at: Option<DateTime<Utc>>,
     categories: Vec<ScoreboardRankingCategory>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct ScoreboardCategoryChallengeItem {
     challenge_id: Uuid,
     challenge_title: String,
     challenge_slug: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct ScoreboardCategoryItem {
     category: String,
     challenges: Vec<ScoreboardCategoryChallengeItem>,
 }
 
+/// Catalog, team rankings and player rankings, as cached together.
+type ScoreboardRankings = (
+    Vec<ScoreboardCategoryItem>,
+    Vec<ScoreboardRankingEntry>,
+    Vec<ScoreboardRankingEntry>,
+);
+
 #[derive(Debug, Serialize)]
 struct ScoreboardRankingsResponse {
     contest_id: Uuid,
+    participation_mode: String,
+    division_id: Option<Uuid>,
     generated_at: DateTime<Utc>,
+    frozen: bool,
+    freeze_at: Option<DateTime<Utc>>,
     categories: Vec<ScoreboardCategoryItem>,
     team_rankings: Vec<ScoreboardRankingEntry>,
     player_rankings: Vec<ScoreboardRankingEntry>,
 }
 
-#[derive(Debug, FromRow)]
-struct ScoreboardRow {
-    team_id: Uuid,
-    team_name: String,
+/// CTFtime scoreboard feed; times are unix seconds.
+#[derive(Debug, Serialize)]
+struct CtftimeScoreboard {
+    tasks: Vec<String>,
+    standings: Vec<CtftimeStanding>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CtftimeStanding {
+    pos: usize,
+    team: String,
     score: i64,
-    solved_count: i64,
-    last_submit_at: Option<DateTime<Utc>>,
+    task_stats: BTreeMap<String, CtftimeTaskStat>,
+    last_accept: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct CtftimeTaskStat {
+    points: i64,
+    time: i64,
 }
 
 #[derive(Debug, FromRow)]
-struct ScoreboardTimelineEventRow {
-    submission_id: i64,
+struct ScoreboardParticipantRow {
     team_id: Uuid,
     team_name: String,
-    score_awarded: i32,
-    submitted_at: DateTime<Utc>,
 }
 
 #[derive(Debug, FromRow)]
-struct RankingSolveEventRow {
-    _submission_id: i64,
-    team_id: Uuid,
-    team_name: String,
-    user_id: Uuid,
-    username: String,
+struct ContestChallengeCatalogRow {
     challenge_id: Uuid,
     challenge_title: String,
     challenge_slug: String,
     challenge_category: String,
-    score_awarded: i32,
-    submitted_at: DateTime<Utc>,
 }
 
 #[derive(Debug, FromRow)]
-struct ContestChallengeCatalogRow {
+struct ScoreboardChallengeRow {
     challenge_id: Uuid,
     challenge_title: String,
-    challenge_slug: String,
     challenge_category: String,
 }
 
+#[derive(Debug, Serialize)]
+struct ScoreboardChallengeItem {
+    challenge_id: Uuid,
+    challenge_title: String,
+    category: String,
+    solve_count: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct ScoreboardChallengeDetailRow {
+    challenge_title: String,
+    challenge_category: String,
+    released_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScoreboardChallengeSolve {
+    position: usize,
+    team_id: Uuid,
+    team_name: String,
+    solved_at: DateTime<Utc>,
+    marker: String,
+    points: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct ScoreboardChallengeSolvesResponse {
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    challenge_title: String,
+    division_id: Option<Uuid>,
+    frozen: bool,
+    freeze_at: Option<DateTime<Utc>>,
+    solves: Vec<ScoreboardChallengeSolve>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScoreboardChallengeStatsResponse {
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    challenge_title: String,
+    category: String,
+    division_id: Option<Uuid>,
+    frozen: bool,
+    freeze_at: Option<DateTime<Utc>>,
+    released_at: DateTime<Utc>,
+    solve_count: i64,
+    wrong_attempts: i64,
+    first_solved_at: Option<DateTime<Utc>>,
+    average_solve_seconds: Option<i64>,
+}
+
+#[derive(Debug, FromRow)]
+struct ScoreboardFreezeRow {
+    freeze_at: Option<DateTime<Utc>>,
+    scoreboard_revealed_at: Option<DateTime<Utc>>,
+}
+
+/// Which slice of submissions a viewer may see. `cutoff` is set while the
+/// contest is frozen for this viewer; only submissions before it count.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ScoreboardView {
+    pub freeze_at: Option<DateTime<Utc>>,
+    pub cutoff: Option<DateTime<Utc>>,
+}
+
+impl ScoreboardView {
+    pub(crate) fn frozen(&self) -> bool {
+        self.cutoff.is_some()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct ScoreboardWsAuthQuery {
     access_token: Option<String>,
     token: Option<String>,
+    division_id: Option<Uuid>,
+    entries: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScoreboardDivisionQuery {
+    division_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScoreboardExportQuery {
+    format: Option<String>,
+    division_id: Option<Uuid>,
 }
 
 #[derive(Debug, Deserialize)]
 struct ScoreboardTimelineQuery {
     max_snapshots: Option<i64>,
     top_n: Option<i64>,
+    division_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone)]
 struct RankingSubjectState {
     subject_name: String,
     total_score: i64,
+    score_adjustment: i64,
+    /// Deductions within `score_adjustment`, for the score floor.
+    deducted: i64,
     solved_count: i64,
     last_submit_at: Option<DateTime<Utc>>,
     categories: HashMap<String, Vec<ScoreboardRankingChallenge>>,
 }
 
+impl RankingSubjectState {
+    fn new(subject_name: String) -> Self {
+        Self {
+            subject_name,
+            total_score: 0,
+            score_adjustment: 0,
+            deducted: 0,
+            solved_count: 0,
+            last_submit_at: None,
+            categories: HashMap::new(),
+        }
+    }
+
+    fn adjust(&mut self, points: i64) {
+        self.total_score += points;
+        self.score_adjustment += points;
+        self.deducted -= points.min(0);
+    }
+}
+
 #[derive(Debug, Clone)]
-struct TimelineTeamState {
+struct TeamScoreState {
     team_name: String,
     score: i64,
+    /// Deductions within `score`, for the score floor.
+    deducted: i64,
     solved_count: i64,
     last_submit_at: Option<DateTime<Utc>>,
 }
 
+impl TeamScoreState {
+    fn new(team_name: String) -> Self {
+        Self {
+            team_name,
+            score: 0,
+            deducted: 0,
+            solved_count: 0,
+            last_submit_at: None,
+        }
+    }
+
+    fn adjust(&mut self, points: i64) {
+        self.score += points;
+        self.deducted -= points.min(0);
+    }
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/contests/{contest_id}/scoreboard", get(get_scoreboard))
+        .route(
+            "/contests/{contest_id}/scoreboard/export",
+            get(export_scoreboard),
+        )
         .route(
             "/contests/{contest_id}/scoreboard/rankings",
             get(get_scoreboard_rankings),
@@ -190,32 +428,159 @@ pub fn router() -> Router<Arc<AppState>> {
             "/contests/{contest_id}/scoreboard/timeline",
             get(get_scoreboard_timeline),
         )
+        .route(
+            "/contests/{contest_id}/scoreboard/challenges",
+            get(get_scoreboard_challenges),
+        )
+        .route(
+            "/contests/{contest_id}/scoreboard/challenges/{challenge_id}/solves",
+            get(get_scoreboard_challenge_solves),
+        )
+        .route(
+            "/contests/{contest_id}/scoreboard/challenges/{challenge_id}/stats",
+            get(get_scoreboard_challenge_stats),
+        )
         .route("/contests/{contest_id}/scoreboard/ws", get(scoreboard_ws))
 }
 
 async fn get_scoreboard(
     State(state): State<Arc<AppState>>,
     Path(contest_id): Path<Uuid>,
-    current_user: AuthenticatedUser,
+    Query(query): Query<ScoreboardDivisionQuery>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    current_user: Option<AuthenticatedUser>,
 ) -> AppResult<Json<Vec<ScoreboardEntry>>> {
-    ensure_scoreboard_access(state.as_ref(), contest_id, &current_user).await?;
-    let entries = load_scoreboard_entries(state.as_ref(), contest_id).await?;
+    let viewer_role = ensure_scoreboard_access(
+        state.as_ref(),
+        contest_id,
+        current_user.as_ref(),
+        peer,
+        &headers,
+    )
+    .await?;
+    let view = resolve_scoreboard_view(state.as_ref(), contest_id, &viewer_role).await?;
+    let entries =
+        cached_scoreboard_entries(state.as_ref(), contest_id, view.cutoff, query.division_id)
+            .await?;
     Ok(Json(entries))
 }
 
+/// Final standings as a CTFtime feed (`format=ctftime`, default) or CSV,
+/// built from the team rankings and subject to the viewer's freeze state.
+async fn export_scoreboard(
+    State(state): State<Arc<AppState>>,
+    Path(contest_id): Path<Uuid>,
+    Query(query): Query<ScoreboardExportQuery>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    current_user: Option<AuthenticatedUser>,
+) -> AppResult<Response> {
+    let format = query
+        .format
+        .as_deref()
+        .map(|value| value.trim().to_lowercase())
+        .unwrap_or_else(|| "ctftime".to_string());
+    if format != "ctftime" && format != "csv" {
+        return Err(AppError::BadRequest(
+            "format must be one of: ctftime, csv".to_string(),
+        ));
+    }
+
+    let viewer_role = ensure_scoreboard_access(
+        state.as_ref(),
+        contest_id,
+        current_user.as_ref(),
+        peer,
+        &headers,
+    )
+    .await?;
+    let view = resolve_scoreboard_view(state.as_ref(), contest_id, &viewer_role).await?;
+
+    let (categories, team_rankings, _) =
+        cached_scoreboard_rankings(state.as_ref(), contest_id, view.cutoff, query.division_id)
+            .await?;
+    let scoreboard = build_ctftime_scoreboard(&categories, &team_rankings);
+
+    if format == "ctftime" {
+        return Ok(Json(scoreboard).into_response());
+    }
+
+    let mut body = csv_line(
+        &["pos", "team", "score", "solved_count", "last_accept"]
+            .into_iter()
+            .map(str::to_string)
+            .chain(scoreboard.tasks.iter().map(|task| csv_text_field(task)))
+            .collect::<Vec<_>>(),
+    );
+    for (standing, entry) in scoreboard.standings.iter().zip(&team_rankings) {
+        let mut fields = vec![
+            standing.pos.to_string(),
+            csv_text_field(&standing.team),
+            standing.score.to_string(),
+            entry.solved_count.to_string(),
+            entry
+                .last_submit_at
+                .map(|value| value.to_rfc3339())
+                .unwrap_or_default(),
+        ];
+        fields.extend(scoreboard.tasks.iter().map(|task| {
+            standing
+                .task_stats
+                .get(task)
+                .map(|stat| stat.points.to_string())
+                .unwrap_or_default()
+        }));
+        body.push_str(&csv_line(&fields));
+    }
+
+    let disposition = format!(
+        "attachment; filename=\"scoreboard-{}-{}.csv\"",
+        contest_id,
+        Utc::now().format("%Y%m%d%H%M%S")
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::CACHE_CONTROL, "no-cache".to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
+
 async fn get_scoreboard_rankings(
     State(state): State<Arc<AppState>>,
     Path(contest_id): Path<Uuid>,
-    current_user: AuthenticatedUser,
+    Query(query): Query<ScoreboardDivisionQuery>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    current_user: Option<AuthenticatedUser>,
 ) -> AppResult<Json<ScoreboardRankingsResponse>> {
-    ensure_scoreboard_access(state.as_ref(), contest_id, &current_user).await?;
+    let viewer_role = ensure_scoreboard_access(
+        state.as_ref(),
+        contest_id,
+        current_user.as_ref(),
+        peer,
+        &headers,
+    )
+    .await?;
+    let view = resolve_scoreboard_view(state.as_ref(), contest_id, &viewer_role).await?;
+
+    let contest = load_contest_gate(state.as_ref(), contest_id).await?;
 
     let (categories, team_rankings, player_rankings) =
-        load_scoreboard_rankings(state.as_ref(), contest_id).await?;
+        cached_scoreboard_rankings(state.as_ref(), contest_id, view.cutoff, query.division_id)
+            .await?;
 
     Ok(Json(ScoreboardRankingsResponse {
         contest_id,
+        participation_mode: contest.participation_mode,
+        division_id: query.division_id,
         generated_at: Utc::now(),
+        frozen: view.frozen(),
+        freeze_at: view.freeze_at,
         categories,
         team_rankings,
         player_rankings,
@@ -226,287 +591,1343 @@ async fn get_scoreboard_timeline(
     State(state): State<Arc<AppState>>,
     Path(contest_id): Path<Uuid>,
     Query(query): Query<ScoreboardTimelineQuery>,
-    current_user: AuthenticatedUser,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    current_user: Option<AuthenticatedUser>,
 ) -> AppResult<Json<ScoreboardTimelineResponse>> {
-    ensure_scoreboard_access(state.as_ref(), contest_id, &current_user).await?;
+    let viewer_role = ensure_scoreboard_access(
+        state.as_ref(),
+        contest_id,
+        current_user.as_ref(),
+        peer,
+        &headers,
+    )
+    .await?;
+    let view = resolve_scoreboard_view(state.as_ref(), contest_id, &viewer_role).await?;
 
     let max_snapshots = query.max_snapshots.unwrap_or(800).clamp(1, 5000) as usize;
     let top_n = query.top_n.unwrap_or(12).clamp(1, 200) as usize;
 
-    let (snapshots, latest_entries) =
-        load_scoreboard_timeline(state.as_ref(), contest_id, max_snapshots, top_n).await?;
+    let (snapshots, latest_entries) = load_cached_scoreboard(
+        state.as_ref(),
+        contest_id,
+        &format!("timeline:{}:{}", max_snapshots, top_n),
+        view.cutoff,
+        query.division_id,
+        || async {
+            let division =
+                load_division_team_ids(state.as_ref(), contest_id, query.division_id).await?;
+            load_scoreboard_timeline(
+                state.as_ref(),
+                contest_id,
+                view.cutoff,
+                division.as_ref(),
+                max_snapshots,
+                top_n,
+            )
+            .await
+        },
+    )
+    .await?;
 
     Ok(Json(ScoreboardTimelineResponse {
         contest_id,
+        division_id: query.division_id,
         generated_at: Utc::now(),
+        frozen: view.frozen(),
+        freeze_at: view.freeze_at,
         snapshots,
         latest_entries,
     }))
 }
 
+/// Released challenges with how many teams solved them, subject to the
+/// viewer's freeze state. Spectators only see it with `public_challenge_stats`;
+/// participants do not see challenges their team has not unlocked yet.
+async fn get_scoreboard_challenges(
+    State(state): State<Arc<AppState>>,
+    Path(contest_id): Path<Uuid>,
+    Query(query): Query<ScoreboardDivisionQuery>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    current_user: Option<AuthenticatedUser>,
+) -> AppResult<Json<Vec<ScoreboardChallengeItem>>> {
+    let (viewer_role, team_id) = ensure_challenge_stats_access(
+        state.as_ref(),
+        contest_id,
+        current_user.as_ref(),
+        peer,
+        &headers,
+    )
+    .await?;
+    let view = resolve_scoreboard_view(state.as_ref(), contest_id, &viewer_role).await?;
+
+    let rows = sqlx::query_as::<_, ScoreboardChallengeRow>(
+        "SELECT c.id AS challenge_id,
+                c.title AS challenge_title,
+                c.category AS challenge_category
+         FROM contest_challenges cc
+         JOIN challenges c ON c.id = cc.challenge_id
+         JOIN contests ct ON ct.id = cc.contest_id
+         WHERE cc.contest_id = $1
+           AND c.is_visible = TRUE
+           AND ct.status IN ('running', 'ended')
+           AND (cc.release_at IS NULL OR cc.release_at <= NOW())
+         ORDER BY c.category ASC, cc.sort_order ASC, c.title ASC",
+    )
+    .bind(contest_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+    let locked = match team_id {
+        Some(team_id) => load_locked_challenge_ids(state.as_ref(), contest_id, team_id).await?,
+        None => HashSet::new(),
+    };
+
+    let (_, team_rankings, _) =
+        cached_scoreboard_rankings(state.as_ref(), contest_id, view.cutoff, query.division_id)
+            .await?;
+    let mut solve_counts: HashMap<Uuid, i64> = HashMap::new();
+    for challenge in team_rankings
+        .iter()
+        .flat_map(|entry| &entry.categories)
+        .flat_map(|category| &category.challenges)
+        .filter(|challenge| challenge.marker != "partial")
+    {
+        *solve_counts.entry(challenge.challenge_id).or_default() += 1;
+    }
+
+    Ok(Json(
+        rows.into_iter()
+            .filter(|row| !locked.contains(&row.challenge_id))
+            .map(|row| ScoreboardChallengeItem {
+                solve_count: solve_counts.get(&row.challenge_id).copied().unwrap_or(0),
+                challenge_id: row.challenge_id,
+                challenge_title: row.challenge_title,
+                category: row.challenge_category,
+            })
+            .collect(),
+    ))
+}
+
+/// Teams that fully solved a challenge, in solve order.
+async fn get_scoreboard_challenge_solves(
+    State(state): State<Arc<AppState>>,
+    Path((contest_id, challenge_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<ScoreboardDivisionQuery>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    current_user: Option<AuthenticatedUser>,
+) -> AppResult<Json<ScoreboardChallengeSolvesResponse>> {
+    let (viewer_role, team_id) = ensure_challenge_stats_access(
+        state.as_ref(),
+        contest_id,
+        current_user.as_ref(),
+        peer,
+        &headers,
+    )
+    .await?;
+    let view = resolve_scoreboard_view(state.as_ref(), contest_id, &viewer_role).await?;
+    let challenge = load_scoreboard_challenge(
+        state.as_ref(),
+        contest_id,
+        challenge_id,
+        &viewer_role,
+        team_id,
+    )
+    .await?;
+
+    let (_, team_rankings, _) =
+        cached_scoreboard_rankings(state.as_ref(), contest_id, view.cutoff, query.division_id)
+            .await?;
+
+    Ok(Json(ScoreboardChallengeSolvesResponse {
+        contest_id,
+        challenge_id,
+        challenge_title: challenge.challenge_title,
+        division_id: query.division_id,
+        frozen: view.frozen(),
+        freeze_at: view.freeze_at,
+        solves: challenge_solves_from_rankings(&team_rankings, challenge_id),
+    }))
+}
+
+async fn get_scoreboard_challenge_stats(
+    State(state): State<Arc<AppState>>,
+    Path((contest_id, challenge_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<ScoreboardDivisionQuery>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    current_user: Option<AuthenticatedUser>,
+) -> AppResult<Json<ScoreboardChallengeStatsResponse>> {
+    let (viewer_role, team_id) = ensure_challenge_stats_access(
+        state.as_ref(),
+        contest_id,
+        current_user.as_ref(),
+        peer,
+        &headers,
+    )
+    .await?;
+    let view = resolve_scoreboard_view(state.as_ref(), contest_id, &viewer_role).await?;
+    let challenge = load_scoreboard_challenge(
+        state.as_ref(),
+        contest_id,
+        challenge_id,
+        &viewer_role,
+        team_id,
+    )
+    .await?;
+    let division = load_division_team_ids(state.as_ref(), contest_id, query.division_id).await?;
+
+    let (_, team_rankings, _) =
+        cached_scoreboard_rankings(state.as_ref(), contest_id, view.cutoff, query.division_id)
+            .await?;
+    let solves = challenge_solves_from_rankings(&team_rankings, challenge_id);
+
+    let division_team_ids: Option<Vec<Uuid>> =
+        division.map(|team_ids| team_ids.into_iter().collect());
+    let wrong_attempts = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*)
+         FROM submissions
+         WHERE contest_id = $1
+           AND challenge_id = $2
+           AND verdict = 'wrong'
+           AND ($3::timestamptz IS NULL OR submitted_at < $3)
+           AND ($4::uuid[] IS NULL OR team_id = ANY($4))",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(view.cutoff)
+    .bind(division_team_ids)
+    .fetch_one(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    Ok(Json(ScoreboardChallengeStatsResponse {
+        contest_id,
+        challenge_id,
+        challenge_title: challenge.challenge_title,
+        category: challenge.challenge_category,
+        division_id: query.division_id,
+        frozen: view.frozen(),
+        freeze_at: view.freeze_at,
+        released_at: challenge.released_at,
+        solve_count: solves.len() as i64,
+        wrong_attempts,
+        first_solved_at: solves.first().map(|solve| solve.solved_at),
+        average_solve_seconds: average_solve_seconds(&solves, challenge.released_at),
+    }))
+}
+
+/// Challenge statistics follow scoreboard access; spectators additionally
+/// need `public_challenge_stats`. Also returns the participant's team.
+async fn ensure_challenge_stats_access(
+    state: &AppState,
+    contest_id: Uuid,
+    current_user: Option<&AuthenticatedUser>,
+    peer: SocketAddr,
+    headers: &HeaderMap,
+) -> AppResult<(String, Option<Uuid>)> {
+    let (viewer_role, team_id) =
+        ensure_scoreboard_viewer(state, contest_id, current_user, peer, headers).await?;
+    if viewer_role == SCOREBOARD_SPECTATOR_ROLE
+        && !load_contest_gate(state, contest_id)
+            .await?
+            .public_challenge_stats
+    {
+        return Err(AppError::Forbidden);
+    }
+    Ok((viewer_role, team_id))
+}
+
+/// Admins and judges may look up any bound challenge; everyone else only
+/// visible, released ones once the contest is running, and participants only
+/// once their team has unlocked it.
+async fn load_scoreboard_challenge(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    viewer_role: &str,
+    team_id: Option<Uuid>,
+) -> AppResult<ScoreboardChallengeDetailRow> {
+    let not_available =
+        || AppError::BadRequest("challenge is not available in this contest".to_string());
+
+    let challenge = sqlx::query_as::<_, ScoreboardChallengeDetailRow>(
+        "SELECT c.title AS challenge_title,
+                c.category AS challenge_category,
+                COALESCE(cc.release_at, ct.start_at) AS released_at
+         FROM contest_challenges cc
+         JOIN challenges c ON c.id = cc.challenge_id
+         JOIN contests ct ON ct.id = cc.contest_id
+         WHERE cc.contest_id = $1
+           AND cc.challenge_id = $2
+           AND (
+               $3
+               OR (
+                   c.is_visible = TRUE
+                   AND ct.status IN ('running', 'ended')
+                   AND (cc.release_at IS NULL OR cc.release_at <= NOW())
+               )
+           )
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(is_privileged_role(viewer_role))
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or_else(not_available)?;
+
+    if let Some(team_id) = team_id {
+        let locked = load_locked_challenge_ids(state, contest_id, team_id).await?;
+        if locked.contains(&challenge_id) {
+            return Err(not_available());
+        }
+    }
+
+    Ok(challenge)
+}
+
+/// Full solves only: stage progress short of completion (`partial`) is left
+/// out. Positions follow solve time among the listed teams; blood markers
+/// stay contest-wide, so in a division the first listed solver may carry
+/// `second_blood`.
+fn challenge_solves_from_rankings(
+    team_rankings: &[ScoreboardRankingEntry],
+    challenge_id: Uuid,
+) -> Vec<ScoreboardChallengeSolve> {
+    let mut solves: Vec<ScoreboardChallengeSolve> = team_rankings
+        .iter()
+        .filter_map(|entry| {
+            entry
+                .categories
+                .iter()
+                .flat_map(|category| &category.challenges)
+                .find(|challenge| {
+                    challenge.challenge_id == challenge_id && challenge.marker != "partial"
+                })
+                .map(|challenge| ScoreboardChallengeSolve {
+                    position: 0,
+                    team_id: entry.subject_id,
+                    team_name: entry.subject_name.clone(),
+                    solved_at: challenge.submitted_at,
+                    marker: challenge.marker.clone(),
+                    points: challenge.score_awarded,
+                })
+        })
+        .collect();
+
+    solves.sort_by(|lhs, rhs| {
+        lhs.solved_at
+            .cmp(&rhs.solved_at)
+            .then_with(|| lhs.team_name.cmp(&rhs.team_name))
+    });
+    for (idx, solve) in solves.iter_mut().enumerate() {
+        solve.position = idx + 1;
+    }
+    solves
+}
+
+/// Mean seconds from release to solve; solves recorded before the release
+/// time (e.g. a release moved later) count as zero.
+fn average_solve_seconds(
+    solves: &[ScoreboardChallengeSolve],
+    released_at: DateTime<Utc>,
+) -> Option<i64> {
+    if solves.is_empty() {
+        return None;
+    }
+
+    let total: i64 = solves
+        .iter()
+        .map(|solve| (solve.solved_at - released_at).num_seconds().max(0))
+        .sum();
+    Some(total / solves.len() as i64)
+}
+
 async fn scoreboard_ws(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
     Path(contest_id): Path<Uuid>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
     Query(query): Query<ScoreboardWsAuthQuery>,
 ) -> AppResult<impl IntoResponse> {
+    let division_id = query.division_id;
+    let with_entries = query.entries.unwrap_or(false);
     let current_user = resolve_ws_user(state.as_ref(), &headers, query)?;
-    ensure_scoreboard_access(state.as_ref(), contest_id, &current_user).await?;
+    let viewer_role = ensure_scoreboard_access(
+        state.as_ref(),
+        contest_id,
+        current_user.as_ref(),
+        peer,
+        &headers,
+    )
+    .await?;
+    let user_id = current_user.map(|user| user.user_id);
+    load_division_team_ids(state.as_ref(), contest_id, division_id).await?;
 
-    Ok(ws.on_upgrade(move |socket| scoreboard_ws_loop(socket, state, contest_id, current_user)))
+    let channel = scoreboard_channel(
+        contest_id,
+        division_id,
+        is_privileged_role(&viewer_role),
+        with_entries,
+    );
+    Ok(ws.on_upgrade(move |socket| {
+        scoreboard_ws_loop(
+            socket,
+            state,
+            contest_id,
+            division_id,
+            viewer_role,
+            user_id,
+            channel,
+        )
+    }))
 }
 
 fn resolve_ws_user(
     state: &AppState,
     headers: &HeaderMap,
     query: ScoreboardWsAuthQuery,
-) -> AppResult<AuthenticatedUser> {
+) -> AppResult<Option<AuthenticatedUser>> {
     let token_from_header = auth::extract_bearer_token(headers).ok().map(str::to_string);
-    let token = token_from_header
-        .or(query.access_token)
-        .or(query.token)
-        .ok_or(AppError::Unauthorized)?;
+    let Some(token) = token_from_header.or(query.access_token).or(query.token) else {
+        return Ok(None);
+    };
 
-    auth::decode_access_token(&token, &state.config.jwt_secret)
+    auth::decode_access_token(&token, &state.config.jwt_secret).map(Some)
+}
+
+/// Sends the current board, then forwards what is published on `channel`.
+/// Payloads are built once per change for each board, so the socket only
+/// relays them.
+async fn scoreboard_ws_loop(
+    mut socket: WebSocket,
+    state: Arc<AppState>,
+    contest_id: Uuid,
+    division_id: Option<Uuid>,
+    viewer_role: String,
+    user_id: Option<Uuid>,
+    channel: String,
+) {
+    if send_scoreboard_snapshot(
+        &mut socket,
+        state.as_ref(),
+        contest_id,
+        division_id,
+        &viewer_role,
+    )
+    .await
+    .is_err()
+    {
+        return;
+    }
+
+    let mut pubsub = match state.redis_client.get_async_pubsub().await {
+        Ok(pubsub) => pubsub,
+        Err(err) => {
+            warn!(
+                contest_id = %contest_id,
+                user_id = ?user_id,
+                error = %err,
+                "failed to create redis pubsub connection"
+            );
+            let _ = socket
+                .send(Message::Close(Some(axum::extract::ws::CloseFrame {
+                    code: axum::extract::ws::close_code::ERROR,
+                    reason: "pubsub init failed".into(),
+                })))
+                .await;
+            return;
+        }
+    };
+
+    if let Err(err) = pubsub.subscribe(&channel).await {
+        warn!(
+            contest_id = %contest_id,
+            user_id = ?user_id,
+            error = %err,
+            "failed to subscribe scoreboard channel"
+        );
+        let _ = socket
+            .send(Message::Close(Some(axum::extract::ws::CloseFrame {
+                code: axum::extract::ws::close_code::ERROR,
+                reason: "pubsub subscribe failed".into(),
+            })))
+            .await;
+        return;
+    }
+
+    let mut pubsub_stream = pubsub.on_message();
+
+    loop {
+        tokio::select! {
+            inbound = socket.recv() => {
+                match inbound {
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(Message::Ping(payload))) => {
+                        if socket.send(Message::Pong(payload)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(err)) => {
+                        warn!(
+                            contest_id = %contest_id,
+                            user_id = ?user_id,
+                            error = %err,
+                            "websocket receive error"
+                        );
+                        break;
+                    }
+                }
+            }
+            update = pubsub_stream.next() => {
+                let Some(update) = update else {
+                    break;
+                };
+
+                let Ok(payload) = update.get_payload::<String>() else {
+                    continue;
+                };
+                if socket.send(Message::Text(payload.into())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// The full board a socket starts from.
+async fn send_scoreboard_snapshot(
+    socket: &mut WebSocket,
+    state: &AppState,
+    contest_id: Uuid,
+    division_id: Option<Uuid>,
+    viewer_role: &str,
+) -> Result<(), ()> {
+    let view = resolve_scoreboard_view(state, contest_id, viewer_role)
+        .await
+        .map_err(|err| {
+            warn!(contest_id = %contest_id, error = %err, "failed to resolve scoreboard freeze state");
+        })?;
+
+    let entries = cached_scoreboard_entries(state, contest_id, view.cutoff, division_id)
+        .await
+        .map_err(|err| {
+            warn!(contest_id = %contest_id, error = %err, "failed to build scoreboard snapshot");
+        })?;
+
+    let payload = ScoreboardPushPayload {
+        event: SCOREBOARD_EVENT_UPDATE,
+        contest_id,
+        division_id,
+        frozen: view.frozen(),
+        freeze_at: view.freeze_at,
+        entries: Some(entries),
+        deltas: Vec::new(),
+    };
+    let encoded = serde_json::to_string(&payload).map_err(|err| {
+        warn!(contest_id = %contest_id, error = %err, "failed to serialize scoreboard payload");
+    })?;
+
+    socket.send(Message::Text(encoded.into())).await.map_err(|err| {
+        warn!(contest_id = %contest_id, error = %err, "failed to send websocket scoreboard payload");
+    })
+}
+
+/// Returns the role whose freeze view the caller gets. Participants, admins
+/// and judges keep their usual access; anyone else, signed in or not, reads
+/// a public scoreboard as a spectator and is rate limited per client IP.
+async fn ensure_scoreboard_access(
+    state: &AppState,
+    contest_id: Uuid,
+    current_user: Option<&AuthenticatedUser>,
+    peer: SocketAddr,
+    headers: &HeaderMap,
+) -> AppResult<String> {
+    ensure_scoreboard_viewer(state, contest_id, current_user, peer, headers)
+        .await
+        .map(|(viewer_role, _)| viewer_role)
+}
+
+/// [`ensure_scoreboard_access`] plus the participant's team, which is `None`
+/// for admins, judges and spectators.
+async fn ensure_scoreboard_viewer(
+    state: &AppState,
+    contest_id: Uuid,
+    current_user: Option<&AuthenticatedUser>,
+    peer: SocketAddr,
+    headers: &HeaderMap,
+) -> AppResult<(String, Option<Uuid>)> {
+    let contest = load_contest_gate(state, contest_id).await?;
+
+    if let Some(current_user) = current_user {
+        match ensure_user_contest_workspace_access(state, contest_id, current_user).await {
+            Ok(team_id) => return Ok((current_user.role.clone(), team_id)),
+            Err(err) if !is_public_scoreboard(&contest) => return Err(err),
+            Err(_) => {}
+        }
+    } else if !is_public_scoreboard(&contest) {
+        return Err(AppError::Unauthorized);
+    }
+
+    let client_ip = spectator_client_ip(peer, headers, &state.trusted_proxies);
+    enforce_spectator_rate_limit(state, &contest, client_ip).await?;
+    Ok((SCOREBOARD_SPECTATOR_ROLE.to_string(), None))
+}
+
+/// Fixed one-minute window per contest and client IP.
+async fn enforce_spectator_rate_limit(
+    state: &AppState,
+    contest: &ContestGateRow,
+    client_ip: IpAddr,
+) -> AppResult<()> {
+    let key = format!("scoreboard:spectator:{}:{}", contest.id, client_ip);
+    let mut redis_conn = state.redis.clone();
+
+    let count: i64 = redis_conn.incr(&key, 1).await.map_err(AppError::internal)?;
+    if count == 1 {
+        let _: bool = redis_conn
+            .expire(&key, SPECTATOR_RATE_WINDOW_SECS)
+            .await
+            .map_err(AppError::internal)?;
+    }
+
+    if count > i64::from(contest.public_scoreboard_rate_limit) {
+        return Err(AppError::TooManyRequests(format!(
+            "spectator rate limit exceeded: max {} requests per {} seconds",
+            contest.public_scoreboard_rate_limit, SPECTATOR_RATE_WINDOW_SECS
+        )));
+    }
+
+    Ok(())
+}
+
+/// The connecting peer is the client unless it is one of `TRUSTED_PROXIES`.
+/// Behind trusted proxies, `X-Forwarded-For` is walked from the right and the
+/// first hop that is not itself a trusted proxy is the client; `X-Real-IP` is
+/// used when the proxy sends no forwarding chain.
+fn spectator_client_ip(peer: SocketAddr, headers: &HeaderMap, trusted: &[IpAddr]) -> IpAddr {
+    let peer_ip = peer.ip().to_canonical();
+    if !trusted.contains(&peer_ip) {
+        return peer_ip;
+    }
+
+    let forwarded_for = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+    if !forwarded_for.is_empty() {
+        let mut client_ip = peer_ip;
+        for hop in forwarded_for.into_iter().rev() {
+            match hop.parse::<IpAddr>() {
+                Ok(ip) => {
+                    client_ip = ip.to_canonical();
+                    if !trusted.contains(&client_ip) {
+                        break;
+                    }
+                }
+                // An unparsable hop was written by something we do not
+                // trust; stop at the last address a trusted proxy vouched for.
+                Err(_) => break,
+            }
+        }
+        return client_ip;
+    }
+
+    headers
+        .get("x-real-ip")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<IpAddr>().ok())
+        .map(|ip| ip.to_canonical())
+        .unwrap_or(peer_ip)
+}
+
+/// Resolves the freeze state for a viewer. Admins and judges always see live
+/// standings; everyone else sees standings as of `freeze_at` until an admin
+/// reveals the scoreboard.
+pub(crate) async fn resolve_scoreboard_view(
+    state: &AppState,
+    contest_id: Uuid,
+    viewer_role: &str,
+) -> AppResult<ScoreboardView> {
+    let row = sqlx::query_as::<_, ScoreboardFreezeRow>(
+        "SELECT freeze_at, scoreboard_revealed_at
+         FROM contests
+         WHERE id = $1
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest("contest not found".to_string()))?;
+
+    let cutoff = match row.freeze_at {
+        Some(freeze_at)
+            if row.scoreboard_revealed_at.is_none()
+                && freeze_at <= Utc::now()
+                && !is_privileged_role(viewer_role) =>
+        {
+            Some(freeze_at)
+        }
+        _ => None,
+    };
+
+    Ok(ScoreboardView {
+        freeze_at: row.freeze_at,
+        cutoff,
+    })
+}
+
+/// A team's live total, read from the cached contest-wide standings so the
+/// judge does not rescore the contest on every submission. Teams missing
+/// from the standings have no points yet.
+pub(crate) async fn load_team_total(
+    state: &AppState,
+    contest_id: Uuid,
+    team_id: Uuid,
+) -> AppResult<i64> {
+    let entries = cached_scoreboard_entries(state, contest_id, None, None).await?;
+    Ok(entries
+        .iter()
+        .find(|entry| entry.team_id == team_id)
+        .map(|entry| entry.score)
+        .unwrap_or(0))
+}
+
+/// Publishes a standings refresh with rank-change deltas only (reveals,
+/// rejudges, adjustments, penalties).
+pub(crate) async fn publish_scoreboard_update(state: &AppState, contest_id: Uuid, event: &str) {
+    publish_scoreboard_change(state, contest_id, event, None).await;
+}
+
+/// Publishes a newly credited solve together with the resulting standings.
+pub(crate) async fn publish_scoreboard_solve(
+    state: &AppState,
+    contest_id: Uuid,
+    solve: ScoreboardSolve,
+) {
+    publish_scoreboard_change(state, contest_id, SCOREBOARD_EVENT_UPDATE, Some(&solve)).await;
+}
+
+/// Announcements don't move the standings, so the payload carries no entries.
+pub(crate) async fn publish_scoreboard_announcement(
+    state: &AppState,
+    contest_id: Uuid,
+    announcement_id: Uuid,
+    title: &str,
+    published_at: Option<DateTime<Utc>>,
+) {
+    let view = match resolve_scoreboard_view(state, contest_id, "player").await {
+        Ok(view) => view,
+        Err(err) => {
+            warn!(contest_id = %contest_id, error = %err, "failed to resolve scoreboard freeze state");
+            return;
+        }
+    };
+
+    for division_id in scoreboard_boards(state, contest_id).await {
+        let payload = ScoreboardPushPayload {
+            event: SCOREBOARD_EVENT_ANNOUNCEMENT,
+            contest_id,
+            division_id,
+            frozen: view.frozen(),
+            freeze_at: view.freeze_at,
+            entries: None,
+            deltas: vec![ScoreboardDelta::Announcement {
+                announcement_id,
+                title: title.to_string(),
+                published_at,
+            }],
+        };
+        for live in [false, true] {
+            publish_scoreboard_payload(state, contest_id, live, &payload).await;
+        }
+    }
+}
+
+/// Boards a change is published to: the contest-wide one (`None`) and one
+/// per division.
+async fn scoreboard_boards(state: &AppState, contest_id: Uuid) -> Vec<Option<Uuid>> {
+    let division_ids = match list_contest_division_ids(state, contest_id).await {
+        Ok(division_ids) => division_ids,
+        Err(err) => {
+            warn!(contest_id = %contest_id, error = %err, "failed to load scoreboard divisions");
+            Vec::new()
+        }
+    };
+    std::iter::once(None)
+        .chain(division_ids.into_iter().map(Some))
+        .collect()
+}
+
+/// Each board is published on its own channel, once with the full standings
+/// for sockets that asked for `entries` and once without them. Admins and
+/// judges follow the `live` (unfrozen) channels.
+fn scoreboard_channel(
+    contest_id: Uuid,
+    division_id: Option<Uuid>,
+    live: bool,
+    with_entries: bool,
+) -> String {
+    let mut channel = format!("scoreboard:contest:{}", contest_id);
+    if let Some(division_id) = division_id {
+        channel = format!("{channel}:division:{division_id}");
+    }
+    if live {
+        channel.push_str(":live");
+    }
+    if with_entries {
+        channel.push_str(":entries");
+    }
+    channel
+}
+
+/// Computes the standings of each board once per change and fans them out:
+/// the public channels get the (possibly frozen) board, the live channels the
+/// unfrozen one for admins and judges.
+async fn publish_scoreboard_change(
+    state: &AppState,
+    contest_id: Uuid,
+    event: &str,
+    solve: Option<&ScoreboardSolve>,
+) {
+    // Retire the cached boards first so the standings below are rebuilt
+    // from the change being published.
+    invalidate_scoreboard_cache(state, contest_id).await;
+
+    let solve = match solve {
+        Some(solve) => Some(with_ranked_points(state, contest_id, solve).await),
+        None => None,
+    };
+    let solve = solve.as_ref();
+
+    let view = match resolve_scoreboard_view(state, contest_id, "player").await {
+        Ok(view) => view,
+        Err(err) => {
+            warn!(contest_id = %contest_id, error = %err, "failed to resolve scoreboard freeze state");
+            return;
+        }
+    };
+
+    for division_id in scoreboard_boards(state, contest_id).await {
+        publish_board_change(state, contest_id, division_id, event, view, solve).await;
+    }
+}
+
+async fn publish_board_change(
+    state: &AppState,
+    contest_id: Uuid,
+    division_id: Option<Uuid>,
+    event: &str,
+    view: ScoreboardView,
+    solve: Option<&ScoreboardSolve>,
+) {
+    let Some(public) =
+        build_change_payload(state, contest_id, division_id, event, view, solve, false).await
+    else {
+        return;
+    };
+    publish_scoreboard_payload(state, contest_id, false, &public).await;
+
+    if view.frozen() {
+        let live_view = ScoreboardView {
+            freeze_at: view.freeze_at,
+            cutoff: None,
+        };
+        if let Some(live) = build_change_payload(
+            state,
+            contest_id,
+            division_id,
+            event,
+            live_view,
+            solve,
+            true,
+        )
+        .await
+        {
+            publish_scoreboard_payload(state, contest_id, true, &live).await;
+        }
+    } else {
+        publish_scoreboard_payload(state, contest_id, true, &public).await;
+    }
+}
+
+async fn build_change_payload<'a>(
+    state: &AppState,
+    contest_id: Uuid,
+    division_id: Option<Uuid>,
+    event: &'a str,
+    view: ScoreboardView,
+    solve: Option<&ScoreboardSolve>,
+    live: bool,
+) -> Option<ScoreboardPushPayload<'a>> {
+    let division = load_division_team_ids(state, contest_id, division_id)
+        .await
+        .map_err(|err| {
+            warn!(contest_id = %contest_id, error = %err, "failed to load scoreboard division");
+        })
+        .ok()?;
+    let entries = cached_scoreboard_entries(state, contest_id, view.cutoff, division_id)
+        .await
+        .map_err(|err| {
+            warn!(contest_id = %contest_id, error = %err, "failed to build scoreboard snapshot");
+        })
+        .ok()?;
+
+    let mut deltas = Vec::new();
+    // Solves after the freeze stay hidden from the public board.
+    if let Some(solve) =
+        solve.filter(|solve| !view.frozen() && in_division(division.as_ref(), solve.team_id))
+    {
+        deltas.extend(solve_deltas(solve, &entries));
+    }
+    let standings_key = match (division_id, live) {
+        (Some(division_id), true) => format!("live:division:{division_id}"),
+        (Some(division_id), false) => format!("public:division:{division_id}"),
+        (None, true) => "live".to_string(),
+        (None, false) => "public".to_string(),
+    };
+    if let Some(previous) = swap_cached_standings(state, contest_id, &standings_key, &entries).await
+    {
+        deltas.extend(rank_change_deltas(&previous, &entries));
+    }
+
+    Some(ScoreboardPushPayload {
+        event,
+        contest_id,
+        division_id,
+        frozen: view.frozen(),
+        freeze_at: view.freeze_at,
+        entries: Some(entries),
+        deltas,
+    })
+}
+
+/// Takes the solve's points from the live rankings, which value it at the
+/// challenge's current solve count like the standings do. The rankings are
+/// cached as well, ready for clients refreshing after the push.
+async fn with_ranked_points(
+    state: &AppState,
+    contest_id: Uuid,
+    solve: &ScoreboardSolve,
+) -> ScoreboardSolve {
+    let mut solve = solve.clone();
+    match cached_scoreboard_rankings(state, contest_id, None, None).await {
+        Ok((_, team_rankings, _)) => {
+            if let Some(points) = ranked_solve_points(&team_rankings, &solve) {
+                solve.points = points;
+            }
+        }
+        Err(err) => {
+            warn!(contest_id = %contest_id, error = %err, "failed to build scoreboard rankings");
+        }
+    }
+    solve
+}
+
+/// Points the team's ranking entry credits for the solved challenge, or for
+/// the solved stage of a multi-stage challenge.
+fn ranked_solve_points(
+    team_rankings: &[ScoreboardRankingEntry],
+    solve: &ScoreboardSolve,
+) -> Option<i64> {
+    let challenge = team_rankings
+        .iter()
+        .find(|entry| entry.subject_id == solve.team_id)?
+        .categories
+        .iter()
+        .flat_map(|category| &category.challenges)
+        .find(|challenge| challenge.challenge_id == solve.challenge_id)?;
+
+    match solve.stage_key.as_deref() {
+        Some(stage_key) => challenge
+            .stages
+            .iter()
+            .find(|stage| stage.stage_key == stage_key)
+            .map(|stage| stage.score_awarded),
+        None => Some(challenge.score_awarded),
+    }
 }
 
-async fn scoreboard_ws_loop(
-    mut socket: WebSocket,
-    state: Arc<AppState>,
+/// Stores the standings just published and returns the previous ones.
+async fn swap_cached_standings(
+    state: &AppState,
     contest_id: Uuid,
-    current_user: AuthenticatedUser,
-) {
-    if send_scoreboard_snapshot(&mut socket, state.as_ref(), contest_id)
-        .await
-        .is_err()
-    {
-        return;
+    standings_key: &str,
+    entries: &[ScoreboardEntry],
+) -> Option<Vec<ScoreboardEntry>> {
+    let key = format!("scoreboard:contest:{contest_id}:standings:{standings_key}");
+    let encoded = serde_json::to_string(entries).ok()?;
+    let mut redis_conn = state.redis.clone();
+
+    let result: Result<(Option<String>,), redis::RedisError> = redis::pipe()
+        .atomic()
+        .get(&key)
+        .set_ex(&key, encoded, SCOREBOARD_STANDINGS_TTL_SECS)
+        .ignore()
+        .query_async(&mut redis_conn)
+        .await;
+
+    match result {
+        Ok((previous,)) => previous.and_then(|raw| serde_json::from_str(&raw).ok()),
+        Err(err) => {
+            warn!(contest_id = %contest_id, error = %err, "failed to cache scoreboard standings");
+            None
+        }
     }
+}
 
-    let channel = format!("scoreboard:contest:{}", contest_id);
-
-    let mut pubsub = match state.redis_client.get_async_pubsub().await {
-        Ok(pubsub) => pubsub,
+/// Publishes the payload to its board's channels, with and without the
+/// standings.
+async fn publish_scoreboard_payload(
+    state: &AppState,
+    contest_id: Uuid,
+    live: bool,
+    payload: &ScoreboardPushPayload<'_>,
+) {
+    let mut value = match serde_json::to_value(payload) {
+        Ok(value) => value,
         Err(err) => {
-            warn!(
-                contest_id = %contest_id,
-                user_id = %current_user.user_id,
-                error = %err,
-                "failed to create redis pubsub connection"
-            );
-            let _ = socket
-                .send(Message::Close(Some(axum::extract::ws::CloseFrame {
-                    code: axum::extract::ws::close_code::ERROR,
-                    reason: "pubsub init failed".into(),
-                })))
-                .await;
+            warn!(contest_id = %contest_id, error = %err, "failed to serialize scoreboard payload");
             return;
         }
     };
 
-    if let Err(err) = pubsub.subscribe(&channel).await {
-        warn!(
-            contest_id = %contest_id,
-            user_id = %current_user.user_id,
-            error = %err,
-            "failed to subscribe scoreboard channel"
-        );
-        let _ = socket
-            .send(Message::Close(Some(axum::extract::ws::CloseFrame {
-                code: axum::extract::ws::close_code::ERROR,
-                reason: "pubsub subscribe failed".into(),
-            })))
-            .await;
-        return;
+    let mut redis_conn = state.redis.clone();
+    for with_entries in [true, false] {
+        if !with_entries {
+            if let Some(fields) = value.as_object_mut() {
+                fields.remove("entries");
+            }
+        }
+        let channel = scoreboard_channel(contest_id, payload.division_id, live, with_entries);
+        let publish_result: Result<usize, redis::RedisError> =
+            redis_conn.publish(&channel, value.to_string()).await;
+        if let Err(err) = publish_result {
+            warn!(contest_id = %contest_id, error = %err, "failed to publish scoreboard update event");
+        }
     }
+}
 
-    let mut pubsub_stream = pubsub.on_message();
+fn solve_deltas(solve: &ScoreboardSolve, entries: &[ScoreboardEntry]) -> Vec<ScoreboardDelta> {
+    let team_name = entries
+        .iter()
+        .find(|entry| entry.team_id == solve.team_id)
+        .map(|entry| entry.team_name.clone())
+        .unwrap_or_default();
+
+    let mut deltas = vec![ScoreboardDelta::Solve {
+        team_id: solve.team_id,
+        team_name: team_name.clone(),
+        challenge_id: solve.challenge_id,
+        challenge_title: solve.challenge_title.clone(),
+        points: solve.points,
+        submitted_at: solve.submitted_at,
+    }];
+    if solve.first_blood {
+        deltas.push(ScoreboardDelta::FirstBlood {
+            team_id: solve.team_id,
+            team_name,
+            challenge_id: solve.challenge_id,
+            challenge_title: solve.challenge_title.clone(),
+            submitted_at: solve.submitted_at,
+        });
+    }
+    deltas
+}
 
-    loop {
-        tokio::select! {
-            inbound = socket.recv() => {
-                match inbound {
-                    Some(Ok(Message::Close(_))) | None => break,
-                    Some(Ok(Message::Ping(payload))) => {
-                        if socket.send(Message::Pong(payload)).await.is_err() {
-                            break;
-                        }
-                    }
-                    Some(Ok(_)) => {}
-                    Some(Err(err)) => {
-                        warn!(
-                            contest_id = %contest_id,
-                            user_id = %current_user.user_id,
-                            error = %err,
-                            "websocket receive error"
-                        );
-                        break;
-                    }
-                }
-            }
-            update = pubsub_stream.next() => {
-                if update.is_none() {
-                    break;
-                }
+/// One delta per team whose rank differs from `previous`, including teams
+/// that just entered the board.
+fn rank_change_deltas(
+    previous: &[ScoreboardEntry],
+    current: &[ScoreboardEntry],
+) -> Vec<ScoreboardDelta> {
+    let previous_ranks: HashMap<Uuid, usize> = previous
+        .iter()
+        .map(|entry| (entry.team_id, entry.rank))
+        .collect();
 
-                if send_scoreboard_snapshot(&mut socket, state.as_ref(), contest_id).await.is_err() {
-                    break;
-                }
-            }
-        }
-    }
+    current
+        .iter()
+        .filter_map(|entry| {
+            let previous_rank = previous_ranks.get(&entry.team_id).copied();
+            (previous_rank != Some(entry.rank)).then(|| ScoreboardDelta::RankChange {
+                team_id: entry.team_id,
+                team_name: entry.team_name.clone(),
+                previous_rank,
+                rank: entry.rank,
+                score: entry.score,
+            })
+        })
+        .collect()
 }
 
-async fn send_scoreboard_snapshot(
-    socket: &mut WebSocket,
+/// Teams outside `division` (when set) are left out before ranking.
+fn in_division(division: Option<&HashSet<Uuid>>, team_id: Uuid) -> bool {
+    division.is_none_or(|team_ids| team_ids.contains(&team_id))
+}
+
+async fn cached_scoreboard_entries(
     state: &AppState,
     contest_id: Uuid,
-) -> Result<(), ()> {
-    let entries = load_scoreboard_entries(state, contest_id)
-        .await
-        .map_err(|err| {
-            warn!(contest_id = %contest_id, error = %err, "failed to build scoreboard snapshot");
-        })?;
-
-    let payload = serde_json::to_string(&ScoreboardPushPayload {
-        event: "scoreboard_update",
+    cutoff: Option<DateTime<Utc>>,
+    division_id: Option<Uuid>,
+) -> AppResult<Vec<ScoreboardEntry>> {
+    load_cached_scoreboard(
+        state,
         contest_id,
-        entries,
-    })
-    .map_err(|err| {
-        warn!(contest_id = %contest_id, error = %err, "failed to serialize scoreboard payload");
-    })?;
-
-    socket.send(Message::Text(payload.into())).await.map_err(|err| {
-        warn!(contest_id = %contest_id, error = %err, "failed to send websocket scoreboard payload");
-    })
+        "entries",
+        cutoff,
+        division_id,
+        || async {
+            let division = load_division_team_ids(state, contest_id, division_id).await?;
+            load_scoreboard_entries(state, contest_id, cutoff, division.as_ref()).await
+        },
+    )
+    .await
 }
 
-async fn ensure_scoreboard_access(
+async fn cached_scoreboard_rankings(
     state: &AppState,
     contest_id: Uuid,
-    current_user: &AuthenticatedUser,
-) -> AppResult<()> {
-    ensure_user_contest_workspace_access(state, contest_id, current_user).await?;
-    Ok(())
+    cutoff: Option<DateTime<Utc>>,
+    division_id: Option<Uuid>,
+) -> AppResult<ScoreboardRankings> {
+    load_cached_scoreboard(
+        state,
+        contest_id,
+        "rankings",
+        cutoff,
+        division_id,
+        || async {
+            let division = load_division_team_ids(state, contest_id, division_id).await?;
+            load_scoreboard_rankings(state, contest_id, cutoff, division.as_ref()).await
+        },
+    )
+    .await
 }
 
 async fn load_scoreboard_entries(
     state: &AppState,
     contest_id: Uuid,
+    cutoff: Option<DateTime<Utc>>,
+    division: Option<&HashSet<Uuid>>,
 ) -> AppResult<Vec<ScoreboardEntry>> {
-    let rows = sqlx::query_as::<_, ScoreboardRow>(
-        "SELECT s.team_id,
-                t.name AS team_name,
-                COALESCE(SUM(s.score_awarded), 0) AS score,
-                COUNT(*) FILTER (WHERE s.verdict = 'accepted' AND s.score_awarded > 0) AS solved_count,
-                MAX(s.submitted_at) AS last_submit_at
+    let participants = sqlx::query_as::<_, ScoreboardParticipantRow>(
+        "SELECT DISTINCT s.team_id,
+                t.name AS team_name
          FROM submissions s
          JOIN teams t ON t.id = s.team_id
          WHERE s.contest_id = $1
-         GROUP BY s.team_id, t.name
-         ORDER BY score DESC, solved_count DESC, last_submit_at ASC",
+           AND ($2::timestamptz IS NULL OR s.submitted_at < $2)",
     )
     .bind(contest_id)
+    .bind(cutoff)
     .fetch_all(&state.db)
     .await
     .map_err(AppError::internal)?;
 
-    let mut scoreboard = Vec::with_capacity(rows.len());
-    let mut last_rank_score: Option<(i64, i64, Option<DateTime<Utc>>)> = None;
-    let mut current_rank = 0_usize;
+    let solves = load_contest_solves(state, contest_id, cutoff).await?;
 
-    for (index, row) in rows.into_iter().enumerate() {
-        let key = (row.score, row.solved_count, row.last_submit_at);
-        if last_rank_score.as_ref() != Some(&key) {
-            current_rank = index + 1;
-            last_rank_score = Some(key);
+    let mut team_states: HashMap<Uuid, TeamScoreState> = participants
+        .into_iter()
+        .filter(|row| in_division(division, row.team_id))
+        .map(|row| (row.team_id, TeamScoreState::new(row.team_name)))
+        .collect();
+
+    let mut completion = ChallengeCompletionTracker::default();
+    for item in solves.scored() {
+        if !in_division(division, item.record.team_id) {
+            continue;
+        }
+        let completed = completion.record(item.record.team_id, &item.record);
+        let team_state = team_states
+            .entry(item.record.team_id)
+            .or_insert_with(|| TeamScoreState::new(item.record.team_name.clone()));
+        team_state.score += item.points;
+        if completed {
+            team_state.solved_count += 1;
         }
+        team_state.last_submit_at = Some(item.record.submitted_at);
+    }
 
-        scoreboard.push(ScoreboardEntry {
-            rank: current_rank,
-            team_id: row.team_id,
-            team_name: row.team_name,
-            score: row.score,
-            solved_count: row.solved_count,
-            last_submit_at: row.last_submit_at,
-        });
+    for adjustment in &solves.adjustments {
+        if !in_division(division, adjustment.team_id) {
+            continue;
+        }
+        team_states
+            .entry(adjustment.team_id)
+            .or_insert_with(|| TeamScoreState::new(adjustment.team_name.clone()))
+            .adjust(adjustment.points);
     }
 
-    Ok(scoreboard)
+    Ok(build_ranked_entries_from_states(
+        &team_states,
+        solves.config.score_floor,
+    ))
 }
 
 async fn load_scoreboard_timeline(
     state: &AppState,
     contest_id: Uuid,
+    cutoff: Option<DateTime<Utc>>,
+    division: Option<&HashSet<Uuid>>,
     max_snapshots: usize,
     top_n: usize,
 ) -> AppResult<(Vec<ScoreboardTimelineSnapshot>, Vec<ScoreboardEntry>)> {
-    let latest_entries = load_scoreboard_entries(state, contest_id).await?;
-
-    let events = sqlx::query_as::<_, ScoreboardTimelineEventRow>(
-        "SELECT s.id AS submission_id,
-                s.team_id,
-                t.name AS team_name,
-                s.score_awarded,
-                s.submitted_at
-         FROM submissions s
-         JOIN teams t ON t.id = s.team_id
-         WHERE s.contest_id = $1
-           AND s.verdict = 'accepted'
-           AND s.score_awarded > 0
-         ORDER BY s.submitted_at ASC, s.id ASC",
-    )
-    .bind(contest_id)
-    .fetch_all(&state.db)
-    .await
-    .map_err(AppError::internal)?;
+    let latest_entries = load_scoreboard_entries(state, contest_id, cutoff, division).await?;
 
-    if events.is_empty() {
+    let solves = load_contest_solves(state, contest_id, cutoff).await?;
+    let has_snapshot_adjustments = solves
+        .adjustments
+        .iter()
+        .any(ScoreAdjustment::has_own_snapshot);
+    if solves.solves.is_empty() && !has_snapshot_adjustments {
         return Ok((Vec::new(), latest_entries));
     }
 
-    let mut team_states: HashMap<Uuid, TimelineTeamState> = HashMap::new();
-    let mut snapshots: Vec<ScoreboardTimelineSnapshot> = Vec::with_capacity(events.len());
+    // Adjustments carry no submission id, so they are folded into the next
+    // solve snapshot instead of producing their own; round ticks and admin
+    // adjustments are the exception and get one snapshot per timestamp.
+    let mut adjustments = solves.adjustments.iter().peekable();
+
+    let mut team_states: HashMap<Uuid, TeamScoreState> = HashMap::new();
+    let mut challenge_solvers: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    let mut completion = ChallengeCompletionTracker::default();
+    let mut snapshots: Vec<ScoreboardTimelineSnapshot> = Vec::with_capacity(solves.solves.len());
+
+    for solve in &solves.solves {
+        apply_timeline_adjustments(
+            &mut adjustments,
+            Some(solve.submitted_at),
+            division,
+            &mut team_states,
+            &mut snapshots,
+            top_n,
+            solves.config.score_floor,
+        );
+
+        let points = if solve.stage_key.is_some() {
+            solve.stage_points.max(0) as i64
+        } else {
+            let solvers = challenge_solvers.entry(solve.challenge_id).or_default();
+            let previous_value =
+                challenge_value(&solves.config, solve.bounds(), solvers.len() as i64);
+            let current_value =
+                challenge_value(&solves.config, solve.bounds(), solvers.len() as i64 + 1);
+
+            // Under dynamic scoring every earlier solver loses the decay as well.
+            let decay = current_value - previous_value;
+            if decay != 0 {
+                for team_id in solvers.iter() {
+                    if let Some(team_state) = team_states.get_mut(team_id) {
+                        team_state.score += decay;
+                    }
+                }
+            }
+            solvers.push(solve.team_id);
+            current_value + solve.blood_bonus.max(0) as i64
+        };
+        let completed = completion.record(solve.team_id, solve);
+
+        // Solves outside the division still decay dynamic values above.
+        if !in_division(division, solve.team_id) {
+            continue;
+        }
 
-    for event in events {
         let team_state = team_states
-            .entry(event.team_id)
-            .or_insert_with(|| TimelineTeamState {
-                team_name: event.team_name.clone(),
-                score: 0,
-                solved_count: 0,
-                last_submit_at: None,
-            });
+            .entry(solve.team_id)
+            .or_insert_with(|| TeamScoreState::new(solve.team_name.clone()));
 
-        team_state.team_name = event.team_name.clone();
-        team_state.score += event.score_awarded as i64;
-        team_state.solved_count += 1;
-        team_state.last_submit_at = Some(event.submitted_at);
+        team_state.team_name = solve.team_name.clone();
+        team_state.score += points;
+        if completed {
+            team_state.solved_count += 1;
+        }
+        team_state.last_submit_at = Some(solve.submitted_at);
 
-        let mut entries = build_ranked_entries_from_states(&team_states);
+        let mut entries = build_ranked_entries_from_states(&team_states, solves.config.score_floor);
         if entries.len() > top_n {
             entries.truncate(top_n);
         }
 
         snapshots.push(ScoreboardTimelineSnapshot {
-            trigger_submission_id: event.submission_id,
-            timestamp: event.submitted_at,
+            trigger_submission_id: Some(solve.submission_id),
+            timestamp: solve.submitted_at,
             entries,
         });
     }
+    apply_timeline_adjustments(
+        &mut adjustments,
+        None,
+        division,
+        &mut team_states,
+        &mut snapshots,
+        top_n,
+        solves.config.score_floor,
+    );
 
     let snapshots = downsample_timeline_snapshots(snapshots, max_snapshots);
     Ok((snapshots, latest_entries))
 }
 
+/// Applies the adjustments up to `until` (all when `None`), closing a
+/// snapshot after the last adjustment of each round tick.
+fn apply_timeline_adjustments<'a>(
+    adjustments: &mut Peekable<impl Iterator<Item = &'a ScoreAdjustment>>,
+    until: Option<DateTime<Utc>>,
+    division: Option<&HashSet<Uuid>>,
+    team_states: &mut HashMap<Uuid, TeamScoreState>,
+    snapshots: &mut Vec<ScoreboardTimelineSnapshot>,
+    top_n: usize,
+    score_floor: i32,
+) {
+    while let Some(adjustment) =
+        adjustments.next_if(|item| until.is_none_or(|until| item.occurred_at <= until))
+    {
+        if in_division(division, adjustment.team_id) {
+            team_states
+                .entry(adjustment.team_id)
+                .or_insert_with(|| TeamScoreState::new(adjustment.team_name.clone()))
+                .adjust(adjustment.points);
+        }
+
+        let snapshot_closed = adjustment.has_own_snapshot()
+            && adjustments.peek().is_none_or(|next| {
+                !next.has_own_snapshot() || next.occurred_at != adjustment.occurred_at
+            });
+        if snapshot_closed {
+            let mut entries = build_ranked_entries_from_states(team_states, score_floor);
+            entries.truncate(top_n);
+            snapshots.push(ScoreboardTimelineSnapshot {
+                trigger_submission_id: None,
+                timestamp: adjustment.occurred_at,
+                entries,
+            });
+        }
+    }
+}
+
 fn build_ranked_entries_from_states(
-    team_states: &HashMap<Uuid, TimelineTeamState>,
+    team_states: &HashMap<Uuid, TeamScoreState>,
+    score_floor: i32,
 ) -> Vec<ScoreboardEntry> {
-    let mut rows: Vec<(Uuid, TimelineTeamState)> = team_states
+    let mut rows: Vec<(Uuid, TeamScoreState)> = team_states
         .iter()
-        .map(|(team_id, state)| (*team_id, state.clone()))
+        .map(|(team_id, state)| {
+            let mut state = state.clone();
+            state.score = apply_score_floor(score_floor, state.score, state.deducted);
+            (*team_id, state)
+        })
         .collect();
 
     rows.sort_by(|lhs, rhs| {
@@ -581,11 +2002,9 @@ fn downsample_timeline_snapshots(
 async fn load_scoreboard_rankings(
     state: &AppState,
     contest_id: Uuid,
-) -> AppResult<(
-    Vec<ScoreboardCategoryItem>,
-    Vec<ScoreboardRankingEntry>,
-    Vec<ScoreboardRankingEntry>,
-)> {
+    cutoff: Option<DateTime<Utc>>,
+    division: Option<&HashSet<Uuid>>,
+) -> AppResult<ScoreboardRankings> {
     let catalog_rows = sqlx::query_as::<_, ContestChallengeCatalogRow>(
         "SELECT c.id AS challenge_id,
                 c.title AS challenge_title,
@@ -626,97 +2045,118 @@ async fn load_scoreboard_rankings(
         });
     }
 
-    let events = sqlx::query_as::<_, RankingSolveEventRow>(
-        "SELECT s.id AS _submission_id,
-                s.team_id,
-                t.name AS team_name,
-                s.user_id,
-                u.username,
-                c.id AS challenge_id,
-                c.title AS challenge_title,
-                c.slug AS challenge_slug,
-                c.category AS challenge_category,
-                s.score_awarded,
-                s.submitted_at
-         FROM submissions s
-         JOIN teams t ON t.id = s.team_id
-         JOIN users u ON u.id = s.user_id
-         JOIN challenges c ON c.id = s.challenge_id
-         WHERE s.contest_id = $1
-           AND s.verdict = 'accepted'
-           AND s.score_awarded > 0
-         ORDER BY s.submitted_at ASC, s.id ASC",
-    )
-    .bind(contest_id)
-    .fetch_all(&state.db)
-    .await
-    .map_err(AppError::internal)?;
+    let solves = load_contest_solves(state, contest_id, cutoff).await?;
 
     let mut team_states: HashMap<Uuid, RankingSubjectState> = HashMap::new();
     let mut player_states: HashMap<Uuid, RankingSubjectState> = HashMap::new();
-    let mut team_seen: HashSet<(Uuid, Uuid)> = HashSet::new();
-    let mut player_seen: HashSet<(Uuid, Uuid)> = HashSet::new();
+    let mut team_seen: HashSet<(Uuid, Uuid, Option<String>)> = HashSet::new();
+    let mut player_seen: HashSet<(Uuid, Uuid, Option<String>)> = HashSet::new();
+    let mut team_completion = ChallengeCompletionTracker::default();
+    let mut player_completion = ChallengeCompletionTracker::default();
     let mut team_blood_order: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
     let mut player_blood_order: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
 
-    for row in events {
-        if team_seen.insert((row.team_id, row.challenge_id)) {
-            let order = marker_order_for_subject(
-                &mut team_blood_order,
-                row.challenge_id,
-                row.team_id,
-            );
-            let solve = ScoreboardRankingChallenge {
-                challenge_id: row.challenge_id,
-                challenge_title: row.challenge_title.clone(),
-                challenge_slug: row.challenge_slug.clone(),
-                marker: marker_name(order).to_string(),
-                score_awarded: row.score_awarded,
-                submitted_at: row.submitted_at,
-            };
-            push_subject_solve(
-                &mut team_states,
-                row.team_id,
-                row.team_name.clone(),
-                row.challenge_category.clone(),
-                solve,
-                row.score_awarded,
-                row.submitted_at,
-            );
+    for item in solves.scored() {
+        let points = item.points;
+        let row = item.record;
+        // Blood markers follow the contest-wide solve order, as the blood
+        // bonuses in `points` do; a division only filters who is listed.
+        let listed = in_division(division, row.team_id);
+        if team_seen.insert((row.team_id, row.challenge_id, row.stage_key.clone())) {
+            let completed = team_completion.record(row.team_id, &row);
+            let order = completed.then(|| {
+                marker_order_for_subject(&mut team_blood_order, row.challenge_id, row.team_id)
+            });
+            if listed {
+                push_subject_solve(
+                    &mut team_states,
+                    row.team_id,
+                    row.team_name.clone(),
+                    row.challenge_category.clone(),
+                    build_ranking_challenge(&row, points, order),
+                    completed,
+                );
+            }
         }
 
-        if player_seen.insert((row.user_id, row.challenge_id)) {
-            let order = marker_order_for_subject(
-                &mut player_blood_order,
-                row.challenge_id,
-                row.user_id,
-            );
-            let solve = ScoreboardRankingChallenge {
-                challenge_id: row.challenge_id,
-                challenge_title: row.challenge_title,
-                challenge_slug: row.challenge_slug,
-                marker: marker_name(order).to_string(),
-                score_awarded: row.score_awarded,
-                submitted_at: row.submitted_at,
-            };
-            push_subject_solve(
-                &mut player_states,
-                row.user_id,
-                row.username,
-                row.challenge_category,
-                solve,
-                row.score_awarded,
-                row.submitted_at,
-            );
+        if player_seen.insert((row.user_id, row.challenge_id, row.stage_key.clone())) {
+            let completed = player_completion.record(row.user_id, &row);
+            let order = completed.then(|| {
+                marker_order_for_subject(&mut player_blood_order, row.challenge_id, row.user_id)
+            });
+            if listed {
+                push_subject_solve(
+                    &mut player_states,
+                    row.user_id,
+                    row.username.clone(),
+                    row.challenge_category.clone(),
+                    build_ranking_challenge(&row, points, order),
+                    completed,
+                );
+            }
+        }
+    }
+
+    for adjustment in &solves.adjustments {
+        if !in_division(division, adjustment.team_id) {
+            continue;
         }
+        team_states
+            .entry(adjustment.team_id)
+            .or_insert_with(|| RankingSubjectState::new(adjustment.team_name.clone()))
+            .adjust(adjustment.points);
     }
 
-    let team_rankings = build_ranking_entries(team_states, &category_order);
-    let player_rankings = build_ranking_entries(player_states, &category_order);
+    let score_floor = solves.config.score_floor;
+    let team_rankings = build_ranking_entries(team_states, &category_order, score_floor);
+    let player_rankings = build_ranking_entries(player_states, &category_order, score_floor);
 
     Ok((categories, team_rankings, player_rankings))
 }
 
+/// Tasks are keyed by challenge title; only fully solved challenges appear in
+/// `taskStats`.
+fn build_ctftime_scoreboard(
+    categories: &[ScoreboardCategoryItem],
+    team_rankings: &[ScoreboardRankingEntry],
+) -> CtftimeScoreboard {
+    let tasks = categories
+        .iter()
+        .flat_map(|category| category.challenges.iter())
+        .map(|challenge| challenge.challenge_title.clone())
+        .collect();
+
+    let standings = team_rankings
+        .iter()
+        .map(|entry| CtftimeStanding {
+            pos: entry.rank,
+            team: entry.subject_name.clone(),
+            score: entry.total_score,
+            task_stats: entry
+                .categories
+                .iter()
+                .flat_map(|category| category.challenges.iter())
+                .filter(|challenge| challenge.marker != "partial")
+                .map(|challenge| {
+                    (
+                        challenge.challenge_title.clone(),
+                        CtftimeTaskStat {
+                            points: challenge.score_awarded,
+                            time: challenge.submitted_at.timestamp(),
+                        },
+                    )
+                })
+                .collect(),
+            last_accept: entry
+                .last_submit_at
+                .map(|value| value.timestamp())
+                .unwrap_or(0),
+        })
+        .collect();
+
+    CtftimeScoreboard { tasks, standings }
+}
+
 fn marker_order_for_subject(
     order_map: &mut HashMap<Uuid, Vec<Uuid>>,
     challenge_id: Uuid,
@@ -740,34 +2180,82 @@ fn marker_name(order: usize) -> &'static str {
     }
 }
 
+/// `blood_order` is set once the subject has completed the challenge; stage
+/// solves before that are marked `partial`.
+fn build_ranking_challenge(
+    row: &SolveRecord,
+    points: i64,
+    blood_order: Option<usize>,
+) -> ScoreboardRankingChallenge {
+    let stages = match row.stage_key.as_ref() {
+        Some(stage_key) => vec![ScoreboardRankingStage {
+            stage_key: stage_key.clone(),
+            stage_title: row
+                .stage_title
+                .clone()
+                .unwrap_or_else(|| stage_key.clone()),
+            score_awarded: points,
+            submitted_at: row.submitted_at,
+        }],
+        None => Vec::new(),
+    };
+
+    ScoreboardRankingChallenge {
+        challenge_id: row.challenge_id,
+        challenge_title: row.challenge_title.clone(),
+        challenge_slug: row.challenge_slug.clone(),
+        marker: blood_order.map(marker_name).unwrap_or("partial").to_string(),
+        score_awarded: points,
+        submitted_at: row.submitted_at,
+        stages,
+    }
+}
+
 fn push_subject_solve(
     states: &mut HashMap<Uuid, RankingSubjectState>,
     subject_id: Uuid,
     subject_name: String,
     category: String,
     solve: ScoreboardRankingChallenge,
-    score_awarded: i32,
-    submitted_at: DateTime<Utc>,
+    completed: bool,
 ) {
-    let state = states.entry(subject_id).or_insert_with(|| RankingSubjectState {
-        subject_name,
-        total_score: 0,
-        solved_count: 0,
-        last_submit_at: None,
-        categories: HashMap::new(),
-    });
+    let state = states
+        .entry(subject_id)
+        .or_insert_with(|| RankingSubjectState::new(subject_name));
 
-    state.total_score += score_awarded as i64;
-    state.solved_count += 1;
-    state.last_submit_at = Some(submitted_at);
-    state.categories.entry(category).or_default().push(solve);
+    state.total_score += solve.score_awarded;
+    if completed {
+        state.solved_count += 1;
+    }
+    state.last_submit_at = Some(solve.submitted_at);
+
+    let challenges = state.categories.entry(category).or_default();
+    match challenges
+        .iter_mut()
+        .find(|item| item.challenge_id == solve.challenge_id)
+    {
+        Some(existing) => {
+            existing.score_awarded += solve.score_awarded;
+            existing.submitted_at = solve.submitted_at;
+            existing.marker = solve.marker;
+            existing.stages.extend(solve.stages);
+        }
+        None => challenges.push(solve),
+    }
 }
 
 fn build_ranking_entries(
     states: HashMap<Uuid, RankingSubjectState>,
     category_order: &[String],
+    score_floor: i32,
 ) -> Vec<ScoreboardRankingEntry> {
-    let mut rows: Vec<(Uuid, RankingSubjectState)> = states.into_iter().collect();
+    let mut rows: Vec<(Uuid, RankingSubjectState)> = states
+        .into_iter()
+        .map(|(subject_id, mut state)| {
+            state.total_score = apply_score_floor(score_floor, state.total_score, state.deducted);
+            (subject_id, state)
+        })
+        .collect();
     rows.sort_by(|lhs, rhs| {
         rhs.1
             .total_score
@@ -805,7 +2293,10 @@ fn build_ranking_entries(
                 });
                 ScoreboardRankingCategory {
                     category,
-                    solved_count: challenges.len() as i64,
+                    solved_count: challenges
+                        .iter()
+                        .filter(|item| item.marker != "partial")
+                        .count() as i64,
                     challenges,
                 }
             })
@@ -829,6 +2320,7 @@ fn build_ranking_entries(
             subject_id,
             subject_name: state.subject_name,
             total_score: state.total_score,
+            score_adjustment: state.score_adjustment,
             solved_count: state.solved_count,
             last_submit_at: state.last_submit_at,
             categories,
@@ -837,3 +2329,276 @@ fn build_ranking_entries(
 
     entries
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, SocketAddr};
+
+    use axum::http::{HeaderMap, HeaderValue};
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    use super::{
+        average_solve_seconds, build_ctftime_scoreboard, challenge_solves_from_rankings,
+        rank_change_deltas, ranked_solve_points, scoreboard_channel, solve_deltas,
+        spectator_client_ip, ScoreboardCategoryChallengeItem, ScoreboardCategoryItem,
+        ScoreboardDelta, ScoreboardEntry, ScoreboardRankingCategory, ScoreboardRankingChallenge,
+        ScoreboardRankingEntry, ScoreboardRankingStage, ScoreboardSolve,
+    };
+
+    fn challenge(title: &str, marker: &str, points: i64, at: i64) -> ScoreboardRankingChallenge {
+        ScoreboardRankingChallenge {
+            challenge_id: Uuid::nil(),
+            challenge_title: title.to_string(),
+            challenge_slug: title.to_string(),
+            marker: marker.to_string(),
+            score_awarded: points,
+            submitted_at: Utc.timestamp_opt(at, 0).unwrap(),
+            stages: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ctftime_feed_lists_solved_tasks_only() {
+        let categories = vec![ScoreboardCategoryItem {
+            category: "web".to_string(),
+            challenges: ["login", "upload"]
+                .into_iter()
+                .map(|title| ScoreboardCategoryChallengeItem {
+                    challenge_id: Uuid::nil(),
+                    challenge_title: title.to_string(),
+                    challenge_slug: title.to_string(),
+                })
+                .collect(),
+        }];
+        let rankings = vec![ScoreboardRankingEntry {
+            rank: 1,
+            subject_id: Uuid::nil(),
+            subject_name: "team a".to_string(),
+            total_score: 450,
+            score_adjustment: -50,
+            solved_count: 1,
+            last_submit_at: Some(Utc.timestamp_opt(1_700_000_200, 0).unwrap()),
+            categories: vec![ScoreboardRankingCategory {
+                category: "web".to_string(),
+                solved_count: 1,
+                challenges: vec![
+                    challenge("login", "first_blood", 500, 1_700_000_100),
+                    challenge("upload", "partial", 0, 1_700_000_200),
+                ],
+            }],
+        }];
+
+        let feed = serde_json::to_value(build_ctftime_scoreboard(&categories, &rankings)).unwrap();
+        assert_eq!(
+            feed,
+            serde_json::json!({
+                "tasks": ["login", "upload"],
+                "standings": [{
+                    "pos": 1,
+                    "team": "team a",
+                    "score": 450,
+                    "taskStats": {"login": {"points": 500, "time": 1_700_000_100}},
+                    "lastAccept": 1_700_000_200
+                }]
+            })
+        );
+    }
+
+    fn entry(rank: usize, team: u128, score: i64) -> ScoreboardEntry {
+        ScoreboardEntry {
+            rank,
+            team_id: Uuid::from_u128(team),
+            team_name: format!("team {team}"),
+            score,
+            solved_count: 0,
+            last_submit_at: None,
+        }
+    }
+
+    #[test]
+    fn rank_changes_cover_moved_and_new_teams_only() {
+        let previous = vec![entry(1, 1, 300), entry(2, 2, 200), entry(3, 3, 100)];
+        let current = vec![
+            entry(1, 2, 400),
+            entry(2, 1, 300),
+            entry(3, 3, 150),
+            entry(4, 4, 50),
+        ];
+
+        let moved: Vec<(u128, Option<usize>, usize)> = rank_change_deltas(&previous, &current)
+            .into_iter()
+            .map(|delta| match delta {
+                ScoreboardDelta::RankChange {
+                    team_id,
+                    previous_rank,
+                    rank,
+                    ..
+                } => (team_id.as_u128(), previous_rank, rank),
+                other => panic!("unexpected delta {other:?}"),
+            })
+            .collect();
+
+        assert_eq!(moved, vec![(2, Some(2), 1), (1, Some(1), 2), (4, None, 4)]);
+    }
+
+    #[test]
+    fn challenge_solves_skip_partial_and_follow_solve_time() {
+        let ranking =
+            |team: u128, challenges: Vec<ScoreboardRankingChallenge>| ScoreboardRankingEntry {
+                rank: 1,
+                subject_id: Uuid::from_u128(team),
+                subject_name: format!("team {team}"),
+                total_score: 0,
+                score_adjustment: 0,
+                solved_count: 0,
+                last_submit_at: None,
+                categories: vec![ScoreboardRankingCategory {
+                    category: "pwn".to_string(),
+                    solved_count: 0,
+                    challenges,
+                }],
+            };
+        let rankings = vec![
+            ranking(
+                1,
+                vec![challenge("heap", "second_blood", 480, 1_700_000_300)],
+            ),
+            ranking(
+                2,
+                vec![challenge("heap", "first_blood", 500, 1_700_000_100)],
+            ),
+            ranking(3, vec![challenge("heap", "partial", 100, 1_700_000_050)]),
+        ];
+
+        let solves = challenge_solves_from_rankings(&rankings, Uuid::nil());
+        let order: Vec<(usize, u128, &str)> = solves
+            .iter()
+            .map(|solve| {
+                (
+                    solve.position,
+                    solve.team_id.as_u128(),
+                    solve.marker.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(order, vec![(1, 2, "first_blood"), (2, 1, "second_blood")]);
+
+        let released_at = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        assert_eq!(average_solve_seconds(&solves, released_at), Some(200));
+        assert_eq!(average_solve_seconds(&[], released_at), None);
+    }
+
+    #[test]
+    fn solve_delta_points_match_ranking_points() {
+        let team_id = Uuid::from_u128(1);
+        let mut staged = challenge("chain", "partial", 150, 1_700_000_400);
+        staged.challenge_id = Uuid::from_u128(9);
+        staged.stages = vec![ScoreboardRankingStage {
+            stage_key: "leak".to_string(),
+            stage_title: "leak".to_string(),
+            score_awarded: 150,
+            submitted_at: Utc.timestamp_opt(1_700_000_400, 0).unwrap(),
+        }];
+        let rankings = vec![ScoreboardRankingEntry {
+            rank: 1,
+            subject_id: team_id,
+            subject_name: "team 1".to_string(),
+            total_score: 600,
+            score_adjustment: 0,
+            solved_count: 1,
+            last_submit_at: None,
+            categories: vec![ScoreboardRankingCategory {
+                category: "pwn".to_string(),
+                solved_count: 1,
+                // Decayed from 500 to 410 since the solve, plus a 10% first blood.
+                challenges: vec![challenge("heap", "first_blood", 450, 1_700_000_100), staged],
+            }],
+        }];
+        let mut solve = ScoreboardSolve {
+            team_id,
+            challenge_id: Uuid::nil(),
+            challenge_title: "heap".to_string(),
+            stage_key: None,
+            points: 550,
+            first_blood: true,
+            submitted_at: Utc.timestamp_opt(1_700_000_100, 0).unwrap(),
+        };
+
+        let points_of = |solve: &ScoreboardSolve| {
+            let deltas = solve_deltas(solve, &[entry(1, 1, 600)]);
+            match &deltas[0] {
+                ScoreboardDelta::Solve { points, .. } => *points,
+                other => panic!("unexpected delta {other:?}"),
+            }
+        };
+
+        solve.points = ranked_solve_points(&rankings, &solve).unwrap();
+        assert_eq!(points_of(&solve), 450);
+
+        solve.challenge_id = Uuid::from_u128(9);
+        solve.stage_key = Some("leak".to_string());
+        solve.points = ranked_solve_points(&rankings, &solve).unwrap();
+        assert_eq!(points_of(&solve), 150);
+
+        solve.team_id = Uuid::from_u128(2);
+        assert_eq!(ranked_solve_points(&rankings, &solve), None);
+    }
+
+    #[test]
+    fn spectator_ip_only_trusts_forwarding_from_configured_proxies() {
+        let proxy: IpAddr = "10.0.0.2".parse().unwrap();
+        let client: SocketAddr = "203.0.113.7:40000".parse().unwrap();
+        let via_proxy: SocketAddr = "10.0.0.2:40000".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("1.1.1.1, 198.51.100.9, 10.0.0.2"),
+        );
+        headers.insert("x-real-ip", HeaderValue::from_static("1.1.1.1"));
+
+        // Direct clients cannot pick their bucket with forged headers.
+        assert_eq!(spectator_client_ip(client, &headers, &[proxy]), client.ip());
+        assert_eq!(spectator_client_ip(via_proxy, &headers, &[]), proxy);
+
+        // Behind the proxy, the rightmost untrusted hop is the client; hops
+        // further left were supplied by that client.
+        assert_eq!(
+            spectator_client_ip(via_proxy, &headers, &[proxy]),
+            "198.51.100.9".parse::<IpAddr>().unwrap()
+        );
+
+        headers.remove("x-forwarded-for");
+        assert_eq!(
+            spectator_client_ip(via_proxy, &headers, &[proxy]),
+            "1.1.1.1".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn scoreboard_channels_are_distinct_per_board_and_variant() {
+        let contest_id = Uuid::from_u128(1);
+        let division_id = Uuid::from_u128(2);
+
+        assert_eq!(
+            scoreboard_channel(contest_id, None, false, false),
+            format!("scoreboard:contest:{contest_id}")
+        );
+        assert_eq!(
+            scoreboard_channel(contest_id, Some(division_id), true, true),
+            format!("scoreboard:contest:{contest_id}:division:{division_id}:live:entries")
+        );
+
+        let mut channels = Vec::new();
+        for division in [None, Some(division_id)] {
+            for live in [false, true] {
+                for with_entries in [false, true] {
+                    channels.push(scoreboard_channel(contest_id, division, live, with_entries));
+                }
+            }
+        }
+        channels.sort();
+        channels.dedup();
+        assert_eq!(channels.len(), 8);
+    }
+}
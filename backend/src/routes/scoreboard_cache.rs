@@ -0,0 +1,149 @@
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{error::AppResult, state::AppState};
+
+/// Upper bound on how long a cached board survives without an invalidation,
+/// for changes that do not bump the version (team renames and the like).
+const SCOREBOARD_CACHE_TTL_SECS: u64 = 60;
+
+/// Bumps the contest's scoreboard cache version. Every replica reads the
+/// version before building a board, so entries cached under older versions
+/// are never served again and simply expire. Call after the change commits.
+pub(crate) async fn invalidate_scoreboard_cache(state: &AppState, contest_id: Uuid) {
+    let mut redis_conn = state.redis.clone();
+    let result: Result<u64, redis::RedisError> =
+        redis_conn.incr(scoreboard_version_key(contest_id), 1).await;
+    if let Err(err) = result {
+        warn!(contest_id = %contest_id, error = %err, "failed to bump scoreboard cache version");
+    }
+}
+
+/// Invalidates every contest the challenge is bound to.
+pub(crate) async fn invalidate_challenge_scoreboards(state: &AppState, challenge_id: Uuid) {
+    let contest_ids = match sqlx::query_scalar::<_, Uuid>(
+        "SELECT contest_id FROM contest_challenges WHERE challenge_id = $1",
+    )
+    .bind(challenge_id)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(contest_ids) => contest_ids,
+        Err(err) => {
+            warn!(challenge_id = %challenge_id, error = %err, "failed to load challenge contests");
+            return;
+        }
+    };
+
+    for contest_id in contest_ids {
+        invalidate_scoreboard_cache(state, contest_id).await;
+    }
+}
+
+/// Serves a computed board from Redis, building and storing it on a miss.
+/// `kind` names the computation and its parameters; the freeze cutoff and
+/// division are appended here. Redis failures fall back to building.
+pub(crate) async fn load_cached_scoreboard<T, F, Fut>(
+    state: &AppState,
+    contest_id: Uuid,
+    kind: &str,
+    cutoff: Option<DateTime<Utc>>,
+    division_id: Option<Uuid>,
+    build: F,
+) -> AppResult<T>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = AppResult<T>>,
+{
+    let mut redis_conn = state.redis.clone();
+
+    // The version must be read before building so a board computed from
+    // pre-change data can only land under the version the change retired.
+    let version: Option<u64> = match redis_conn.get(scoreboard_version_key(contest_id)).await {
+        Ok(version) => version,
+        Err(err) => {
+            warn!(contest_id = %contest_id, error = %err, "failed to read scoreboard cache version");
+            return build().await;
+        }
+    };
+    let key = scoreboard_cache_key(contest_id, version.unwrap_or(0), kind, cutoff, division_id);
+
+    match redis_conn.get::<_, Option<String>>(&key).await {
+        Ok(Some(raw)) => match serde_json::from_str(&raw) {
+            Ok(value) => return Ok(value),
+            Err(err) => {
+                warn!(contest_id = %contest_id, key = %key, error = %err, "discarding unreadable scoreboard cache entry");
+            }
+        },
+        Ok(None) => {}
+        Err(err) => {
+            warn!(contest_id = %contest_id, error = %err, "failed to read scoreboard cache");
+        }
+    }
+
+    let value = build().await?;
+    if let Ok(encoded) = serde_json::to_string(&value) {
+        let result: Result<(), redis::RedisError> = redis_conn
+            .set_ex(&key, encoded, SCOREBOARD_CACHE_TTL_SECS)
+            .await;
+        if let Err(err) = result {
+            warn!(contest_id = %contest_id, error = %err, "failed to write scoreboard cache");
+        }
+    }
+
+    Ok(value)
+}
+
+fn scoreboard_version_key(contest_id: Uuid) -> String {
+    format!("scoreboard:contest:{}:version", contest_id)
+}
+
+fn scoreboard_cache_key(
+    contest_id: Uuid,
+    version: u64,
+    kind: &str,
+    cutoff: Option<DateTime<Utc>>,
+    division_id: Option<Uuid>,
+) -> String {
+    let freeze = cutoff
+        .map(|value| value.timestamp_millis().to_string())
+        .unwrap_or_else(|| "live".to_string());
+    let division = division_id
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "all".to_string());
+
+    format!(
+        "scoreboard:contest:{}:cache:v{}:{}:{}:{}",
+        contest_id, version, kind, freeze, division
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    use super::scoreboard_cache_key;
+
+    #[test]
+    fn cache_key_separates_versions_freeze_states_and_divisions() {
+        let contest_id = Uuid::nil();
+        let division_id = Uuid::from_u128(7);
+        let cutoff = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+        assert_eq!(
+            scoreboard_cache_key(contest_id, 3, "entries", None, None),
+            format!("scoreboard:contest:{contest_id}:cache:v3:entries:live:all")
+        );
+        assert_eq!(
+            scoreboard_cache_key(contest_id, 4, "entries", Some(cutoff), Some(division_id)),
+            format!("scoreboard:contest:{contest_id}:cache:v4:entries:1700000000000:{division_id}")
+        );
+    }
+}
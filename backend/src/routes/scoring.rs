@@ -0,0 +1,595 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    routes::{
+        attack_defense::{load_attack_defense_adjustments, CONTEST_TYPE_ATTACK_DEFENSE},
+        koth::load_koth_adjustments,
+    },
+    state::AppState,
+};
+
+const MAX_DIFFICULTY_SCORE: i32 = 100_000;
+
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct ContestScoringConfig {
+    pub contest_type: String,
+    pub scoring_mode: String,
+    pub scoring_formula: String,
+    pub dynamic_decay: i32,
+    #[sqlx(json)]
+    pub difficulty_scores: DifficultyScores,
+    pub first_blood_bonus_percent: i32,
+    pub second_blood_bonus_percent: i32,
+    pub third_blood_bonus_percent: i32,
+    pub score_floor: i32,
+}
+
+/// Points per challenge difficulty, stored in `contests.difficulty_scores`
+/// and used when `scoring_mode` is `difficulty`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DifficultyScores {
+    #[serde(default = "default_easy_score")]
+    pub easy: i32,
+    #[serde(default = "default_normal_score")]
+    pub normal: i32,
+    #[serde(default = "default_hard_score")]
+    pub hard: i32,
+    #[serde(default = "default_insane_score")]
+    pub insane: i32,
+}
+
+impl Default for DifficultyScores {
+    fn default() -> Self {
+        Self {
+            easy: default_easy_score(),
+            normal: default_normal_score(),
+            hard: default_hard_score(),
+            insane: default_insane_score(),
+        }
+    }
+}
+
+impl DifficultyScores {
+    pub(crate) fn score_for(&self, difficulty: &str) -> i32 {
+        match difficulty {
+            "easy" => self.easy,
+            "hard" => self.hard,
+            "insane" => self.insane,
+            _ => self.normal,
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let items = [
+            ("easy", self.easy),
+            ("normal", self.normal),
+            ("hard", self.hard),
+            ("insane", self.insane),
+        ];
+        for (difficulty, score) in items {
+            if !(0..=MAX_DIFFICULTY_SCORE).contains(&score) {
+                return Err(format!(
+                    "difficulty_scores.{} must be between 0 and {}",
+                    difficulty, MAX_DIFFICULTY_SCORE
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn to_value(self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_else(|_| serde_json::json!({}))
+    }
+}
+
+fn default_easy_score() -> i32 {
+    100
+}
+
+fn default_normal_score() -> i32 {
+    200
+}
+
+fn default_hard_score() -> i32 {
+    300
+}
+
+fn default_insane_score() -> i32 {
+    500
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChallengeScoreBounds<'a> {
+    pub static_score: i32,
+    pub min_score: i32,
+    pub max_score: i32,
+    pub difficulty: &'a str,
+}
+
+/// First accepted submission of a team for a challenge, or for one stage of
+/// a multi-stage challenge when `stage_key` is set.
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct SolveRecord {
+    pub submission_id: i64,
+    pub team_id: Uuid,
+    pub team_name: String,
+    pub user_id: Uuid,
+    pub username: String,
+    pub challenge_id: Uuid,
+    pub challenge_title: String,
+    pub challenge_slug: String,
+    pub challenge_category: String,
+    pub challenge_difficulty: String,
+    pub static_score: i32,
+    pub min_score: i32,
+    pub max_score: i32,
+    pub blood_bonus: i32,
+    pub stage_key: Option<String>,
+    pub stage_title: Option<String>,
+    pub stage_points: i32,
+    pub stage_count: i32,
+    pub submitted_at: DateTime<Utc>,
+}
+
+impl SolveRecord {
+    pub(crate) fn bounds(&self) -> ChallengeScoreBounds<'_> {
+        ChallengeScoreBounds {
+            static_score: self.static_score,
+            min_score: self.min_score,
+            max_score: self.max_score,
+            difficulty: &self.challenge_difficulty,
+        }
+    }
+}
+
+/// Points added to or taken from a team outside of solves, e.g. the cost of
+/// an unlocked hint or a wrong-flag penalty. Deductions carry negative
+/// `points`. `source` names where the points come from: `hint`, `penalty`,
+/// `admin` or `tick` (periodic KOTH/AD round awards).
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct ScoreAdjustment {
+    pub team_id: Uuid,
+    pub team_name: String,
+    pub points: i64,
+    pub occurred_at: DateTime<Utc>,
+    pub source: String,
+}
+
+const ADJUSTMENT_SOURCE_ADMIN: &str = "admin";
+const ADJUSTMENT_SOURCE_TICK: &str = "tick";
+
+impl ScoreAdjustment {
+    /// Periodic round awards and admin adjustments get their own timeline
+    /// snapshot; the rest are folded into the next solve snapshot.
+    pub fn has_own_snapshot(&self) -> bool {
+        matches!(
+            self.source.as_str(),
+            ADJUSTMENT_SOURCE_TICK | ADJUSTMENT_SOURCE_ADMIN
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ScoredSolve {
+    pub record: SolveRecord,
+    pub points: i64,
+}
+
+/// Solves and score adjustments of a contest, each ordered by time, with the
+/// scoring config needed to value them.
+#[derive(Debug, Clone)]
+pub(crate) struct ContestSolves {
+    pub config: ContestScoringConfig,
+    pub solves: Vec<SolveRecord>,
+    pub adjustments: Vec<ScoreAdjustment>,
+}
+
+impl ContestSolves {
+    /// Values every solve against the current solve count of its challenge,
+    /// so under dynamic scoring early solvers decay together with later ones.
+    /// Blood bonuses are fixed at solve time and added on top. Stage solves
+    /// are worth their stage points and do not take part in the decay.
+    pub(crate) fn scored(&self) -> Vec<ScoredSolve> {
+        let mut solve_counts: HashMap<Uuid, i64> = HashMap::new();
+        for solve in self.solves.iter().filter(|solve| solve.stage_key.is_none()) {
+            *solve_counts.entry(solve.challenge_id).or_insert(0) += 1;
+        }
+
+        self.solves
+            .iter()
+            .map(|solve| {
+                if solve.stage_key.is_some() {
+                    return ScoredSolve {
+                        record: solve.clone(),
+                        points: solve.stage_points.max(0) as i64,
+                    };
+                }
+
+                let solve_count = solve_counts
+                    .get(&solve.challenge_id)
+                    .copied()
+                    .unwrap_or(1);
+                let value = challenge_value(&self.config, solve.bounds(), solve_count);
+                ScoredSolve {
+                    record: solve.clone(),
+                    points: value + solve.blood_bonus.max(0) as i64,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Keeps deductions (hint costs, wrong-flag penalties, negative adjustments)
+/// from taking a team's `total` below the contest's score floor. `deducted`
+/// is the sum of those deductions as a positive number; a team that never
+/// gained the floor keeps what it gained.
+pub(crate) fn apply_score_floor(score_floor: i32, total: i64, deducted: i64) -> i64 {
+    let gained = total + deducted;
+    total.max(gained.min(i64::from(score_floor)))
+}
+
+/// Tracks when a subject (team or player) finishes a challenge. Plain
+/// challenges finish on their solve; multi-stage challenges once every stage
+/// has been solved.
+#[derive(Debug, Default)]
+pub(crate) struct ChallengeCompletionTracker {
+    stages_solved: HashMap<(Uuid, Uuid), i32>,
+    completed: HashSet<(Uuid, Uuid)>,
+}
+
+impl ChallengeCompletionTracker {
+    /// Records `solve` for `subject_id` and returns whether it completed the
+    /// challenge just now.
+    pub(crate) fn record(&mut self, subject_id: Uuid, solve: &SolveRecord) -> bool {
+        let key = (subject_id, solve.challenge_id);
+        if self.completed.contains(&key) {
+            return false;
+        }
+
+        if solve.stage_key.is_some() {
+            let solved = self.stages_solved.entry(key).or_insert(0);
+            *solved += 1;
+            if *solved < solve.stage_count {
+                return false;
+            }
+        }
+
+        self.completed.insert(key);
+        true
+    }
+}
+
+/// Value of a challenge once `solve_count` teams have solved it.
+pub(crate) fn challenge_value(
+    config: &ContestScoringConfig,
+    bounds: ChallengeScoreBounds<'_>,
+    solve_count: i64,
+) -> i64 {
+    match config.scoring_mode.as_str() {
+        "dynamic" => dynamic_value(config, bounds, solve_count),
+        "difficulty" => config.difficulty_scores.score_for(bounds.difficulty).max(0) as i64,
+        _ => bounds.static_score.max(0) as i64,
+    }
+}
+
+/// Decays from `max_score` towards `min_score` as solves come in. The first
+/// solver always sees `max_score`; `dynamic_decay` sets how fast the curve
+/// falls:
+/// - `hyperbolic`: loses half of the range after `decay` further solves.
+/// - `logarithmic`: CTFd's parabola, reaching `min_score` after `decay` further
+///   solves.
+/// - `linear`: loses `decay` points per further solve.
+fn dynamic_value(
+    config: &ContestScoringConfig,
+    bounds: ChallengeScoreBounds<'_>,
+    solve_count: i64,
+) -> i64 {
+    let min_score = bounds.min_score.max(0);
+    let max_score = bounds.max_score.max(min_score);
+    if max_score == min_score {
+        return max_score as i64;
+    }
+
+    let decay = config.dynamic_decay.max(1) as f64;
+    let solves = (solve_count - 1).max(0) as f64;
+    let range = (max_score - min_score) as f64;
+    let raw = match config.scoring_formula.as_str() {
+        "logarithmic" => max_score as f64 - range * (solves.min(decay) / decay).powi(2),
+        "linear" => max_score as f64 - decay * solves,
+        _ => min_score as f64 + range * (decay / (decay + solves)),
+    };
+    raw.round().clamp(min_score as f64, max_score as f64) as i64
+}
+
+/// Bonus for the `position`-th solver (0-based) on top of `base_score`.
+pub(crate) fn blood_bonus(config: &ContestScoringConfig, base_score: i64, position: i64) -> i64 {
+    let bonus_percent = match position {
+        0 => config.first_blood_bonus_percent,
+        1 => config.second_blood_bonus_percent,
+        2 => config.third_blood_bonus_percent,
+        _ => 0,
+    }
+    .clamp(0, 500);
+
+    if bonus_percent == 0 || base_score <= 0 {
+        return 0;
+    }
+
+    ((base_score as f64) * (bonus_percent as f64 / 100.0)).round() as i64
+}
+
+pub(crate) async fn load_contest_scoring_config(
+    state: &AppState,
+    contest_id: Uuid,
+) -> AppResult<ContestScoringConfig> {
+    sqlx::query_as::<_, ContestScoringConfig>(
+        "SELECT contest_type,
+                scoring_mode,
+                scoring_formula,
+                dynamic_decay,
+                difficulty_scores,
+                first_blood_bonus_percent,
+                second_blood_bonus_percent,
+                third_blood_bonus_percent,
+                score_floor
+         FROM contests
+         WHERE id = $1
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)?
+    .ok_or(AppError::BadRequest("contest not found".to_string()))
+}
+
+/// Loads the solves visible before `cutoff` (all solves when `None`).
+pub(crate) async fn load_contest_solves(
+    state: &AppState,
+    contest_id: Uuid,
+    cutoff: Option<DateTime<Utc>>,
+) -> AppResult<ContestSolves> {
+    let config = load_contest_scoring_config(state, contest_id).await?;
+
+    // Attack-defense contests are scored per round; their accepted
+    // submissions are stolen flags, not solves.
+    if config.contest_type == CONTEST_TYPE_ATTACK_DEFENSE {
+        let mut adjustments = load_score_adjustments(state, contest_id, cutoff).await?;
+        adjustments.extend(load_attack_defense_adjustments(state, contest_id, cutoff).await?);
+        adjustments.sort_by_key(|item| item.occurred_at);
+        return Ok(ContestSolves {
+            config,
+            solves: Vec::new(),
+            adjustments,
+        });
+    }
+
+    let mut solves = sqlx::query_as::<_, SolveRecord>(
+        "SELECT DISTINCT ON (s.team_id, s.challenge_id, s.stage_key)
+                s.id AS submission_id,
+                s.team_id,
+                t.name AS team_name,
+                s.user_id,
+                u.username,
+                c.id AS challenge_id,
+                c.title AS challenge_title,
+                c.slug AS challenge_slug,
+                c.category AS challenge_category,
+                c.difficulty AS challenge_difficulty,
+                c.static_score,
+                c.min_score,
+                c.max_score,
+                s.blood_bonus,
+                s.stage_key,
+                st.stage->>'title' AS stage_title,
+                CASE
+                    WHEN s.stage_key IS NULL THEN 0
+                    ELSE COALESCE((st.stage->>'points')::int, s.score_awarded)
+                END AS stage_points,
+                jsonb_array_length(c.stages) AS stage_count,
+                s.submitted_at
+         FROM submissions s
+         JOIN teams t ON t.id = s.team_id
+         JOIN users u ON u.id = s.user_id
+         JOIN challenges c ON c.id = s.challenge_id
+         LEFT JOIN LATERAL (
+             SELECT item AS stage
+             FROM jsonb_array_elements(c.stages) item
+             WHERE item->>'key' = s.stage_key
+             LIMIT 1
+         ) st ON TRUE
+         WHERE s.contest_id = $1
+           AND s.verdict = 'accepted'
+           AND ($2::timestamptz IS NULL OR s.submitted_at < $2)
+         ORDER BY s.team_id, s.challenge_id, s.stage_key, s.submitted_at ASC, s.id ASC",
+    )
+    .bind(contest_id)
+    .bind(cutoff)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    solves.sort_by(|lhs, rhs| {
+        lhs.submitted_at
+            .cmp(&rhs.submitted_at)
+            .then_with(|| lhs.submission_id.cmp(&rhs.submission_id))
+    });
+
+    let adjustments = load_score_adjustments(state, contest_id, cutoff).await?;
+
+    Ok(ContestSolves {
+        config,
+        solves,
+        adjustments,
+    })
+}
+
+async fn load_score_adjustments(
+    state: &AppState,
+    contest_id: Uuid,
+    cutoff: Option<DateTime<Utc>>,
+) -> AppResult<Vec<ScoreAdjustment>> {
+    let mut adjustments = sqlx::query_as::<_, ScoreAdjustment>(
+        "SELECT u.team_id,
+                t.name AS team_name,
+                -(u.cost::bigint) AS points,
+                u.unlocked_at AS occurred_at,
+                'hint' AS source
+         FROM challenge_hint_unlocks u
+         JOIN teams t ON t.id = u.team_id
+         WHERE u.contest_id = $1
+           AND u.cost > 0
+           AND ($2::timestamptz IS NULL OR u.unlocked_at < $2)
+         ORDER BY u.unlocked_at ASC, u.id ASC",
+    )
+    .bind(contest_id)
+    .bind(cutoff)
+    .fetch_all(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    adjustments.extend(
+        sqlx::query_as::<_, ScoreAdjustment>(
+            "SELECT a.team_id,
+                    t.name AS team_name,
+                    a.points::bigint AS points,
+                    a.created_at AS occurred_at,
+                    'admin' AS source
+             FROM contest_score_adjustments a
+             JOIN teams t ON t.id = a.team_id
+             WHERE a.contest_id = $1
+               AND ($2::timestamptz IS NULL OR a.created_at < $2)
+             ORDER BY a.created_at ASC, a.id ASC",
+        )
+        .bind(contest_id)
+        .bind(cutoff)
+        .fetch_all(&state.db)
+        .await
+        .map_err(AppError::internal)?,
+    );
+    adjustments.extend(
+        sqlx::query_as::<_, ScoreAdjustment>(
+            "SELECT s.team_id,
+                    t.name AS team_name,
+                    -(s.penalty_points::bigint) AS points,
+                    s.submitted_at AS occurred_at,
+                    'penalty' AS source
+             FROM submissions s
+             JOIN teams t ON t.id = s.team_id
+             WHERE s.contest_id = $1
+               AND s.penalty_points > 0
+               AND ($2::timestamptz IS NULL OR s.submitted_at < $2)
+             ORDER BY s.submitted_at ASC, s.id ASC",
+        )
+        .bind(contest_id)
+        .bind(cutoff)
+        .fetch_all(&state.db)
+        .await
+        .map_err(AppError::internal)?,
+    );
+    adjustments.extend(load_koth_adjustments(state, contest_id, cutoff).await?);
+    adjustments.sort_by_key(|item| item.occurred_at);
+    Ok(adjustments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        apply_score_floor, blood_bonus, challenge_value, ChallengeScoreBounds,
+        ContestScoringConfig, DifficultyScores,
+    };
+
+    fn config(mode: &str) -> ContestScoringConfig {
+        formula_config(mode, "hyperbolic")
+    }
+
+    fn formula_config(mode: &str, formula: &str) -> ContestScoringConfig {
+        ContestScoringConfig {
+            contest_type: "jeopardy".to_string(),
+            scoring_mode: mode.to_string(),
+            scoring_formula: formula.to_string(),
+            dynamic_decay: 20,
+            difficulty_scores: DifficultyScores::default(),
+            first_blood_bonus_percent: 10,
+            second_blood_bonus_percent: 5,
+            third_blood_bonus_percent: 2,
+            score_floor: 0,
+        }
+    }
+
+    const BOUNDS: ChallengeScoreBounds<'static> = ChallengeScoreBounds {
+        static_score: 100,
+        min_score: 50,
+        max_score: 500,
+        difficulty: "hard",
+    };
+
+    #[test]
+    fn static_mode_ignores_solve_count() {
+        let cfg = config("static");
+        assert_eq!(challenge_value(&cfg, BOUNDS, 1), 100);
+        assert_eq!(challenge_value(&cfg, BOUNDS, 40), 100);
+    }
+
+    #[test]
+    fn dynamic_value_decays_with_solves() {
+        let cfg = config("dynamic");
+        assert_eq!(challenge_value(&cfg, BOUNDS, 0), 500);
+        assert_eq!(challenge_value(&cfg, BOUNDS, 1), 500);
+        assert_eq!(challenge_value(&cfg, BOUNDS, 21), 275);
+        assert!(challenge_value(&cfg, BOUNDS, 100_000) >= 50);
+    }
+
+    #[test]
+    fn alternative_curves_reach_the_floor() {
+        let cfg = formula_config("dynamic", "logarithmic");
+        assert_eq!(challenge_value(&cfg, BOUNDS, 1), 500);
+        assert_eq!(challenge_value(&cfg, BOUNDS, 11), 388);
+        assert_eq!(challenge_value(&cfg, BOUNDS, 21), 50);
+        assert_eq!(challenge_value(&cfg, BOUNDS, 500), 50);
+
+        let cfg = formula_config("dynamic", "linear");
+        assert_eq!(challenge_value(&cfg, BOUNDS, 1), 500);
+        assert_eq!(challenge_value(&cfg, BOUNDS, 11), 300);
+        assert_eq!(challenge_value(&cfg, BOUNDS, 40), 50);
+    }
+
+    #[test]
+    fn difficulty_mode_uses_contest_table() {
+        let mut cfg = config("difficulty");
+        assert_eq!(challenge_value(&cfg, BOUNDS, 1), 300);
+        cfg.difficulty_scores.hard = 350;
+        assert_eq!(challenge_value(&cfg, BOUNDS, 30), 350);
+        assert!(DifficultyScores {
+            insane: -1,
+            ..DifficultyScores::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn blood_bonus_only_for_first_three() {
+        let cfg = config("dynamic");
+        assert_eq!(blood_bonus(&cfg, 500, 0), 50);
+        assert_eq!(blood_bonus(&cfg, 500, 1), 25);
+        assert_eq!(blood_bonus(&cfg, 500, 2), 10);
+        assert_eq!(blood_bonus(&cfg, 500, 3), 0);
+    }
+
+    #[test]
+    fn score_floor_only_absorbs_deductions() {
+        assert_eq!(apply_score_floor(0, 300, 50), 300);
+        assert_eq!(apply_score_floor(0, -40, 140), 0);
+        assert_eq!(apply_score_floor(-100, -150, 200), -100);
+        assert_eq!(apply_score_floor(100, 20, 480), 100);
+        assert_eq!(apply_score_floor(100, 30, 20), 50);
+        assert_eq!(apply_score_floor(100, 0, 0), 0);
+    }
+}
@@ -1,7 +1,16 @@
-use std::{process::Stdio, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    process::Stdio,
+    sync::Arc,
+};
 
 use argon2::{password_hash::PasswordHash, Argon2, PasswordVerifier};
-use axum::{extract::State, routing::post, Json, Router};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue},
+    routing::post,
+    Json, Router,
+};
 use chrono::{DateTime, Utc};
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
@@ -16,13 +25,33 @@ use uuid::Uuid;
 
 use crate::{
     auth::AuthenticatedUser,
+    challenge_stages::{match_challenge_stage, parse_challenge_stages, ChallengeStage},
     error::{AppError, AppResult},
-    routes::contest_access::ensure_team_contest_workspace_access,
+    flag_rules::{matches_any_flag_rule, parse_flag_rules},
+    routes::{
+        attack_defense::{capture_attack_flag, AttackCapture, CONTEST_TYPE_ATTACK_DEFENSE},
+        challenge_unlocks::ensure_challenge_unlocked,
+        contest_access::{
+            ensure_team_contest_workspace_access, is_privileged_role, resolve_contest_team_id,
+        },
+        dynamic_flags::{find_dynamic_flag_owner, load_dynamic_flag, DynamicFlagOwnerRow},
+        koth::CHALLENGE_TYPE_KOTH,
+        scoreboard::{
+            load_team_total, publish_scoreboard_solve, publish_scoreboard_update, ScoreboardSolve,
+            SCOREBOARD_EVENT_UPDATE,
+        },
+        scoring::{blood_bonus, challenge_value, ChallengeScoreBounds, ContestScoringConfig},
+        webhook_verifier::{
+            parse_webhook_verifier_config, run_webhook_verifier, WebhookVerdict,
+            WebhookVerifyRequest,
+        },
+    },
     state::AppState,
+    submission_limits::{parse_challenge_rate_limit, wrong_backoff_seconds},
 };
 
-const SUBMISSION_RATE_WINDOW_SECS: i64 = 30;
-const SUBMISSION_RATE_MAX_COUNT: i64 = 10;
+/// How long a team's wrong-answer streak on a challenge is remembered.
+const WRONG_STREAK_TTL_SECS: i64 = 3600;
 
 #[derive(Debug, Deserialize)]
 struct SubmitFlagRequest {
@@ -37,12 +66,10 @@ struct SubmitFlagResponse {
     score_awarded: i32,
     total_score: i64,
     message: String,
+    stage_key: Option<String>,
     submitted_at: DateTime<Utc>,
-}
-
-#[derive(Debug, FromRow)]
-struct TeamMembershipRow {
-    team_id: Uuid,
+    penalty_points: i32,
+    retry_after_seconds: Option<i64>,
 }
 
 #[derive(Debug, FromRow)]
@@ -50,26 +77,148 @@ struct JudgeContextRow {
     contest_status: String,
     contest_start_at: DateTime<Utc>,
     contest_end_at: DateTime<Utc>,
+    contest_type: String,
     contest_scoring_mode: String,
+    contest_scoring_formula: String,
     contest_dynamic_decay: i32,
+    contest_difficulty_scores: Value,
     contest_first_blood_bonus_percent: i32,
     contest_second_blood_bonus_percent: i32,
     contest_third_blood_bonus_percent: i32,
+    contest_submission_rate_window_seconds: i32,
+    contest_submission_rate_user_max: i32,
+    contest_submission_rate_team_max: i32,
+    contest_wrong_backoff_threshold: i32,
+    contest_wrong_backoff_base_seconds: i32,
+    contest_wrong_backoff_max_seconds: i32,
+    contest_wrong_submission_penalty: i32,
+    contest_score_floor: i32,
+    challenge_title: String,
+    challenge_type: String,
     flag_mode: String,
     flag_hash: String,
+    flag_rules: Value,
+    stages: Value,
     static_score: i32,
     min_score: i32,
     max_score: i32,
+    difficulty: String,
     is_visible: bool,
     release_at: Option<DateTime<Utc>>,
+    rate_limit: Value,
+    wrong_submission_penalty: Option<i32>,
     metadata: Value,
 }
 
+/// A flag to judge, as passed on to the verifiers.
+#[derive(Debug, Clone, Copy)]
+struct FlagAttempt<'a> {
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Uuid,
+    flag: &'a str,
+    /// Set when re-judging without applying, so verifiers with side effects
+    /// can tell the call is only a preview.
+    dry_run: bool,
+}
+
+/// A submission turned away before judging, with how long to wait.
+#[derive(Debug)]
+struct RateLimitHit {
+    message: String,
+    retry_after_seconds: i64,
+}
+
 #[derive(Debug, FromRow)]
-struct SubmittedAtRow {
+struct PriorSolves {
+    team_solved: bool,
+    team_stage_keys: Vec<String>,
+    /// Number of teams with an accepted submission on the challenge.
+    solver_count: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct InsertedSubmissionRow {
+    id: i64,
     submitted_at: DateTime<Utc>,
 }
 
+#[derive(Debug, FromRow)]
+struct RejudgeSubmissionRow {
+    id: i64,
+    team_id: Uuid,
+    team_name: String,
+    username: String,
+    submitted_flag: String,
+    verdict: String,
+    score_awarded: i32,
+    blood_bonus: i32,
+    penalty_points: i32,
+    stage_key: Option<String>,
+    submitted_at: DateTime<Utc>,
+}
+
+/// A submission whose outcome differs when judged again.
+#[derive(Debug, Serialize)]
+pub(crate) struct RejudgeChangeItem {
+    pub submission_id: i64,
+    pub challenge_id: Uuid,
+    pub challenge_title: String,
+    pub team_id: Uuid,
+    pub team_name: String,
+    pub username: String,
+    pub submitted_at: DateTime<Utc>,
+    pub old_verdict: String,
+    pub new_verdict: String,
+    pub old_score_awarded: i32,
+    pub new_score_awarded: i32,
+    pub old_penalty_points: i32,
+    pub new_penalty_points: i32,
+    pub old_stage_key: Option<String>,
+    pub new_stage_key: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RejudgeReport {
+    pub contest_id: Uuid,
+    pub challenge_id: Option<Uuid>,
+    pub applied: bool,
+    pub scanned: i64,
+    pub changed: i64,
+    pub items: Vec<RejudgeChangeItem>,
+}
+
+/// Solves accumulated while replaying a challenge's submissions in order.
+#[derive(Debug, Default)]
+struct ReplayedSolves {
+    solved_teams: HashSet<Uuid>,
+    stage_keys: HashMap<Uuid, Vec<String>>,
+}
+
+impl ReplayedSolves {
+    fn prior(&self, team_id: Uuid) -> PriorSolves {
+        PriorSolves {
+            team_solved: self.solved_teams.contains(&team_id),
+            team_stage_keys: self.stage_keys.get(&team_id).cloned().unwrap_or_default(),
+            solver_count: self.solved_teams.len() as i64,
+        }
+    }
+
+    fn record(&mut self, team_id: Uuid, outcome: &JudgeOutcome) {
+        if outcome.verdict != "accepted" {
+            return;
+        }
+        self.solved_teams.insert(team_id);
+        if let Some(stage_key) = outcome.stage_key.as_ref() {
+            let keys = self.stage_keys.entry(team_id).or_default();
+            if !keys.contains(stage_key) {
+                keys.push(stage_key.clone());
+            }
+        }
+    }
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new().route("/submissions", post(submit_flag))
 }
@@ -78,7 +227,7 @@ async fn submit_flag(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
     Json(req): Json<SubmitFlagRequest>,
-) -> AppResult<Json<SubmitFlagResponse>> {
+) -> AppResult<(HeaderMap, Json<SubmitFlagResponse>)> {
     info!(
         user_id = %current_user.user_id,
         user_role = %current_user.role,
@@ -98,82 +247,42 @@ async fn submit_flag(
         return Err(AppError::BadRequest("flag is required".to_string()));
     }
 
-    let membership = sqlx::query_as::<_, TeamMembershipRow>(
-        "SELECT team_id FROM team_members WHERE user_id = $1 LIMIT 1",
-    )
-    .bind(current_user.user_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(AppError::internal)?;
-
-    let membership = match membership {
-        Some(row) => row,
-        None => {
-            warn!(
-                user_id = %current_user.user_id,
-                user_role = %current_user.role,
-                contest_id = %req.contest_id,
-                challenge_id = %req.challenge_id,
-                "submission denied: user has no team membership"
-            );
-            return Err(AppError::BadRequest(
-                "join or create a team before entering the contest".to_string(),
-            ));
-        }
-    };
+    let team_id =
+        match resolve_contest_team_id(state.as_ref(), req.contest_id, current_user.user_id).await {
+            Ok(team_id) => team_id,
+            Err(err) => {
+                warn!(
+                    user_id = %current_user.user_id,
+                    user_role = %current_user.role,
+                    contest_id = %req.contest_id,
+                    challenge_id = %req.challenge_id,
+                    "submission denied: user has no team in this contest"
+                );
+                return Err(err);
+            }
+        };
 
     info!(
         user_id = %current_user.user_id,
-        team_id = %membership.team_id,
+        team_id = %team_id,
         contest_id = %req.contest_id,
         challenge_id = %req.challenge_id,
         "submission team membership resolved"
     );
 
-    ensure_team_contest_workspace_access(
-        state.as_ref(),
-        req.contest_id,
-        membership.team_id,
-        &current_user,
-    )
-    .await?;
+    ensure_team_contest_workspace_access(state.as_ref(), req.contest_id, team_id, &current_user)
+        .await?;
 
-    let judge_ctx = sqlx::query_as::<_, JudgeContextRow>(
-        "SELECT ct.status AS contest_status,
-                ct.start_at AS contest_start_at,
-                ct.end_at AS contest_end_at,
-                ct.scoring_mode AS contest_scoring_mode,
-                ct.dynamic_decay AS contest_dynamic_decay,
-                ct.first_blood_bonus_percent AS contest_first_blood_bonus_percent,
-                ct.second_blood_bonus_percent AS contest_second_blood_bonus_percent,
-                ct.third_blood_bonus_percent AS contest_third_blood_bonus_percent,
-                c.flag_mode,
-                c.flag_hash,
-                c.static_score,
-                c.min_score,
-                c.max_score,
-                c.is_visible,
-                cc.release_at,
-                c.metadata
-         FROM contest_challenges cc
-         JOIN contests ct ON ct.id = cc.contest_id
-         JOIN challenges c ON c.id = cc.challenge_id
-         WHERE cc.contest_id = $1 AND cc.challenge_id = $2
-         LIMIT 1",
-    )
-    .bind(req.contest_id)
-    .bind(req.challenge_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(AppError::internal)?
-    .ok_or(AppError::BadRequest(
-        "challenge is not available in this contest".to_string(),
-    ))?;
+    let judge_ctx = load_judge_context(state.as_ref(), req.contest_id, req.challenge_id)
+        .await?
+        .ok_or(AppError::BadRequest(
+            "challenge is not available in this contest".to_string(),
+        ))?;
 
     if let Err(err) = validate_submission_window(&judge_ctx) {
         warn!(
             user_id = %current_user.user_id,
-            team_id = %membership.team_id,
+            team_id = %team_id,
             contest_id = %req.contest_id,
             challenge_id = %req.challenge_id,
             contest_status = %judge_ctx.contest_status,
@@ -185,140 +294,377 @@ async fn submit_flag(
         return Err(err);
     }
 
-    if let Err(err) =
-        enforce_submission_rate_limit(state.as_ref(), current_user.user_id, req.contest_id).await
-    {
-        if let AppError::TooManyRequests(message) = err {
+    if !is_privileged_role(&current_user.role) {
+        if let Err(err) =
+            ensure_challenge_unlocked(state.as_ref(), req.contest_id, req.challenge_id, team_id)
+                .await
+        {
             warn!(
                 user_id = %current_user.user_id,
-                team_id = %membership.team_id,
+                team_id = %team_id,
                 contest_id = %req.contest_id,
                 challenge_id = %req.challenge_id,
-                message = %message,
-                "submission rate limited"
+                error = %err,
+                "submission rejected: challenge prerequisites not met"
             );
-            let submitted_at = insert_submission(
-                state.as_ref(),
-                SubmissionInsertParams {
-                    contest_id: req.contest_id,
-                    challenge_id: req.challenge_id,
-                    team_id: membership.team_id,
-                    user_id: current_user.user_id,
-                    submitted_flag,
-                    verdict: "rate_limited",
-                    score_awarded: 0,
-                    message: &message,
-                },
-            )
-            .await?;
-
-            let total_score =
-                fetch_total_score(state.as_ref(), req.contest_id, membership.team_id).await?;
-            publish_scoreboard_update(state.as_ref(), req.contest_id).await;
+            return Err(err);
+        }
+    }
 
-            return Ok(Json(SubmitFlagResponse {
+    if let Some(hit) = enforce_submission_rate_limit(
+        state.as_ref(),
+        &judge_ctx,
+        req.contest_id,
+        req.challenge_id,
+        team_id,
+        current_user.user_id,
+    )
+    .await?
+    {
+        warn!(
+            user_id = %current_user.user_id,
+            team_id = %team_id,
+            contest_id = %req.contest_id,
+            challenge_id = %req.challenge_id,
+            message = %hit.message,
+            retry_after_seconds = hit.retry_after_seconds,
+            "submission rate limited"
+        );
+        let inserted = insert_submission(
+            state.as_ref(),
+            SubmissionInsertParams {
+                contest_id: req.contest_id,
+                challenge_id: req.challenge_id,
+                team_id,
+                user_id: current_user.user_id,
+                submitted_flag,
+                verdict: "rate_limited",
+                score_awarded: 0,
+                blood_bonus: 0,
+                penalty_points: 0,
+                stage_key: None,
+                message: &hit.message,
+            },
+        )
+        .await?;
+
+        let total_score = load_team_total(state.as_ref(), req.contest_id, team_id).await?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::RETRY_AFTER,
+            HeaderValue::from(hit.retry_after_seconds),
+        );
+        return Ok((
+            headers,
+            Json(SubmitFlagResponse {
                 verdict: "rate_limited".to_string(),
                 score_awarded: 0,
                 total_score,
-                message,
-                submitted_at,
-            }));
-        }
-
-        return Err(err);
+                message: hit.message,
+                stage_key: None,
+                submitted_at: inserted.submitted_at,
+                penalty_points: 0,
+                retry_after_seconds: Some(hit.retry_after_seconds),
+            }),
+        ));
     }
 
-    let already_solved = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(
-            SELECT 1 FROM submissions
-            WHERE contest_id = $1
-              AND challenge_id = $2
-              AND team_id = $3
-              AND verdict = 'accepted'
-         )",
+    let prior =
+        load_prior_solves(state.as_ref(), req.contest_id, req.challenge_id, team_id).await?;
+
+    let outcome = judge_flag(
+        state.as_ref(),
+        &judge_ctx,
+        FlagAttempt {
+            contest_id: req.contest_id,
+            challenge_id: req.challenge_id,
+            team_id,
+            flag: submitted_flag,
+            dry_run: false,
+        },
+        &prior,
     )
-    .bind(req.contest_id)
-    .bind(req.challenge_id)
-    .bind(membership.team_id)
-    .fetch_one(&state.db)
-    .await
-    .map_err(AppError::internal)?;
+    .await?;
 
-    let (verdict, score_awarded, message) = judge_flag(
+    record_wrong_streak(
         state.as_ref(),
         &judge_ctx,
         req.contest_id,
         req.challenge_id,
-        membership.team_id,
-        submitted_flag,
-        already_solved,
+        team_id,
+        &outcome.verdict,
     )
     .await?;
 
-    let submitted_at = insert_submission(
+    let penalty_points = if outcome.verdict == "wrong" {
+        wrong_submission_penalty(&judge_ctx)
+    } else {
+        0
+    };
+
+    let inserted = insert_submission(
         state.as_ref(),
         SubmissionInsertParams {
             contest_id: req.contest_id,
             challenge_id: req.challenge_id,
-            team_id: membership.team_id,
+            team_id,
             user_id: current_user.user_id,
             submitted_flag,
-            verdict: &verdict,
-            score_awarded,
-            message: &message,
+            verdict: &outcome.verdict,
+            score_awarded: outcome.score_awarded,
+            blood_bonus: outcome.blood_bonus,
+            penalty_points,
+            stage_key: outcome.stage_key.as_deref(),
+            message: &outcome.message,
         },
     )
     .await?;
 
-    let total_score = fetch_total_score(state.as_ref(), req.contest_id, membership.team_id).await?;
-    publish_scoreboard_update(state.as_ref(), req.contest_id).await;
+    if let Some(owner) = outcome.shared_flag_owner.as_ref() {
+        record_flag_sharing_incident(
+            state.as_ref(),
+            req.contest_id,
+            req.challenge_id,
+            team_id,
+            current_user.user_id,
+            owner,
+            &inserted,
+        )
+        .await?;
+    }
+
+    // Only submissions that move the standings are pushed to the scoreboard.
+    if is_new_solve(&judge_ctx, &prior, &outcome) {
+        let solve = ScoreboardSolve {
+            team_id,
+            challenge_id: req.challenge_id,
+            challenge_title: judge_ctx.challenge_title.clone(),
+            stage_key: outcome.stage_key.clone(),
+            points: i64::from(outcome.score_awarded),
+            first_blood: outcome.stage_key.is_none()
+                && judge_ctx.contest_type != CONTEST_TYPE_ATTACK_DEFENSE
+                && prior.solver_count == 0,
+            submitted_at: inserted.submitted_at,
+        };
+        publish_scoreboard_solve(state.as_ref(), req.contest_id, solve).await;
+    } else if penalty_points > 0 {
+        publish_scoreboard_update(state.as_ref(), req.contest_id, SCOREBOARD_EVENT_UPDATE).await;
+    }
+    // Read after publishing: a change has just rebuilt the cached standings.
+    let total_score = load_team_total(state.as_ref(), req.contest_id, team_id).await?;
 
     info!(
         user_id = %current_user.user_id,
-        team_id = %membership.team_id,
+        team_id = %team_id,
         contest_id = %req.contest_id,
         challenge_id = %req.challenge_id,
-        verdict = %verdict,
-        score_awarded,
+        verdict = %outcome.verdict,
+        stage_key = ?outcome.stage_key,
+        score_awarded = outcome.score_awarded,
+        penalty_points,
         total_score,
         "submission judged"
     );
 
-    Ok(Json(SubmitFlagResponse {
-        verdict,
-        score_awarded,
-        total_score,
-        message,
-        submitted_at,
-    }))
+    Ok((
+        HeaderMap::new(),
+        Json(SubmitFlagResponse {
+            verdict: outcome.verdict,
+            score_awarded: outcome.score_awarded,
+            total_score,
+            message: outcome.message,
+            stage_key: outcome.stage_key,
+            submitted_at: inserted.submitted_at,
+            penalty_points,
+            retry_after_seconds: None,
+        }),
+    ))
+}
+
+/// Whether an accepted submission credits something the team did not have:
+/// a fresh capture in attack-defense, a new stage, or the challenge itself.
+fn is_new_solve(ctx: &JudgeContextRow, prior: &PriorSolves, outcome: &JudgeOutcome) -> bool {
+    if outcome.verdict != "accepted" {
+        return false;
+    }
+    if ctx.contest_type == CONTEST_TYPE_ATTACK_DEFENSE {
+        return outcome.score_awarded > 0;
+    }
+    match outcome.stage_key.as_ref() {
+        Some(stage_key) => !prior.team_stage_keys.contains(stage_key),
+        None => !prior.team_solved,
+    }
 }
 
+/// Applies the contest-wide per-user and per-team budgets, the challenge's
+/// own budgets when it has any, and the wrong-answer cooldown. Counters use
+/// fixed Redis windows, so `retry_after_seconds` is the remaining window.
 async fn enforce_submission_rate_limit(
     state: &AppState,
-    user_id: Uuid,
+    ctx: &JudgeContextRow,
     contest_id: Uuid,
-) -> AppResult<()> {
+    challenge_id: Uuid,
+    team_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<Option<RateLimitHit>> {
     let mut redis_conn = state.redis.clone();
-    let key = format!("ratelimit:submit:{}:{}", contest_id, user_id);
 
-    let count: i64 = redis_conn.incr(&key, 1).await.map_err(AppError::internal)?;
-    if count == 1 {
-        let _: bool = redis_conn
-            .expire(&key, SUBMISSION_RATE_WINDOW_SECS)
-            .await
-            .map_err(AppError::internal)?;
+    let cooldown_key = wrong_backoff_key(contest_id, challenge_id, team_id);
+    let cooldown: i64 = redis_conn
+        .ttl(&cooldown_key)
+        .await
+        .map_err(AppError::internal)?;
+    if cooldown > 0 {
+        return Ok(Some(RateLimitHit {
+            message: format!(
+                "too many wrong flags for this challenge, retry in {} seconds",
+                cooldown
+            ),
+            retry_after_seconds: cooldown,
+        }));
+    }
+
+    let contest_window = ctx.contest_submission_rate_window_seconds as i64;
+    let mut budgets = vec![
+        (
+            format!("ratelimit:submit:{}:user:{}", contest_id, user_id),
+            ctx.contest_submission_rate_user_max as i64,
+            contest_window,
+            "user",
+        ),
+        (
+            format!("ratelimit:submit:{}:team:{}", contest_id, team_id),
+            ctx.contest_submission_rate_team_max as i64,
+            contest_window,
+            "team",
+        ),
+    ];
+
+    let challenge_limit = parse_challenge_rate_limit(&ctx.rate_limit);
+    if challenge_limit.has_budget() {
+        let window = challenge_limit
+            .window_seconds
+            .map(|value| value as i64)
+            .unwrap_or(contest_window);
+        if let Some(max) = challenge_limit.user_max {
+            budgets.push((
+                format!(
+                    "ratelimit:submit:{}:{}:user:{}",
+                    contest_id, challenge_id, user_id
+                ),
+                max as i64,
+                window,
+                "user",
+            ));
+        }
+        if let Some(max) = challenge_limit.team_max {
+            budgets.push((
+                format!(
+                    "ratelimit:submit:{}:{}:team:{}",
+                    contest_id, challenge_id, team_id
+                ),
+                max as i64,
+                window,
+                "team",
+            ));
+        }
+    }
+
+    for (key, max, window, subject) in budgets {
+        let count: i64 = redis_conn.incr(&key, 1).await.map_err(AppError::internal)?;
+        if count == 1 {
+            let _: bool = redis_conn
+                .expire(&key, window)
+                .await
+                .map_err(AppError::internal)?;
+        }
+
+        if count > max {
+            let ttl: i64 = redis_conn.ttl(&key).await.map_err(AppError::internal)?;
+            return Ok(Some(RateLimitHit {
+                message: format!(
+                    "submission rate limit exceeded: max {} submissions per {} seconds per {}",
+                    max, window, subject
+                ),
+                retry_after_seconds: if ttl > 0 { ttl } else { window },
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Tracks consecutive wrong flags of a team on a challenge and starts the
+/// cooldown once the streak reaches the backoff threshold. A correct flag
+/// resets the streak.
+async fn record_wrong_streak(
+    state: &AppState,
+    ctx: &JudgeContextRow,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Uuid,
+    verdict: &str,
+) -> AppResult<()> {
+    let threshold = parse_challenge_rate_limit(&ctx.rate_limit)
+        .wrong_backoff_threshold
+        .unwrap_or(ctx.contest_wrong_backoff_threshold);
+    if threshold <= 0 {
+        return Ok(());
     }
 
-    if count > SUBMISSION_RATE_MAX_COUNT {
-        return Err(AppError::TooManyRequests(format!(
-            "submission rate limit exceeded: max {} submissions per {} seconds",
-            SUBMISSION_RATE_MAX_COUNT, SUBMISSION_RATE_WINDOW_SECS
-        )));
+    let mut redis_conn = state.redis.clone();
+    let streak_key = format!(
+        "ratelimit:wrongstreak:{}:{}:{}",
+        contest_id, challenge_id, team_id
+    );
+
+    match verdict {
+        "wrong" => {
+            let streak: i64 = redis_conn
+                .incr(&streak_key, 1)
+                .await
+                .map_err(AppError::internal)?;
+            let _: bool = redis_conn
+                .expire(&streak_key, WRONG_STREAK_TTL_SECS)
+                .await
+                .map_err(AppError::internal)?;
+
+            if let Some(seconds) = wrong_backoff_seconds(
+                streak,
+                threshold,
+                ctx.contest_wrong_backoff_base_seconds,
+                ctx.contest_wrong_backoff_max_seconds,
+            ) {
+                redis_conn
+                    .set_ex::<_, _, ()>(
+                        wrong_backoff_key(contest_id, challenge_id, team_id),
+                        streak,
+                        seconds as u64,
+                    )
+                    .await
+                    .map_err(AppError::internal)?;
+            }
+        }
+        "accepted" => {
+            redis_conn
+                .del::<_, ()>(&streak_key)
+                .await
+                .map_err(AppError::internal)?;
+        }
+        _ => {}
     }
 
     Ok(())
 }
 
+fn wrong_backoff_key(contest_id: Uuid, challenge_id: Uuid, team_id: Uuid) -> String {
+    format!(
+        "ratelimit:backoff:{}:{}:{}",
+        contest_id, challenge_id, team_id
+    )
+}
+
 struct SubmissionInsertParams<'a> {
     contest_id: Uuid,
     challenge_id: Uuid,
@@ -327,14 +673,17 @@ struct SubmissionInsertParams<'a> {
     submitted_flag: &'a str,
     verdict: &'a str,
     score_awarded: i32,
+    blood_bonus: i32,
+    penalty_points: i32,
+    stage_key: Option<&'a str>,
     message: &'a str,
 }
 
 async fn insert_submission(
     state: &AppState,
     params: SubmissionInsertParams<'_>,
-) -> AppResult<DateTime<Utc>> {
-    let submitted_at = sqlx::query_as::<_, SubmittedAtRow>(
+) -> AppResult<InsertedSubmissionRow> {
+    sqlx::query_as::<_, InsertedSubmissionRow>(
         "INSERT INTO submissions (
             contest_id,
             challenge_id,
@@ -343,11 +692,14 @@ async fn insert_submission(
             submitted_flag,
             verdict,
             score_awarded,
+            blood_bonus,
+            penalty_points,
+            stage_key,
             judger_message,
             judged_at
          )
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
-         RETURNING submitted_at",
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW())
+         RETURNING id, submitted_at",
     )
     .bind(params.contest_id)
     .bind(params.challenge_id)
@@ -356,38 +708,376 @@ async fn insert_submission(
     .bind(params.submitted_flag)
     .bind(params.verdict)
     .bind(params.score_awarded)
+    .bind(params.blood_bonus)
+    .bind(params.penalty_points)
+    .bind(params.stage_key)
     .bind(params.message)
     .fetch_one(&state.db)
     .await
-    .map_err(AppError::internal)?
-    .submitted_at;
+    .map_err(AppError::internal)
+}
 
-    Ok(submitted_at)
+/// Records that `submitter_team_id` submitted the dynamic flag issued to
+/// another team. Repeated submissions of the same pair are folded into one
+/// incident.
+async fn record_flag_sharing_incident(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    submitter_team_id: Uuid,
+    submitter_user_id: Uuid,
+    owner: &DynamicFlagOwnerRow,
+    submission: &InsertedSubmissionRow,
+) -> AppResult<()> {
+    sqlx::query(
+        "INSERT INTO flag_sharing_incidents (
+            contest_id,
+            challenge_id,
+            submitter_team_id,
+            submitter_user_id,
+            owner_team_id,
+            first_submission_id,
+            flag_issued_at,
+            first_submitted_at,
+            last_submitted_at
+         )
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8)
+         ON CONFLICT (contest_id, challenge_id, submitter_team_id, owner_team_id)
+         DO UPDATE SET last_submitted_at = EXCLUDED.last_submitted_at,
+                       occurrence_count = flag_sharing_incidents.occurrence_count + 1",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(submitter_team_id)
+    .bind(submitter_user_id)
+    .bind(owner.team_id)
+    .bind(submission.id)
+    .bind(owner.issued_at)
+    .bind(submission.submitted_at)
+    .execute(&state.db)
+    .await
+    .map_err(AppError::internal)?;
+
+    warn!(
+        contest_id = %contest_id,
+        challenge_id = %challenge_id,
+        submitter_team_id = %submitter_team_id,
+        submitter_user_id = %submitter_user_id,
+        owner_team_id = %owner.team_id,
+        submission_id = submission.id,
+        "dynamic flag of another team submitted"
+    );
+
+    Ok(())
 }
 
-async fn fetch_total_score(state: &AppState, contest_id: Uuid, team_id: Uuid) -> AppResult<i64> {
-    sqlx::query_scalar::<_, i64>(
-        "SELECT COALESCE(SUM(score_awarded), 0)
-         FROM submissions
-         WHERE contest_id = $1 AND team_id = $2",
+/// Judges the stored flags of a contest (or one of its challenges) again
+/// against the current challenge configuration, replaying each challenge's
+/// submissions in order so first-solve, stage and blood bonus decisions are
+/// rebuilt from the new verdicts. Rate-limited submissions were never judged
+/// and are left alone. Script and webhook verifiers are always told the
+/// replay is a dry run, and it runs outside any transaction; when `apply` is
+/// set, only the computed changes are written afterwards under a short lock on
+/// the contest.
+pub(crate) async fn rejudge_submissions(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Option<Uuid>,
+    apply: bool,
+) -> AppResult<RejudgeReport> {
+    let challenges = sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT c.id, c.title
+         FROM contest_challenges cc
+         JOIN challenges c ON c.id = cc.challenge_id
+         WHERE cc.contest_id = $1
+           AND ($2::uuid IS NULL OR cc.challenge_id = $2)
+         ORDER BY cc.sort_order ASC, c.created_at ASC",
     )
     .bind(contest_id)
-    .bind(team_id)
-    .fetch_one(&state.db)
+    .bind(challenge_id)
+    .fetch_all(&state.db)
     .await
-    .map_err(AppError::internal)
+    .map_err(AppError::internal)?;
+
+    if challenge_id.is_some() && challenges.is_empty() {
+        return Err(AppError::BadRequest(
+            "challenge is not available in this contest".to_string(),
+        ));
+    }
+
+    let mut scanned = 0_i64;
+    let mut scanned_challenge_ids = Vec::new();
+    let mut items = Vec::new();
+    let mut updates = Vec::new();
+    for (current_challenge_id, challenge_title) in challenges {
+        let Some(ctx) = load_judge_context(state, contest_id, current_challenge_id).await? else {
+            continue;
+        };
+        if ctx.contest_type == CONTEST_TYPE_ATTACK_DEFENSE {
+            return Err(AppError::BadRequest(
+                "attack-defense submissions cannot be re-judged".to_string(),
+            ));
+        }
+        scanned_challenge_ids.push(current_challenge_id);
+
+        let rows = sqlx::query_as::<_, RejudgeSubmissionRow>(
+            "SELECT s.id,
+                    s.team_id,
+                    t.name AS team_name,
+                    u.username,
+                    s.submitted_flag,
+                    s.verdict,
+                    s.score_awarded,
+                    s.blood_bonus,
+                    s.penalty_points,
+                    s.stage_key,
+                    s.submitted_at
+             FROM submissions s
+             JOIN teams t ON t.id = s.team_id
+             JOIN users u ON u.id = s.user_id
+             WHERE s.contest_id = $1
+               AND s.challenge_id = $2
+               AND s.verdict <> 'rate_limited'
+             ORDER BY s.submitted_at ASC, s.id ASC",
+        )
+        .bind(contest_id)
+        .bind(current_challenge_id)
+        .fetch_all(&state.db)
+        .await
+        .map_err(AppError::internal)?;
+
+        let mut replayed = ReplayedSolves::default();
+        for row in rows {
+            scanned += 1;
+            let prior = replayed.prior(row.team_id);
+            let outcome = judge_flag(
+                state,
+                &ctx,
+                FlagAttempt {
+                    contest_id,
+                    challenge_id: current_challenge_id,
+                    team_id: row.team_id,
+                    flag: &row.submitted_flag,
+                    dry_run: true,
+                },
+                &prior,
+            )
+            .await?;
+            replayed.record(row.team_id, &outcome);
+
+            let unchanged = outcome.verdict == row.verdict
+                && outcome.score_awarded == row.score_awarded
+                && outcome.blood_bonus == row.blood_bonus
+                && outcome.stage_key == row.stage_key;
+            if unchanged {
+                continue;
+            }
+
+            // A submission that turns wrong is charged like a fresh wrong flag.
+            let penalty_points = if outcome.verdict == "wrong" {
+                wrong_submission_penalty(&ctx)
+            } else {
+                0
+            };
+            items.push(RejudgeChangeItem {
+                submission_id: row.id,
+                challenge_id: current_challenge_id,
+                challenge_title: challenge_title.clone(),
+                team_id: row.team_id,
+                team_name: row.team_name,
+                username: row.username,
+                submitted_at: row.submitted_at,
+                old_verdict: row.verdict,
+                new_verdict: outcome.verdict.clone(),
+                old_score_awarded: row.score_awarded,
+                new_score_awarded: outcome.score_awarded,
+                old_penalty_points: row.penalty_points,
+                new_penalty_points: penalty_points,
+                old_stage_key: row.stage_key,
+                new_stage_key: outcome.stage_key.clone(),
+                message: outcome.message.clone(),
+            });
+            updates.push((row.id, outcome, penalty_points));
+        }
+    }
+
+    if apply && !updates.is_empty() {
+        write_rejudge_updates(state, contest_id, &scanned_challenge_ids, scanned, &updates).await?;
+        publish_scoreboard_update(state, contest_id, SCOREBOARD_EVENT_UPDATE).await;
+    }
+
+    info!(
+        contest_id = %contest_id,
+        challenge_id = ?challenge_id,
+        applied = apply,
+        scanned,
+        changed = items.len(),
+        "submissions re-judged"
+    );
+
+    Ok(RejudgeReport {
+        contest_id,
+        challenge_id,
+        applied: apply,
+        scanned,
+        changed: items.len() as i64,
+        items,
+    })
 }
 
-async fn publish_scoreboard_update(state: &AppState, contest_id: Uuid) {
-    let channel = format!("scoreboard:contest:{}", contest_id);
-    let payload = Utc::now().to_rfc3339();
-    let mut redis_conn = state.redis.clone();
+/// Writes re-judged verdicts. New submissions take a key-share lock on their
+/// contest row through the foreign key, so locking the contest keeps them out
+/// while the replayed set is checked and the updates are written; a submission
+/// that arrived during the replay fails the check instead of being judged
+/// against a stale order.
+async fn write_rejudge_updates(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_ids: &[Uuid],
+    scanned: i64,
+    updates: &[(i64, JudgeOutcome, i32)],
+) -> AppResult<()> {
+    let mut tx = state.db.begin().await.map_err(AppError::internal)?;
+    sqlx::query("SELECT id FROM contests WHERE id = $1 FOR UPDATE")
+        .bind(contest_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::internal)?;
+
+    let current = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*)
+         FROM submissions
+         WHERE contest_id = $1
+           AND challenge_id = ANY($2)
+           AND verdict <> 'rate_limited'",
+    )
+    .bind(contest_id)
+    .bind(challenge_ids)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(AppError::internal)?;
+    if current != scanned {
+        return Err(AppError::Conflict(
+            "new submissions arrived while re-judging, please retry".to_string(),
+        ));
+    }
 
-    let publish_result: Result<usize, redis::RedisError> =
-        redis_conn.publish(channel, payload).await;
-    if let Err(err) = publish_result {
-        warn!(contest_id = %contest_id, error = %err, "failed to publish scoreboard update event");
+    for (submission_id, outcome, penalty_points) in updates {
+        sqlx::query(
+            "UPDATE submissions
+             SET verdict = $2,
+                 score_awarded = $3,
+                 blood_bonus = $4,
+                 stage_key = $5,
+                 judger_message = $6,
+                 penalty_points = $7,
+                 judged_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(submission_id)
+        .bind(&outcome.verdict)
+        .bind(outcome.score_awarded)
+        .bind(outcome.blood_bonus)
+        .bind(outcome.stage_key.as_deref())
+        .bind(&outcome.message)
+        .bind(penalty_points)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::internal)?;
     }
+
+    tx.commit().await.map_err(AppError::internal)
+}
+
+/// Penalty for a wrong flag: the challenge's override or the contest's
+/// penalty. It is recorded in full; the contest's score floor is applied when
+/// totals are computed, so later decay and deductions are capped as well.
+fn wrong_submission_penalty(ctx: &JudgeContextRow) -> i32 {
+    ctx.wrong_submission_penalty
+        .unwrap_or(ctx.contest_wrong_submission_penalty)
+        .max(0)
+}
+
+async fn load_judge_context(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+) -> AppResult<Option<JudgeContextRow>> {
+    sqlx::query_as::<_, JudgeContextRow>(
+        "SELECT ct.status AS contest_status,
+                ct.start_at AS contest_start_at,
+                ct.end_at AS contest_end_at,
+                ct.contest_type,
+                ct.scoring_mode AS contest_scoring_mode,
+                ct.scoring_formula AS contest_scoring_formula,
+                ct.dynamic_decay AS contest_dynamic_decay,
+                ct.difficulty_scores AS contest_difficulty_scores,
+                ct.first_blood_bonus_percent AS contest_first_blood_bonus_percent,
+                ct.second_blood_bonus_percent AS contest_second_blood_bonus_percent,
+                ct.third_blood_bonus_percent AS contest_third_blood_bonus_percent,
+                ct.submission_rate_window_seconds AS contest_submission_rate_window_seconds,
+                ct.submission_rate_user_max AS contest_submission_rate_user_max,
+                ct.submission_rate_team_max AS contest_submission_rate_team_max,
+                ct.wrong_backoff_threshold AS contest_wrong_backoff_threshold,
+                ct.wrong_backoff_base_seconds AS contest_wrong_backoff_base_seconds,
+                ct.wrong_backoff_max_seconds AS contest_wrong_backoff_max_seconds,
+                ct.wrong_submission_penalty AS contest_wrong_submission_penalty,
+                ct.score_floor AS contest_score_floor,
+                c.title AS challenge_title,
+                c.challenge_type,
+                c.flag_mode,
+                c.flag_hash,
+                c.flag_rules,
+                c.stages,
+                c.static_score,
+                c.min_score,
+                c.max_score,
+                c.difficulty,
+                c.is_visible,
+                cc.release_at,
+                cc.rate_limit,
+                cc.wrong_submission_penalty,
+                c.metadata
+         FROM contest_challenges cc
+         JOIN contests ct ON ct.id = cc.contest_id
+         JOIN challenges c ON c.id = cc.challenge_id
+         WHERE cc.contest_id = $1 AND cc.challenge_id = $2
+         LIMIT 1",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(AppError::internal)
+}
+
+/// Loads what had been solved on the challenge before the submission being
+/// judged.
+async fn load_prior_solves(
+    state: &AppState,
+    contest_id: Uuid,
+    challenge_id: Uuid,
+    team_id: Uuid,
+) -> AppResult<PriorSolves> {
+    sqlx::query_as::<_, PriorSolves>(
+        "SELECT COALESCE(BOOL_OR(team_id = $3), FALSE) AS team_solved,
+                COALESCE(
+                    ARRAY_AGG(DISTINCT stage_key)
+                        FILTER (WHERE team_id = $3 AND stage_key IS NOT NULL),
+                    '{}'::text[]
+                ) AS team_stage_keys,
+                COUNT(DISTINCT team_id) AS solver_count
+         FROM submissions
+         WHERE contest_id = $1
+           AND challenge_id = $2
+           AND verdict = 'accepted'",
+    )
+    .bind(contest_id)
+    .bind(challenge_id)
+    .bind(team_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(AppError::internal)
 }
 
 fn validate_submission_window(ctx: &JudgeContextRow) -> AppResult<()> {
@@ -409,6 +1099,12 @@ fn validate_submission_window(ctx: &JudgeContextRow) -> AppResult<()> {
         return Err(AppError::BadRequest("contest is not running".to_string()));
     }
 
+    if ctx.challenge_type == CHALLENGE_TYPE_KOTH {
+        return Err(AppError::BadRequest(
+            "king-of-the-hill challenges are scored by holding the hill".to_string(),
+        ));
+    }
+
     if now < ctx.contest_start_at || now > ctx.contest_end_at {
         warn!(
             now = %now,
@@ -424,115 +1120,225 @@ fn validate_submission_window(ctx: &JudgeContextRow) -> AppResult<()> {
 async fn judge_flag(
     state: &AppState,
     ctx: &JudgeContextRow,
-    contest_id: Uuid,
-    challenge_id: Uuid,
-    team_id: Uuid,
-    submitted_flag: &str,
-    already_solved: bool,
-) -> AppResult<(String, i32, String)> {
+    attempt: FlagAttempt<'_>,
+    prior: &PriorSolves,
+) -> AppResult<JudgeOutcome> {
+    let FlagAttempt {
+        contest_id,
+        challenge_id,
+        team_id,
+        flag: submitted_flag,
+        ..
+    } = attempt;
+
+    if ctx.contest_type == CONTEST_TYPE_ATTACK_DEFENSE {
+        return judge_attack_flag(state, contest_id, challenge_id, team_id, submitted_flag).await;
+    }
+
+    let stages = parse_challenge_stages(&ctx.stages);
+    if ctx.flag_mode == "static" && !stages.is_empty() {
+        return Ok(judge_stage_flag(
+            submitted_flag,
+            &stages,
+            &prior.team_stage_keys,
+        ));
+    }
+
+    let mut shared_flag_owner = None;
     let decision = match ctx.flag_mode.as_str() {
         "static" => {
-            if verify_static_flag(submitted_flag, &ctx.flag_hash)? {
+            if verify_static_flag(submitted_flag, &ctx.flag_hash, &ctx.flag_rules)? {
                 JudgeDecision::Correct("correct flag".to_string())
             } else {
                 JudgeDecision::Wrong("incorrect flag".to_string())
             }
         }
         "dynamic" => {
-            let mut redis_conn = state.redis.clone();
-            let key = format!("flag:dynamic:{}:{}:{}", contest_id, challenge_id, team_id);
-            let expected: Option<String> =
-                redis_conn.get(&key).await.map_err(AppError::internal)?;
+            let expected = load_dynamic_flag(state, contest_id, challenge_id, team_id).await?;
 
             match expected {
                 Some(flag) => {
                     if submitted_flag == flag {
                         JudgeDecision::Correct("correct dynamic flag".to_string())
                     } else {
+                        shared_flag_owner = find_dynamic_flag_owner(
+                            state,
+                            contest_id,
+                            challenge_id,
+                            team_id,
+                            submitted_flag,
+                        )
+                        .await?;
                         JudgeDecision::Wrong("incorrect flag".to_string())
                     }
                 }
                 None => JudgeDecision::Invalid("dynamic flag is not provisioned yet".to_string()),
             }
         }
-        "script" => {
-            run_script_verifier(ctx, contest_id, challenge_id, team_id, submitted_flag).await
-        }
+        "script" => run_script_verifier(ctx, attempt).await,
+        "webhook" => run_webhook_flag_verifier(ctx, attempt).await,
         other => JudgeDecision::Invalid(format!("unsupported flag mode '{}'", other)),
     };
 
     match decision {
         JudgeDecision::Correct(message) => {
-            if already_solved {
-                return Ok((
-                    "accepted".to_string(),
-                    0,
+            if prior.team_solved {
+                return Ok(JudgeOutcome::new(
+                    "accepted",
                     "correct flag, but this challenge is already solved by your team".to_string(),
                 ));
             }
 
-            let score_awarded =
-                calculate_awarded_score(state, ctx, contest_id, challenge_id).await?;
-            Ok(("accepted".to_string(), score_awarded, message))
+            let (base_score, blood_bonus) = calculate_awarded_score(ctx, prior.solver_count);
+            Ok(JudgeOutcome {
+                verdict: "accepted".to_string(),
+                score_awarded: base_score.saturating_add(blood_bonus),
+                blood_bonus,
+                message,
+                shared_flag_owner: None,
+                stage_key: None,
+            })
         }
-        JudgeDecision::Wrong(message) => Ok(("wrong".to_string(), 0, message)),
-        JudgeDecision::Invalid(message) => Ok(("invalid".to_string(), 0, message)),
+        JudgeDecision::Wrong(message) => Ok(JudgeOutcome {
+            shared_flag_owner,
+            ..JudgeOutcome::new("wrong", message)
+        }),
+        JudgeDecision::Invalid(message) => Ok(JudgeOutcome::new("invalid", message)),
+    }
+}
+
+/// Judges a multi-stage challenge: the flag is matched against every stage and
+/// only the stage it belongs to is credited, with that stage's points.
+fn judge_stage_flag(
+    submitted_flag: &str,
+    stages: &[ChallengeStage],
+    solved_stage_keys: &[String],
+) -> JudgeOutcome {
+    let stage = match match_challenge_stage(stages, submitted_flag) {
+        Some(stage) => stage,
+        None => return JudgeOutcome::new("wrong", "incorrect flag".to_string()),
+    };
+
+    if solved_stage_keys.contains(&stage.key) {
+        return JudgeOutcome {
+            stage_key: Some(stage.key.clone()),
+            ..JudgeOutcome::new(
+                "accepted",
+                format!(
+                    "correct flag, but stage '{}' is already solved by your team",
+                    stage.title
+                ),
+            )
+        };
+    }
+
+    let solved_count = stages
+        .iter()
+        .filter(|item| item.key == stage.key || solved_stage_keys.contains(&item.key))
+        .count();
+
+    JudgeOutcome {
+        verdict: "accepted".to_string(),
+        score_awarded: stage.points,
+        blood_bonus: 0,
+        message: format!(
+            "stage '{}' solved ({}/{})",
+            stage.title,
+            solved_count,
+            stages.len()
+        ),
+        shared_flag_owner: None,
+        stage_key: Some(stage.key.clone()),
     }
 }
 
-async fn calculate_awarded_score(
+/// In attack-defense contests a flag is another team's service flag of a
+/// live round; each one scores once per attacking team.
+async fn judge_attack_flag(
     state: &AppState,
-    ctx: &JudgeContextRow,
     contest_id: Uuid,
     challenge_id: Uuid,
-) -> AppResult<i32> {
-    let solved_count = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(DISTINCT team_id)
-         FROM submissions
-         WHERE contest_id = $1
-           AND challenge_id = $2
-           AND verdict = 'accepted'
-           AND score_awarded > 0",
-    )
-    .bind(contest_id)
-    .bind(challenge_id)
-    .fetch_one(&state.db)
-    .await
-    .map_err(AppError::internal)?;
-
-    let base_score = if ctx.contest_scoring_mode != "dynamic" {
-        ctx.static_score.max(0)
-    } else {
-        let min_score = ctx.min_score.max(0);
-        let max_score = ctx.max_score.max(min_score);
-        if max_score == min_score {
-            max_score
-        } else {
-            let decay = ctx.contest_dynamic_decay.max(1) as f64;
-            let solves = solved_count.max(0) as f64;
-            let raw = min_score as f64 + (max_score - min_score) as f64 * (decay / (decay + solves));
-            let score = raw.round() as i32;
-            score.clamp(min_score, max_score)
+    team_id: Uuid,
+    submitted_flag: &str,
+) -> AppResult<JudgeOutcome> {
+    let capture =
+        capture_attack_flag(state, contest_id, challenge_id, team_id, submitted_flag).await?;
+
+    let outcome = match capture {
+        AttackCapture::Captured {
+            victim_team_name,
+            points,
+        } => JudgeOutcome {
+            score_awarded: points,
+            ..JudgeOutcome::new(
+                "accepted",
+                format!("flag of team '{}' captured", victim_team_name),
+            )
+        },
+        AttackCapture::Duplicate => JudgeOutcome::new(
+            "accepted",
+            "correct flag, but your team already submitted it".to_string(),
+        ),
+        AttackCapture::Unknown => JudgeOutcome::new("wrong", "incorrect flag".to_string()),
+        AttackCapture::OwnFlag => JudgeOutcome::new(
+            "wrong",
+            "flag belongs to your own team's service".to_string(),
+        ),
+        AttackCapture::OtherService => {
+            JudgeOutcome::new("wrong", "flag belongs to a different service".to_string())
         }
+        AttackCapture::Expired => JudgeOutcome::new("wrong", "flag has expired".to_string()),
     };
+    Ok(outcome)
+}
 
-    let bonus_percent = match solved_count {
-        0 => ctx.contest_first_blood_bonus_percent,
-        1 => ctx.contest_second_blood_bonus_percent,
-        2 => ctx.contest_third_blood_bonus_percent,
-        _ => 0,
-    }
-    .clamp(0, 500);
+/// Scores the solve at the moment it happens, after `solved_count` other
+/// teams. `score_awarded` is kept as a record of that moment; totals
+/// re-derive the challenge value from the current solve count and only keep
+/// the blood bonus as stored.
+fn calculate_awarded_score(ctx: &JudgeContextRow, solved_count: i64) -> (i32, i32) {
+    let config = ContestScoringConfig {
+        contest_type: ctx.contest_type.clone(),
+        scoring_mode: ctx.contest_scoring_mode.clone(),
+        scoring_formula: ctx.contest_scoring_formula.clone(),
+        dynamic_decay: ctx.contest_dynamic_decay,
+        difficulty_scores: serde_json::from_value(ctx.contest_difficulty_scores.clone())
+            .unwrap_or_default(),
+        first_blood_bonus_percent: ctx.contest_first_blood_bonus_percent,
+        second_blood_bonus_percent: ctx.contest_second_blood_bonus_percent,
+        third_blood_bonus_percent: ctx.contest_third_blood_bonus_percent,
+        score_floor: ctx.contest_score_floor,
+    };
+    let bounds = ChallengeScoreBounds {
+        static_score: ctx.static_score,
+        min_score: ctx.min_score,
+        max_score: ctx.max_score,
+        difficulty: &ctx.difficulty,
+    };
+
+    let base_score = challenge_value(&config, bounds, solved_count + 1);
+    let bonus = blood_bonus(&config, base_score, solved_count);
+    (
+        base_score.clamp(0, i32::MAX as i64) as i32,
+        bonus.clamp(0, i32::MAX as i64) as i32,
+    )
+}
 
-    if bonus_percent == 0 || base_score <= 0 {
-        return Ok(base_score);
+/// A static flag is accepted when it matches `flag_hash` or any of the
+/// challenge's extra flag rules.
+fn verify_static_flag(
+    submitted_flag: &str,
+    stored_flag: &str,
+    flag_rules: &Value,
+) -> AppResult<bool> {
+    if matches_any_flag_rule(&parse_flag_rules(flag_rules), submitted_flag) {
+        return Ok(true);
     }
 
-    let bonus = ((base_score as f64) * (bonus_percent as f64 / 100.0)).round() as i32;
-    Ok(base_score.saturating_add(bonus.max(0)))
-}
+    if stored_flag.is_empty() {
+        return Ok(false);
+    }
 
-fn verify_static_flag(submitted_flag: &str, stored_flag: &str) -> AppResult<bool> {
     if stored_flag.starts_with("$argon2") {
         let parsed_hash = PasswordHash::new(stored_flag).map_err(|_| {
             AppError::BadRequest("challenge static flag hash is malformed".to_string())
@@ -547,6 +1353,30 @@ fn verify_static_flag(submitted_flag: &str, stored_flag: &str) -> AppResult<bool
     Ok(submitted_flag == stored_flag)
 }
 
+#[derive(Debug)]
+struct JudgeOutcome {
+    verdict: String,
+    score_awarded: i32,
+    blood_bonus: i32,
+    message: String,
+    /// Team whose dynamic flag was submitted, when it was not the submitter's.
+    shared_flag_owner: Option<DynamicFlagOwnerRow>,
+    stage_key: Option<String>,
+}
+
+impl JudgeOutcome {
+    fn new(verdict: &str, message: String) -> Self {
+        Self {
+            verdict: verdict.to_string(),
+            score_awarded: 0,
+            blood_bonus: 0,
+            message,
+            shared_flag_owner: None,
+            stage_key: None,
+        }
+    }
+}
+
 #[derive(Debug)]
 enum JudgeDecision {
     Correct(String),
@@ -561,13 +1391,7 @@ struct ScriptVerifierConfig {
     timeout_seconds: u64,
 }
 
-async fn run_script_verifier(
-    ctx: &JudgeContextRow,
-    contest_id: Uuid,
-    challenge_id: Uuid,
-    team_id: Uuid,
-    submitted_flag: &str,
-) -> JudgeDecision {
+async fn run_script_verifier(ctx: &JudgeContextRow, attempt: FlagAttempt<'_>) -> JudgeDecision {
     let config = match parse_script_verifier_config(&ctx.metadata) {
         Ok(config) => config,
         Err(message) => return JudgeDecision::Invalid(message),
@@ -576,10 +1400,11 @@ async fn run_script_verifier(
     let mut command = Command::new(&config.program);
     command
         .args(&config.args)
-        .env("SUBMITTED_FLAG", submitted_flag)
-        .env("CONTEST_ID", contest_id.to_string())
-        .env("CHALLENGE_ID", challenge_id.to_string())
-        .env("TEAM_ID", team_id.to_string())
+        .env("SUBMITTED_FLAG", attempt.flag)
+        .env("CONTEST_ID", attempt.contest_id.to_string())
+        .env("CHALLENGE_ID", attempt.challenge_id.to_string())
+        .env("TEAM_ID", attempt.team_id.to_string())
+        .env("DRY_RUN", if attempt.dry_run { "1" } else { "0" })
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
@@ -624,6 +1449,46 @@ async fn run_script_verifier(
     }
 }
 
+async fn run_webhook_flag_verifier(
+    ctx: &JudgeContextRow,
+    attempt: FlagAttempt<'_>,
+) -> JudgeDecision {
+    let config = match parse_webhook_verifier_config(&ctx.metadata) {
+        Ok(config) => config,
+        Err(message) => return JudgeDecision::Invalid(message),
+    };
+
+    let result = run_webhook_verifier(
+        &config,
+        &WebhookVerifyRequest {
+            contest_id: attempt.contest_id,
+            challenge_id: attempt.challenge_id,
+            team_id: attempt.team_id,
+            flag: attempt.flag,
+            dry_run: attempt.dry_run,
+        },
+    )
+    .await;
+
+    if result.verdict == WebhookVerdict::Invalid {
+        warn!(
+            contest_id = %attempt.contest_id,
+            challenge_id = %attempt.challenge_id,
+            team_id = %attempt.team_id,
+            http_status = ?result.http_status,
+            elapsed_ms = result.elapsed_ms,
+            message = %result.message,
+            "webhook flag verifier did not return a verdict"
+        );
+    }
+
+    match result.verdict {
+        WebhookVerdict::Correct => JudgeDecision::Correct(result.message),
+        WebhookVerdict::Wrong => JudgeDecision::Wrong(result.message),
+        WebhookVerdict::Invalid => JudgeDecision::Invalid(result.message),
+    }
+}
+
 fn parse_script_verifier_config(metadata: &Value) -> Result<ScriptVerifierConfig, String> {
     let script_value = metadata.get("script_verifier").ok_or_else(|| {
         "challenge metadata.script_verifier is required for script mode".to_string()
@@ -15,6 +15,7 @@ use uuid::Uuid;
 use crate::{
     auth::AuthenticatedUser,
     error::{AppError, AppResult},
+    routes::contest_access::{get_user_team_id_optional, is_contest_team_conflict},
     state::AppState,
 };
 
@@ -39,12 +40,18 @@ struct TeamsQuery {
     limit: Option<i64>,
 }
 
+#[derive(Debug, Deserialize)]
+struct MyTeamQuery {
+    contest_id: Option<Uuid>,
+}
+
 #[derive(Debug, Serialize)]
 struct MyTeamResponse {
     team: Option<TeamProfile>,
+    teams: Vec<TeamProfile>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct TeamProfile {
     id: Uuid,
     name: String,
@@ -56,7 +63,7 @@ struct TeamProfile {
     members: Vec<TeamMemberItem>,
 }
 
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Clone, Serialize, FromRow)]
 struct TeamMemberItem {
     user_id: Uuid,
     username: String,
@@ -111,6 +118,11 @@ struct JoinTeamRequest {
     team_name: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct LeaveTeamRequest {
+    team_id: Option<Uuid>,
+}
+
 #[derive(Debug, Deserialize)]
 struct TransferCaptainRequest {
     new_captain_user_id: Uuid,
@@ -118,6 +130,7 @@ struct TransferCaptainRequest {
 
 #[derive(Debug, Deserialize)]
 struct CreateTeamInvitationRequest {
+    team_id: Option<Uuid>,
     invitee_user_id: Option<Uuid>,
     invitee_username: Option<String>,
     message: Option<String>,
@@ -125,6 +138,7 @@ struct CreateTeamInvitationRequest {
 
 #[derive(Debug, Deserialize)]
 struct TeamInvitationsQuery {
+    team_id: Option<Uuid>,
     status: Option<String>,
     limit: Option<i64>,
 }
@@ -134,11 +148,6 @@ struct RespondInvitationRequest {
     action: String,
 }
 
-#[derive(Debug, FromRow)]
-struct TeamIdRow {
-    team_id: Uuid,
-}
-
 #[derive(Debug, FromRow)]
 struct TeamMembershipRow {
     team_id: Uuid,
@@ -228,7 +237,8 @@ async fn list_teams(
          FROM teams t
          LEFT JOIN users u ON u.id = t.captain_user_id
          LEFT JOIN team_members tm ON tm.team_id = t.id
-         WHERE ($1::text IS NULL OR LOWER(t.name) LIKE '%' || LOWER($1) || '%')
+         WHERE t.solo_user_id IS NULL
+           AND ($1::text IS NULL OR LOWER(t.name) LIKE '%' || LOWER($1) || '%')
          GROUP BY t.id, u.username
          ORDER BY t.created_at DESC
          LIMIT $2",
@@ -245,14 +255,24 @@ async fn list_teams(
 async fn get_my_team(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
+    Query(query): Query<MyTeamQuery>,
 ) -> AppResult<Json<MyTeamResponse>> {
-    let my_team_id = fetch_user_team_id(state.as_ref(), current_user.user_id).await?;
-    let team = match my_team_id {
-        Some(team_id) => Some(load_team_profile(state.as_ref(), team_id).await?),
-        None => None,
+    let memberships = fetch_user_team_memberships(state.as_ref(), current_user.user_id).await?;
+    let mut teams = Vec::with_capacity(memberships.len());
+    for membership in &memberships {
+        teams.push(load_team_profile(state.as_ref(), membership.team_id).await?);
+    }
+
+    // `team` is the team used for the given contest, or the oldest team.
+    let my_team_id = match query.contest_id {
+        Some(contest_id) => {
+            get_user_team_id_optional(state.as_ref(), contest_id, current_user.user_id).await?
+        }
+        None => memberships.first().map(|item| item.team_id),
     };
+    let team = my_team_id.and_then(|team_id| teams.iter().find(|item| item.id == team_id).cloned());
 
-    Ok(Json(MyTeamResponse { team }))
+    Ok(Json(MyTeamResponse { team, teams }))
 }
 
 async fn get_team_by_id(
@@ -284,19 +304,6 @@ async fn create_team(
         ));
     }
 
-    if fetch_user_team_id(state.as_ref(), current_user.user_id)
-        .await?
-        .is_some()
-    {
-        warn!(
-            user_id = %current_user.user_id,
-            "team creation denied: user already in a team"
-        );
-        return Err(AppError::Conflict(
-            "you are already in a team; leave current team before creating a new one".to_string(),
-        ));
-    }
-
     let mut tx = state.db.begin().await.map_err(AppError::internal)?;
 
     let team_id = sqlx::query_scalar::<_, Uuid>(
@@ -325,13 +332,7 @@ async fn create_team(
     .bind(current_user.user_id)
     .execute(&mut *tx)
     .await
-    .map_err(|err| {
-        if is_unique_violation(&err) {
-            AppError::Conflict("you are already in a team".to_string())
-        } else {
-            AppError::internal(err)
-        }
-    })?;
+    .map_err(AppError::internal)?;
 
     tx.commit().await.map_err(AppError::internal)?;
 
@@ -351,20 +352,8 @@ async fn join_team(
     current_user: AuthenticatedUser,
     Json(req): Json<JoinTeamRequest>,
 ) -> AppResult<Json<TeamProfile>> {
-    if fetch_user_team_id(state.as_ref(), current_user.user_id)
-        .await?
-        .is_some()
-    {
-        warn!(
-            user_id = %current_user.user_id,
-            "team join denied: user already in a team"
-        );
-        return Err(AppError::Conflict(
-            "you are already in a team; leave current team before joining another one".to_string(),
-        ));
-    }
-
     let target_team_id = resolve_target_team_id(state.as_ref(), &req).await?;
+    ensure_no_contest_team_conflict(state.as_ref(), target_team_id, current_user.user_id).await?;
 
     sqlx::query(
         "INSERT INTO team_members (team_id, user_id, member_role)
@@ -376,7 +365,9 @@ async fn join_team(
     .await
     .map_err(|err| {
         if is_unique_violation(&err) {
-            AppError::Conflict("you are already in a team".to_string())
+            AppError::Conflict("you are already in this team".to_string())
+        } else if is_contest_team_conflict(&err) {
+            contest_team_conflict_error()
         } else if is_foreign_key_violation(&err) {
             AppError::BadRequest("target team does not exist".to_string())
         } else {
@@ -397,10 +388,23 @@ async fn join_team(
 async fn leave_team(
     State(state): State<Arc<AppState>>,
     current_user: AuthenticatedUser,
+    req: Option<Json<LeaveTeamRequest>>,
 ) -> AppResult<Json<LeaveTeamResponse>> {
-    let membership = fetch_user_team_membership(state.as_ref(), current_user.user_id)
-        .await?
-        .ok_or(AppError::BadRequest("you are not in any team".to_string()))?;
+    let requested_team_id = req.and_then(|Json(req)| req.team_id);
+    let memberships = fetch_user_team_memberships(state.as_ref(), current_user.user_id).await?;
+    let membership = match (requested_team_id, memberships.as_slice()) {
+        (_, []) => return Err(AppError::BadRequest("you are not in any team".to_string())),
+        (Some(team_id), _) => memberships
+            .into_iter()
+            .find(|item| item.team_id == team_id)
+            .ok_or(AppError::BadRequest("you are not in this team".to_string()))?,
+        (None, [_]) => memberships.into_iter().next().ok_or(AppError::Forbidden)?,
+        (None, _) => {
+            return Err(AppError::BadRequest(
+                "team_id is required when you belong to several teams".to_string(),
+            ))
+        }
+    };
 
     if membership.member_role == "captain" {
         let member_count = sqlx::query_as::<_, MemberCountRow>(
@@ -684,24 +688,17 @@ async fn create_invitation(
     current_user: AuthenticatedUser,
     Json(req): Json<CreateTeamInvitationRequest>,
 ) -> AppResult<Json<TeamInvitationItem>> {
-    let membership = fetch_user_team_membership(state.as_ref(), current_user.user_id)
-        .await?
-        .ok_or(AppError::Forbidden)?;
-    if membership.member_role != "captain" {
-        return Err(AppError::Forbidden);
-    }
+    let team_id =
+        resolve_captained_team_id(state.as_ref(), current_user.user_id, req.team_id).await?;
 
     let invitee_user_id = resolve_invitee_user_id(state.as_ref(), &req).await?;
     if invitee_user_id == current_user.user_id {
         return Err(AppError::BadRequest("cannot invite yourself".to_string()));
     }
 
-    if fetch_user_team_id(state.as_ref(), invitee_user_id)
-        .await?
-        .is_some()
-    {
+    if is_team_member(state.as_ref(), team_id, invitee_user_id).await? {
         return Err(AppError::Conflict(
-            "invitee is already in a team".to_string(),
+            "invitee is already in this team".to_string(),
         ));
     }
 
@@ -717,7 +714,7 @@ async fn create_invitation(
          VALUES ($1, $2, $3, $4)
          RETURNING id",
     )
-    .bind(membership.team_id)
+    .bind(team_id)
     .bind(current_user.user_id)
     .bind(invitee_user_id)
     .bind(&message)
@@ -797,10 +794,14 @@ async fn list_sent_invitations(
     current_user: AuthenticatedUser,
     Query(query): Query<TeamInvitationsQuery>,
 ) -> AppResult<Json<Vec<TeamInvitationItem>>> {
-    let membership = fetch_user_team_membership(state.as_ref(), current_user.user_id)
+    let captained_team_ids = fetch_user_team_memberships(state.as_ref(), current_user.user_id)
         .await?
-        .ok_or(AppError::Forbidden)?;
-    if membership.member_role != "captain" {
+        .into_iter()
+        .filter(|item| item.member_role == "captain")
+        .map(|item| item.team_id)
+        .filter(|team_id| query.team_id.is_none_or(|requested| requested == *team_id))
+        .collect::<Vec<_>>();
+    if captained_team_ids.is_empty() {
         return Err(AppError::Forbidden);
     }
 
@@ -828,12 +829,12 @@ async fn list_sent_invitations(
          JOIN teams t ON t.id = ti.team_id
          LEFT JOIN users inviter ON inviter.id = ti.inviter_user_id
          LEFT JOIN users invitee ON invitee.id = ti.invitee_user_id
-         WHERE ti.team_id = $1
+         WHERE ti.team_id = ANY($1::uuid[])
            AND ($2::text IS NULL OR ti.status = $2)
          ORDER BY ti.created_at DESC
          LIMIT $3",
     )
-    .bind(membership.team_id)
+    .bind(&captained_team_ids)
     .bind(status_filter)
     .bind(limit)
     .fetch_all(&state.db)
@@ -882,12 +883,24 @@ async fn respond_invitation(
         ));
     }
 
-    if next_status == "accepted"
-        && fetch_user_team_id(state.as_ref(), current_user.user_id)
-            .await?
-            .is_some()
-    {
-        return Err(AppError::Conflict("you are already in a team".to_string()));
+    if next_status == "accepted" {
+        if is_team_member(
+            state.as_ref(),
+            invitation_guard.team_id,
+            current_user.user_id,
+        )
+        .await?
+        {
+            return Err(AppError::Conflict(
+                "you are already in this team".to_string(),
+            ));
+        }
+        ensure_no_contest_team_conflict(
+            state.as_ref(),
+            invitation_guard.team_id,
+            current_user.user_id,
+        )
+        .await?;
     }
 
     let mut tx = state.db.begin().await.map_err(AppError::internal)?;
@@ -923,7 +936,9 @@ async fn respond_invitation(
         .await
         .map_err(|err| {
             if is_unique_violation(&err) {
-                AppError::Conflict("you are already in a team".to_string())
+                AppError::Conflict("you are already in this team".to_string())
+            } else if is_contest_team_conflict(&err) {
+                contest_team_conflict_error()
             } else {
                 AppError::internal(err)
             }
@@ -1004,12 +1019,13 @@ async fn cancel_invitation(
 
 async fn resolve_target_team_id(state: &AppState, req: &JoinTeamRequest) -> AppResult<Uuid> {
     if let Some(team_id) = req.team_id {
-        let exists =
-            sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM teams WHERE id = $1)")
-                .bind(team_id)
-                .fetch_one(&state.db)
-                .await
-                .map_err(AppError::internal)?;
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM teams WHERE id = $1 AND solo_user_id IS NULL)",
+        )
+        .bind(team_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(AppError::internal)?;
 
         if exists {
             return Ok(team_id);
@@ -1032,6 +1048,7 @@ async fn resolve_target_team_id(state: &AppState, req: &JoinTeamRequest) -> AppR
         "SELECT id
          FROM teams
          WHERE LOWER(name) = LOWER($1)
+           AND solo_user_id IS NULL
          LIMIT 1",
     )
     .bind(team_name)
@@ -1149,37 +1166,108 @@ async fn ensure_captain_of_team(state: &AppState, user_id: Uuid, team_id: Uuid)
     Ok(())
 }
 
-async fn fetch_user_team_membership(
+async fn fetch_user_team_memberships(
     state: &AppState,
     user_id: Uuid,
-) -> AppResult<Option<TeamMembershipRow>> {
-    let row = sqlx::query_as::<_, TeamMembershipRow>(
+) -> AppResult<Vec<TeamMembershipRow>> {
+    sqlx::query_as::<_, TeamMembershipRow>(
         "SELECT team_id, member_role
          FROM team_members
          WHERE user_id = $1
-         LIMIT 1",
+         ORDER BY joined_at ASC, team_id ASC",
     )
     .bind(user_id)
-    .fetch_optional(&state.db)
+    .fetch_all(&state.db)
     .await
-    .map_err(AppError::internal)?;
+    .map_err(AppError::internal)
+}
 
-    Ok(row)
+async fn is_team_member(state: &AppState, team_id: Uuid, user_id: Uuid) -> AppResult<bool> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(
+             SELECT 1
+             FROM team_members
+             WHERE team_id = $1
+               AND user_id = $2
+         )",
+    )
+    .bind(team_id)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(AppError::internal)
 }
 
-async fn fetch_user_team_id(state: &AppState, user_id: Uuid) -> AppResult<Option<Uuid>> {
-    let row = sqlx::query_as::<_, TeamIdRow>(
-        "SELECT team_id
-         FROM team_members
-         WHERE user_id = $1
-         LIMIT 1",
+/// The team a captain acts for. `team_id` may be omitted when the user
+/// captains exactly one team.
+async fn resolve_captained_team_id(
+    state: &AppState,
+    user_id: Uuid,
+    team_id: Option<Uuid>,
+) -> AppResult<Uuid> {
+    if let Some(team_id) = team_id {
+        ensure_captain_of_team(state, user_id, team_id).await?;
+        return Ok(team_id);
+    }
+
+    let captained = fetch_user_team_memberships(state, user_id)
+        .await?
+        .into_iter()
+        .filter(|item| item.member_role == "captain")
+        .collect::<Vec<_>>();
+    match captained.as_slice() {
+        [] => Err(AppError::Forbidden),
+        [membership] => Ok(membership.team_id),
+        _ => Err(AppError::BadRequest(
+            "team_id is required when you captain several teams".to_string(),
+        )),
+    }
+}
+
+/// Rejects joining `team_id` when it is registered for a contest in which
+/// the user already plays with another team. The database enforces the same
+/// rule when the membership is written; this check fails early and logs it.
+async fn ensure_no_contest_team_conflict(
+    state: &AppState,
+    team_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<()> {
+    let conflict = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(
+             SELECT 1
+             FROM contest_registrations target
+             JOIN contest_registrations mine
+               ON mine.contest_id = target.contest_id
+              AND mine.team_id <> target.team_id
+             JOIN team_members tm ON tm.team_id = mine.team_id
+             WHERE target.team_id = $1
+               AND tm.user_id = $2
+               AND target.status <> 'rejected'
+               AND mine.status <> 'rejected'
+         )",
     )
+    .bind(team_id)
     .bind(user_id)
-    .fetch_optional(&state.db)
+    .fetch_one(&state.db)
     .await
     .map_err(AppError::internal)?;
 
-    Ok(row.map(|item| item.team_id))
+    if conflict {
+        warn!(
+            team_id = %team_id,
+            user_id = %user_id,
+            "team join denied: user already registered for a shared contest"
+        );
+        return Err(contest_team_conflict_error());
+    }
+
+    Ok(())
+}
+
+fn contest_team_conflict_error() -> AppError {
+    AppError::Conflict(
+        "you are already registered for a contest of this team with another team".to_string(),
+    )
 }
 
 async fn load_team_profile(state: &AppState, team_id: Uuid) -> AppResult<TeamProfile> {
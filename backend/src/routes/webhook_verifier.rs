@@ -0,0 +1,311 @@
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use uuid::Uuid;
+
+const DEFAULT_TIMEOUT_SECONDS: u64 = 5;
+const MIN_SECRET_CHARS: usize = 16;
+const MAX_MESSAGE_CHARS: usize = 240;
+
+pub(crate) const SIGNATURE_HEADER: &str = "X-CTF-Signature";
+pub(crate) const TIMESTAMP_HEADER: &str = "X-CTF-Timestamp";
+
+/// `metadata.webhook_verifier` of a challenge in `webhook` flag mode; the
+/// attack-defense checker in `metadata.ad_checker` has the same shape.
+#[derive(Debug, Clone)]
+pub(crate) struct WebhookVerifierConfig {
+    pub url: String,
+    pub secret: String,
+    pub timeout_seconds: u64,
+}
+
+/// Body POSTed to the verifier for every submission.
+#[derive(Debug, Serialize)]
+pub(crate) struct WebhookVerifyRequest<'a> {
+    pub contest_id: Uuid,
+    pub challenge_id: Uuid,
+    pub team_id: Uuid,
+    pub flag: &'a str,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookVerifyResponse {
+    verdict: String,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum WebhookVerdict {
+    Correct,
+    Wrong,
+    Invalid,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct WebhookVerifierResult {
+    pub verdict: WebhookVerdict,
+    pub message: String,
+    pub http_status: Option<u16>,
+    pub elapsed_ms: u64,
+}
+
+pub(crate) fn parse_webhook_verifier_config(
+    metadata: &Value,
+) -> Result<WebhookVerifierConfig, String> {
+    parse_signed_endpoint_config(metadata, "webhook_verifier")
+}
+
+/// Reads a `{url, secret, timeout_seconds}` object from `metadata.<key>`.
+pub(crate) fn parse_signed_endpoint_config(
+    metadata: &Value,
+    key: &str,
+) -> Result<WebhookVerifierConfig, String> {
+    let obj = metadata
+        .get(key)
+        .and_then(Value::as_object)
+        .ok_or_else(|| format!("challenge metadata.{} is required", key))?;
+
+    let url = obj
+        .get("url")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("metadata.{}.url is required", key))?;
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(format!("metadata.{}.url must be an http(s) URL", key));
+    }
+
+    let secret = obj
+        .get("secret")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .unwrap_or_default();
+    if secret.chars().count() < MIN_SECRET_CHARS {
+        return Err(format!(
+            "metadata.{}.secret must be at least {} characters",
+            key, MIN_SECRET_CHARS
+        ));
+    }
+
+    let timeout_seconds = obj
+        .get("timeout_seconds")
+        .and_then(Value::as_u64)
+        .unwrap_or(DEFAULT_TIMEOUT_SECONDS)
+        .clamp(1, 30);
+
+    Ok(WebhookVerifierConfig {
+        url: url.to_string(),
+        secret: secret.to_string(),
+        timeout_seconds,
+    })
+}
+
+/// Hex HMAC-SHA256 over `"{timestamp}.{body}"`, sent as `sha256=<hex>`.
+pub(crate) fn sign_webhook_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("sha256={}", hex)
+}
+
+/// Sends the submission to the verifier. Transport errors, timeouts, non-2xx
+/// answers and malformed bodies are all reported as `invalid` so a broken
+/// verifier never marks a flag wrong.
+pub(crate) async fn run_webhook_verifier(
+    config: &WebhookVerifierConfig,
+    request: &WebhookVerifyRequest<'_>,
+) -> WebhookVerifierResult {
+    let started = Instant::now();
+    let (verdict, message, http_status) =
+        match post_signed_json::<_, WebhookVerifyResponse>(config, request, "webhook verifier")
+            .await
+        {
+            Ok((body, status)) => {
+                let (verdict, message) = interpret_webhook_response(body);
+                (verdict, message, Some(status))
+            }
+            Err(err) => (WebhookVerdict::Invalid, err.message, err.http_status),
+        };
+
+    WebhookVerifierResult {
+        verdict,
+        message: truncate_message(&message),
+        http_status,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Failure of a signed POST; `http_status` is set when the endpoint answered.
+#[derive(Debug)]
+pub(crate) struct SignedPostError {
+    pub message: String,
+    pub http_status: Option<u16>,
+}
+
+/// POSTs `payload` as signed JSON and decodes a 2xx JSON answer. `label`
+/// names the endpoint in error messages.
+pub(crate) async fn post_signed_json<T: Serialize, R: DeserializeOwned>(
+    config: &WebhookVerifierConfig,
+    payload: &T,
+    label: &str,
+) -> Result<(R, u16), SignedPostError> {
+    let error = |message: String, http_status| SignedPostError {
+        message,
+        http_status,
+    };
+
+    let body = serde_json::to_vec(payload)
+        .map_err(|err| error(format!("failed to encode {} payload: {}", label, err), None))?;
+    let timestamp = Utc::now().timestamp();
+    let signature = sign_webhook_payload(&config.secret, timestamp, &body);
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.timeout_seconds))
+        .build()
+        .map_err(|err| error(format!("failed to build {} client: {}", label, err), None))?;
+
+    let response = client
+        .post(&config.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, signature)
+        .body(body)
+        .send()
+        .await
+        .map_err(|err| {
+            if err.is_timeout() {
+                error(
+                    format!(
+                        "{} timed out after {} seconds",
+                        label, config.timeout_seconds
+                    ),
+                    None,
+                )
+            } else {
+                error(format!("{} request failed: {}", label, err), None)
+            }
+        })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(error(
+            format!("{} responded with status {}", label, status.as_u16()),
+            Some(status.as_u16()),
+        ));
+    }
+
+    let decoded = response.json::<R>().await.map_err(|err| {
+        error(
+            format!("{} returned malformed JSON: {}", label, err),
+            Some(status.as_u16()),
+        )
+    })?;
+    Ok((decoded, status.as_u16()))
+}
+
+fn interpret_webhook_response(body: WebhookVerifyResponse) -> (WebhookVerdict, String) {
+    let message = body
+        .message
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    match body.verdict.trim().to_lowercase().as_str() {
+        "correct" => (
+            WebhookVerdict::Correct,
+            message.unwrap_or_else(|| "correct flag (verified by webhook)".to_string()),
+        ),
+        "wrong" => (
+            WebhookVerdict::Wrong,
+            message.unwrap_or_else(|| "incorrect flag".to_string()),
+        ),
+        "invalid" => (
+            WebhookVerdict::Invalid,
+            message.unwrap_or_else(|| "webhook verifier rejected the submission".to_string()),
+        ),
+        other => (
+            WebhookVerdict::Invalid,
+            format!("webhook verifier returned unknown verdict '{}'", other),
+        ),
+    }
+}
+
+pub(crate) fn truncate_message(message: &str) -> String {
+    let message = message.replace(['\n', '\r'], " ");
+    if message.chars().count() > MAX_MESSAGE_CHARS {
+        message.chars().take(MAX_MESSAGE_CHARS).collect::<String>() + "..."
+    } else {
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        interpret_webhook_response, parse_webhook_verifier_config, sign_webhook_payload,
+        WebhookVerdict, WebhookVerifyResponse,
+    };
+
+    #[test]
+    fn signature_is_hmac_sha256_over_timestamp_and_body() {
+        assert_eq!(
+            sign_webhook_payload("key", 1_700_000_000, b"{}"),
+            "sha256=9d713ed406bb7076d4123f0dc2c39d2df5c654ed4b0cd56b52c8b4c940bd63ae"
+        );
+        assert_ne!(
+            sign_webhook_payload("key", 1_700_000_001, b"{}"),
+            sign_webhook_payload("key", 1_700_000_000, b"{}")
+        );
+    }
+
+    #[test]
+    fn config_requires_url_and_strong_secret() {
+        let config = parse_webhook_verifier_config(&json!({
+            "webhook_verifier": {
+                "url": " https://checker.example/verify ",
+                "secret": "0123456789abcdef",
+                "timeout_seconds": 120
+            }
+        }))
+        .unwrap();
+        assert_eq!(config.url, "https://checker.example/verify");
+        assert_eq!(config.timeout_seconds, 30);
+
+        assert!(parse_webhook_verifier_config(&json!({})).is_err());
+        assert!(parse_webhook_verifier_config(&json!({
+            "webhook_verifier": {"url": "ftp://x", "secret": "0123456789abcdef"}
+        }))
+        .is_err());
+        assert!(parse_webhook_verifier_config(&json!({
+            "webhook_verifier": {"url": "https://x", "secret": "short"}
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn unknown_verdicts_are_invalid() {
+        let parse = |verdict: &str| {
+            interpret_webhook_response(WebhookVerifyResponse {
+                verdict: verdict.to_string(),
+                message: None,
+            })
+            .0
+        };
+        assert_eq!(parse("Correct"), WebhookVerdict::Correct);
+        assert_eq!(parse("wrong"), WebhookVerdict::Wrong);
+        assert_eq!(parse("yes"), WebhookVerdict::Invalid);
+    }
+}
@@ -9,6 +9,7 @@ use argon2::{
 };
 use redis::aio::ConnectionManager;
 use sqlx::{postgres::PgPoolOptions, FromRow, PgPool};
+use std::net::IpAddr;
 use tracing::{info, warn};
 
 pub struct AppState {
@@ -16,6 +17,7 @@ pub struct AppState {
     pub db: PgPool,
     pub redis_client: redis::Client,
     pub redis: ConnectionManager,
+    pub trusted_proxies: Vec<IpAddr>,
 }
 
 impl AppState {
@@ -23,6 +25,7 @@ impl AppState {
         if config.jwt_secret.len() < 32 {
             warn!("JWT_SECRET is shorter than 32 characters; use a stronger secret");
         }
+        let trusted_proxies = config.trusted_proxy_ips()?;
 
         let db = PgPoolOptions::new()
             .max_connections(20)
@@ -48,6 +51,7 @@ impl AppState {
             db,
             redis_client,
             redis,
+            trusted_proxies,
         })
     }
 }
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Extra submission limits of one contest challenge, stored in
+/// `contest_challenges.rate_limit`. They apply on top of the contest-wide
+/// budget; unset fields fall back to the contest settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengeRateLimit {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_seconds: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_max: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_max: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrong_backoff_threshold: Option<i32>,
+}
+
+impl ChallengeRateLimit {
+    /// Whether the challenge has its own submission counters.
+    pub fn has_budget(&self) -> bool {
+        self.user_max.is_some() || self.team_max.is_some()
+    }
+}
+
+pub fn normalize_challenge_rate_limit(
+    limit: ChallengeRateLimit,
+) -> Result<ChallengeRateLimit, String> {
+    check_optional_range(limit.window_seconds, 5, 3600, "rate_limit.window_seconds")?;
+    check_optional_range(limit.user_max, 1, 1000, "rate_limit.user_max")?;
+    check_optional_range(limit.team_max, 1, 5000, "rate_limit.team_max")?;
+    check_optional_range(
+        limit.wrong_backoff_threshold,
+        0,
+        100,
+        "rate_limit.wrong_backoff_threshold",
+    )?;
+    Ok(limit)
+}
+
+pub fn parse_challenge_rate_limit(value: &Value) -> ChallengeRateLimit {
+    serde_json::from_value(value.clone()).unwrap_or_default()
+}
+
+pub fn challenge_rate_limit_to_value(limit: &ChallengeRateLimit) -> Value {
+    serde_json::to_value(limit).unwrap_or_else(|_| Value::Object(Default::default()))
+}
+
+/// Cooldown after `wrong_streak` consecutive wrong flags: none below
+/// `threshold`, then `base_seconds` doubling with every further wrong flag,
+/// capped at `max_seconds`. A `threshold` of 0 disables backoff.
+pub fn wrong_backoff_seconds(
+    wrong_streak: i64,
+    threshold: i32,
+    base_seconds: i32,
+    max_seconds: i32,
+) -> Option<i64> {
+    if threshold <= 0 || wrong_streak < threshold as i64 {
+        return None;
+    }
+
+    let exponent = (wrong_streak - threshold as i64).min(32) as u32;
+    let seconds = (base_seconds.max(1) as i64).saturating_mul(1_i64 << exponent);
+    Some(seconds.min(max_seconds.max(1) as i64))
+}
+
+fn check_optional_range(value: Option<i32>, min: i32, max: i32, field: &str) -> Result<(), String> {
+    match value {
+        Some(value) if !(min..=max).contains(&value) => {
+            Err(format!("{} must be between {} and {}", field, min, max))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        normalize_challenge_rate_limit, parse_challenge_rate_limit, wrong_backoff_seconds,
+        ChallengeRateLimit,
+    };
+
+    #[test]
+    fn backoff_starts_at_threshold_and_doubles_up_to_cap() {
+        assert_eq!(wrong_backoff_seconds(5, 0, 5, 300), None);
+        assert_eq!(wrong_backoff_seconds(2, 3, 5, 300), None);
+        assert_eq!(wrong_backoff_seconds(3, 3, 5, 300), Some(5));
+        assert_eq!(wrong_backoff_seconds(4, 3, 5, 300), Some(10));
+        assert_eq!(wrong_backoff_seconds(6, 3, 5, 300), Some(40));
+        assert_eq!(wrong_backoff_seconds(100, 3, 5, 300), Some(300));
+    }
+
+    #[test]
+    fn challenge_rate_limit_round_trips_and_validates() {
+        let limit = parse_challenge_rate_limit(&json!({"user_max": 3, "window_seconds": 60}));
+        assert_eq!(limit.user_max, Some(3));
+        assert!(limit.has_budget());
+        assert!(!parse_challenge_rate_limit(&json!({})).has_budget());
+
+        assert!(normalize_challenge_rate_limit(ChallengeRateLimit {
+            window_seconds: Some(1),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(normalize_challenge_rate_limit(ChallengeRateLimit {
+            team_max: Some(0),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
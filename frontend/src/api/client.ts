@@ -1266,7 +1266,8 @@ export async function getScoreboardRankings(
 export function buildScoreboardWsUrl(contestId: string, accessToken: string): string {
   const url = new URL(API_BASE_URL);
   const protocol = url.protocol === "https:" ? "wss:" : "ws:";
-  return `${protocol}//${url.host}/api/v1/contests/${contestId}/scoreboard/ws?access_token=${encodeURIComponent(accessToken)}`;
+  // The views redraw from the full standings, so every push carries them.
+  return `${protocol}//${url.host}/api/v1/contests/${contestId}/scoreboard/ws?access_token=${encodeURIComponent(accessToken)}&entries=true`;
 }
 
 export async function startInstance(